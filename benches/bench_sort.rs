@@ -0,0 +1,157 @@
+#![feature(test)]
+
+extern crate test;
+
+#[cfg(test)]
+mod tests {
+    use tesap_std::Vector;
+    use test::Bencher;
+
+    const SORT_COUNT: usize = 10000;
+
+    fn random(len: usize) -> Vec<u64> {
+        let mut x: u64 = 0x2545_f491_4f6c_dd1d;
+        (0..len).map(|_| {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            x
+        }).collect()
+    }
+
+    // Every iteration sorts a fresh copy, so the copy is part of both measurements
+
+    // ===== STABLE =====
+    #[bench]
+    fn bench_sort_stable(b: &mut Bencher) {
+        let input = Vector::from(random(SORT_COUNT));
+        b.iter(|| {
+            let mut v = input.clone();
+            v.sort_stable();
+            test::black_box(v);
+        });
+    }
+
+    #[bench]
+    fn bench_sort_stable_std(b: &mut Bencher) {
+        let input = random(SORT_COUNT);
+        b.iter(|| {
+            let mut v = input.clone();
+            v.sort();
+            test::black_box(v);
+        });
+    }
+
+    #[bench]
+    fn bench_sort_stable_presorted(b: &mut Bencher) {
+        let input: Vector<u64> = (0..SORT_COUNT as u64).collect();
+        b.iter(|| {
+            let mut v = input.clone();
+            v.sort_stable();
+            test::black_box(v);
+        });
+    }
+
+    #[bench]
+    fn bench_sort_stable_presorted_std(b: &mut Bencher) {
+        let input: Vec<u64> = (0..SORT_COUNT as u64).collect();
+        b.iter(|| {
+            let mut v = input.clone();
+            v.sort();
+            test::black_box(v);
+        });
+    }
+
+    // ===== UNSTABLE =====
+    #[bench]
+    fn bench_sort_unstable(b: &mut Bencher) {
+        let input = Vector::from(random(SORT_COUNT));
+        b.iter(|| {
+            let mut v = input.clone();
+            v.sort_unstable();
+            test::black_box(v);
+        });
+    }
+
+    #[bench]
+    fn bench_sort_unstable_std(b: &mut Bencher) {
+        let input = random(SORT_COUNT);
+        b.iter(|| {
+            let mut v = input.clone();
+            v.sort_unstable();
+            test::black_box(v);
+        });
+    }
+
+    #[bench]
+    fn bench_sort_unstable_few_values(b: &mut Bencher) {
+        let input: Vector<u64> = random(SORT_COUNT).into_iter().map(|x| x % 8).collect();
+        b.iter(|| {
+            let mut v = input.clone();
+            v.sort_unstable();
+            test::black_box(v);
+        });
+    }
+
+    #[bench]
+    fn bench_sort_unstable_few_values_std(b: &mut Bencher) {
+        let input: Vec<u64> = random(SORT_COUNT).into_iter().map(|x| x % 8).collect();
+        b.iter(|| {
+            let mut v = input.clone();
+            v.sort_unstable();
+            test::black_box(v);
+        });
+    }
+
+    // ===== CACHED KEY =====
+    #[bench]
+    fn bench_sort_by_cached_key(b: &mut Bencher) {
+        let input = Vector::from(random(SORT_COUNT));
+        b.iter(|| {
+            let mut v = input.clone();
+            v.sort_by_cached_key(|x| x.to_string());
+            test::black_box(v);
+        });
+    }
+
+    #[bench]
+    fn bench_sort_by_cached_key_std(b: &mut Bencher) {
+        let input = random(SORT_COUNT);
+        b.iter(|| {
+            let mut v = input.clone();
+            v.sort_by_cached_key(|x| x.to_string());
+            test::black_box(v);
+        });
+    }
+
+    // ===== RADIX =====
+    #[bench]
+    fn bench_radix_sort(b: &mut Bencher) {
+        let input = Vector::from(random(SORT_COUNT));
+        b.iter(|| {
+            let mut v = input.clone();
+            v.radix_sort();
+            test::black_box(v);
+        });
+    }
+
+    #[bench]
+    fn bench_radix_sort_u32(b: &mut Bencher) {
+        let input: Vector<u32> = random(SORT_COUNT).into_iter().map(|x| x as u32).collect();
+        b.iter(|| {
+            let mut v = input.clone();
+            v.radix_sort();
+            test::black_box(v);
+        });
+    }
+
+    #[bench]
+    fn bench_radix_sort_u32_std(b: &mut Bencher) {
+        let input: Vec<u32> = random(SORT_COUNT).into_iter().map(|x| x as u32).collect();
+        b.iter(|| {
+            let mut v = input.clone();
+            v.sort_unstable();
+            test::black_box(v);
+        });
+    }
+}
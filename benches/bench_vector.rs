@@ -4,14 +4,16 @@ extern crate test;
 
 #[cfg(test)]
 mod tests {
-    use containers::vector::Vector;
+    use tesap_std::{Global, OneAndHalf, PageRounded, Vector};
     use test::Bencher;
 
+    const PUSH_COUNT: usize = 10000;
+
     // ===== NEW =====
     #[bench]
     fn bench_new(b: &mut Bencher) {
         b.iter(|| {
-            let v: Vector<i32> = Vector::new(0, 10000);
+            let v: Vector<i32> = Vector::new_copy(0, 10000);
             test::black_box(v);
         });
     }
@@ -27,7 +29,7 @@ mod tests {
     // ===== APPEND =====
     #[bench]
     fn bench_append(b: &mut Bencher) {
-        let mut v: Vector<i32> = Vector::new(0, 0);
+        let mut v: Vector<i32> = Vector::new_copy(0, 0);
         b.iter(|| {
             v.push(1);
             test::black_box(&v);
@@ -44,10 +46,72 @@ mod tests {
         });
     }
 
+    // ===== PUSH MANY =====
+    // Pushes into an empty container: measures the growth policy, not a single push
+    #[bench]
+    fn bench_push_many(b: &mut Bencher) {
+        b.iter(|| {
+            let mut v: Vector<usize> = Vector::new();
+            for i in 0..PUSH_COUNT {
+                v.push(i);
+            }
+            test::black_box(v);
+        });
+    }
+
+    #[bench]
+    fn bench_push_many_one_and_half(b: &mut Bencher) {
+        b.iter(|| {
+            let mut v: Vector<usize, Global, OneAndHalf> = Vector::new_in(Global);
+            for i in 0..PUSH_COUNT {
+                v.push(i);
+            }
+            test::black_box(v);
+        });
+    }
+
+    #[bench]
+    fn bench_push_many_page_rounded(b: &mut Bencher) {
+        b.iter(|| {
+            let mut v: Vector<usize, Global, PageRounded> = Vector::new_in(Global);
+            for i in 0..PUSH_COUNT {
+                v.push(i);
+            }
+            test::black_box(v);
+        });
+    }
+
+    #[bench]
+    fn bench_push_many_std(b: &mut Bencher) {
+        b.iter(|| {
+            let mut v: Vec<usize> = Vec::new();
+            for i in 0..PUSH_COUNT {
+                v.push(i);
+            }
+            test::black_box(v);
+        });
+    }
+
+    #[bench]
+    fn bench_collect(b: &mut Bencher) {
+        b.iter(|| {
+            let v: Vector<usize> = (0..PUSH_COUNT).collect();
+            test::black_box(v);
+        });
+    }
+
+    #[bench]
+    fn bench_collect_std(b: &mut Bencher) {
+        b.iter(|| {
+            let v: Vec<usize> = (0..PUSH_COUNT).collect();
+            test::black_box(v);
+        });
+    }
+
     // ===== INSERT =====
     #[bench]
     fn bench_insert(b: &mut Bencher) {
-        let mut v: Vector<i32> = Vector::new(0, 10);
+        let mut v: Vector<i32> = Vector::new_copy(0, 10);
         b.iter(|| {
             v.insert(5, 1);
             test::black_box(&v);
@@ -66,7 +130,7 @@ mod tests {
     // ===== POP =====
     #[bench]
     fn bench_pop(b: &mut Bencher) {
-        let mut v: Vector<i32> = Vector::new(0, 10000);
+        let mut v: Vector<i32> = Vector::new_copy(0, 10000);
         b.iter(|| {
             v.pop();
             test::black_box(&v);
@@ -85,7 +149,7 @@ mod tests {
     // ===== INDEX =====
     #[bench]
     fn bench_index(b: &mut Bencher) {
-        let v: Vector<i32> = Vector::new(0, 1000);
+        let v: Vector<i32> = Vector::new_copy(0, 1000);
         b.iter(|| {
             let elem = v[500];
             test::black_box(elem);
@@ -104,7 +168,7 @@ mod tests {
     // ===== INDEX MUT =====
     #[bench]
     fn bench_index_mut(b: &mut Bencher) {
-        let mut v: Vector<i32> = Vector::new(0, 1000);
+        let mut v: Vector<i32> = Vector::new_copy(0, 1000);
         b.iter(|| {
             v[500] = 1;
             test::black_box(&v);
@@ -0,0 +1,118 @@
+use std::alloc;
+use std::ptr;
+use std::fmt;
+
+type Layout = alloc::Layout;
+
+/// Why an allocation request could not be served
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AllocError {
+    /// Requested size overflows `usize` or exceeds `isize::MAX` bytes
+    CapacityOverflow,
+    /// The allocator returned null for this layout
+    OutOfMemory { layout: Layout },
+}
+
+impl fmt::Display for AllocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AllocError::CapacityOverflow => write!(f, "capacity overflow"),
+            AllocError::OutOfMemory { layout } => {
+                write!(f, "memory allocation of {} bytes failed", layout.size())
+            }
+        }
+    }
+}
+
+impl std::error::Error for AllocError {}
+
+/// Infallible APIs end up here: panic on overflow, abort via the std hook on OOM
+pub(crate) fn handle_alloc_error(err: AllocError) -> ! {
+    match err {
+        AllocError::CapacityOverflow => panic!("capacity overflow"),
+        AllocError::OutOfMemory { layout } => alloc::handle_alloc_error(layout),
+    }
+}
+
+/// Memory source for `Chunks` and every container built on top of it.
+///
+/// Modelled after `std::alloc::GlobalAlloc`: failure is signalled by a null pointer,
+/// so arenas, bump or pooled allocators only need to hand out raw blocks.
+///
+/// # Safety
+/// A non-null block returned by `allocate` / `reallocate` must be valid for reads and
+/// writes of `layout` and stay valid until it is passed back to `deallocate` or
+/// `reallocate` of the same allocator.
+///
+/// Clones of an allocator, and instances that compare equal through `PartialEq`,
+/// must be able to free each other's blocks: containers that split or splice their
+/// allocations rely on it.
+pub unsafe trait Allocator {
+    fn allocate(&self, layout: Layout) -> *mut u8;
+
+    /// # Safety
+    /// `ptr` must have been returned by this allocator for exactly `layout`.
+    unsafe fn deallocate(&self, ptr: *mut u8, layout: Layout);
+
+    /// Resizes the block to `new_size` bytes, keeping `layout.align()`.
+    /// The default implementation allocates a new block and copies the common prefix.
+    ///
+    /// # Safety
+    /// Same as for `deallocate`; on success the old `ptr` must no longer be used.
+    unsafe fn reallocate(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_layout = unsafe {
+            Layout::from_size_align_unchecked(new_size, layout.align())
+        };
+        let new_ptr = self.allocate(new_layout);
+        if !new_ptr.is_null() {
+            unsafe {
+                ptr::copy_nonoverlapping(ptr, new_ptr, layout.size().min(new_size));
+                self.deallocate(ptr, layout);
+            }
+        }
+        new_ptr
+    }
+}
+
+/// The process-wide allocator behind `std::alloc::alloc`. Used by default.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Global;
+
+unsafe impl Allocator for Global {
+    fn allocate(&self, layout: Layout) -> *mut u8 {
+        unsafe {
+            alloc::alloc(layout)
+        }
+    }
+
+    unsafe fn deallocate(&self, ptr: *mut u8, layout: Layout) {
+        unsafe {
+            alloc::dealloc(ptr, layout)
+        }
+    }
+
+    unsafe fn reallocate(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        unsafe {
+            alloc::realloc(ptr, layout, new_size)
+        }
+    }
+}
+
+// Allows sharing one arena between several containers: `Vector::new_in(&arena)`
+unsafe impl<A: Allocator + ?Sized> Allocator for &A {
+    fn allocate(&self, layout: Layout) -> *mut u8 {
+        (**self).allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: *mut u8, layout: Layout) {
+        unsafe {
+            (**self).deallocate(ptr, layout)
+        }
+    }
+
+    unsafe fn reallocate(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        unsafe {
+            (**self).reallocate(ptr, layout, new_size)
+        }
+    }
+}
@@ -0,0 +1,423 @@
+use std::cmp::Reverse;
+use std::fmt;
+use std::mem::{self, ManuallyDrop};
+use std::ops::{Deref, DerefMut};
+use std::ptr;
+use std::slice;
+
+use crate::allocator::{Allocator, Global};
+use crate::vector::{self, Vector};
+
+/// Max-heap priority queue stored in a `Vector`: `pop` returns the greatest element.
+///
+/// `data[0]` is the root, the children of `i` are `2i + 1` and `2i + 2`.
+pub struct BinaryHeap<T, A: Allocator = Global> {
+    data: Vector<T, A>,
+}
+
+/// Pops the smallest element first
+pub type MinHeap<T, A = Global> = BinaryHeap<Reverse<T>, A>;
+
+impl<T: Ord> BinaryHeap<T> {
+    // Constructor
+    pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+
+    // Constructor
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_in(capacity, Global)
+    }
+}
+
+impl<T: Ord, A: Allocator> BinaryHeap<T, A> {
+    // Constructor
+    pub fn new_in(alloc: A) -> Self {
+        Self { data: Vector::new_in(alloc) }
+    }
+
+    // Constructor
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        Self { data: Vector::with_capacity_in(capacity, alloc) }
+    }
+
+    /// Heapifies `vec` in O(n)
+    pub fn from_vector(vec: Vector<T, A>) -> Self {
+        let mut heap = Self { data: vec };
+        heap.rebuild();
+        heap
+    }
+
+    pub fn push(&mut self, item: T) {
+        self.data.push(item);
+        self.sift_up(0, self.data.len() - 1);
+    }
+
+    /// Removes the greatest element
+    pub fn pop(&mut self) -> Option<T> {
+        self.data.pop().map(|mut item| {
+            if !self.is_empty() {
+                mem::swap(&mut item, &mut self.data[0]);
+                self.sift_down_to_bottom(0);
+            }
+            item
+        })
+    }
+
+    /// The greatest element
+    pub fn peek(&self) -> Option<&T> {
+        self.data.as_slice().first()
+    }
+
+    /// Mutable access to the greatest element.
+    /// The heap is restored when the returned guard goes out of scope.
+    pub fn peek_mut(&mut self) -> Option<PeekMut<'_, T, A>> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(PeekMut { heap: self, modified: false })
+        }
+    }
+
+    /// Ascending order, O(n log n) in place
+    pub fn into_sorted_vec(mut self) -> Vector<T, A> {
+        let mut end = self.len();
+        while end > 1 {
+            end -= 1;
+            self.data.as_mut_slice().swap(0, end);
+            self.sift_down_range(0, end);
+        }
+        self.into_vector()
+    }
+
+    /// Moves all elements of `other` into `self`, leaving `other` empty
+    pub fn append(&mut self, other: &mut Self) {
+        if self.len() < other.len() {
+            mem::swap(self, other);
+        }
+        let start = self.data.len();
+        self.data.append(&mut other.data);
+        self.rebuild_tail(start);
+    }
+
+    /// Keeps only the elements for which `f` returns true, then restores the heap
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, f: F) {
+        let len = self.data.len();
+        self.data.retain(f);
+        if self.data.len() != len {
+            self.rebuild();
+        }
+    }
+
+    // ======== SIFTING ========
+
+    // Moves the element at `pos` up while it is greater than its parent, not past `start`
+    fn sift_up(&mut self, start: usize, pos: usize) -> usize {
+        let mut hole = Hole::new(self.data.as_mut_slice(), pos);
+        while hole.pos() > start {
+            let parent = (hole.pos() - 1) / 2;
+            if hole.element() <= hole.get(parent) {
+                break;
+            }
+            hole.move_to(parent);
+        }
+        hole.pos()
+    }
+
+    // Moves the element at `pos` down while a child within `..end` is greater
+    fn sift_down_range(&mut self, pos: usize, end: usize) {
+        let mut hole = Hole::new(&mut self.data.as_mut_slice()[..end], pos);
+        let mut child = 2 * hole.pos() + 1;
+
+        while child + 1 < end {
+            // The greater of both children
+            child += (hole.get(child) <= hole.get(child + 1)) as usize;
+            if hole.element() >= hole.get(child) {
+                return;
+            }
+            hole.move_to(child);
+            child = 2 * hole.pos() + 1;
+        }
+
+        if child == end - 1 && hole.element() < hole.get(child) {
+            hole.move_to(child);
+        }
+    }
+
+    // After `pop` the new root is usually small: sink it to a leaf without comparing it,
+    // then sift it back up. Fewer comparisons than `sift_down_range` on average.
+    fn sift_down_to_bottom(&mut self, pos: usize) {
+        let end = self.len();
+        let start = pos;
+
+        let mut hole = Hole::new(self.data.as_mut_slice(), pos);
+        let mut child = 2 * hole.pos() + 1;
+        while child + 1 < end {
+            child += (hole.get(child) <= hole.get(child + 1)) as usize;
+            hole.move_to(child);
+            child = 2 * hole.pos() + 1;
+        }
+        if child == end - 1 {
+            hole.move_to(child);
+        }
+        let pos = hole.pos();
+        drop(hole);
+
+        self.sift_up(start, pos);
+    }
+
+    fn rebuild(&mut self) {
+        let len = self.len();
+        for i in (0..len / 2).rev() {
+            self.sift_down_range(i, len);
+        }
+    }
+
+    // Restores the heap after elements were added from `start` on:
+    // either sifts each new one up, or rebuilds everything, whichever compares less
+    fn rebuild_tail(&mut self, start: usize) {
+        let len = self.len();
+        let tail_len = len - start;
+        if tail_len == 0 {
+            return;
+        }
+
+        let log2 = (usize::BITS - start.leading_zeros()) as usize;
+        if start < tail_len || 2 * len < tail_len * log2 {
+            self.rebuild();
+        } else {
+            for i in start..len {
+                self.sift_up(0, i);
+            }
+        }
+    }
+}
+
+impl<T, A: Allocator> BinaryHeap<T, A> {
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.data.capacity()
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        self.data.reserve(additional);
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        self.data.shrink_to_fit();
+    }
+
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    /// Elements in heap order: the greatest first, the rest in no sorted order
+    pub fn as_slice(&self) -> &[T] {
+        self.data.as_slice()
+    }
+
+    /// Elements in heap order
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.data.as_slice().iter()
+    }
+
+    /// Removes all elements, in heap order
+    pub fn drain(&mut self) -> vector::Drain<'_, T, A> {
+        self.data.drain(..)
+    }
+
+    /// Elements in heap order, without sorting
+    pub fn into_vector(self) -> Vector<T, A> {
+        self.data
+    }
+}
+
+// ======== HOLE ========
+
+// A slot whose element was taken out and is written back on drop,
+// so that a panicking comparison leaves no duplicate behind
+struct Hole<'a, T> {
+    data: &'a mut [T],
+    elem: ManuallyDrop<T>,
+    pos: usize,
+}
+
+impl<'a, T> Hole<'a, T> {
+    fn new(data: &'a mut [T], pos: usize) -> Self {
+        let elem = unsafe {
+            ptr::read(&data[pos])
+        };
+        Hole { data, elem: ManuallyDrop::new(elem), pos }
+    }
+
+    fn pos(&self) -> usize {
+        self.pos
+    }
+
+    fn element(&self) -> &T {
+        &self.elem
+    }
+
+    fn get(&self, index: usize) -> &T {
+        debug_assert!(index != self.pos);
+        &self.data[index]
+    }
+
+    // Moves the element at `index` into the hole; the hole is at `index` afterwards
+    fn move_to(&mut self, index: usize) {
+        debug_assert!(index != self.pos);
+        unsafe {
+            let ptr = self.data.as_mut_ptr();
+            ptr::copy_nonoverlapping(ptr.add(index), ptr.add(self.pos), 1);
+        }
+        self.pos = index;
+    }
+}
+
+impl<T> Drop for Hole<'_, T> {
+    fn drop(&mut self) {
+        unsafe {
+            ptr::copy_nonoverlapping(&*self.elem, &mut self.data[self.pos], 1);
+        }
+    }
+}
+
+// ======== PEEK_MUT ========
+
+/// Mutable access to the greatest element, created by `peek_mut()`.
+/// Sifts the element down on drop if it was accessed mutably.
+pub struct PeekMut<'a, T: Ord, A: Allocator = Global> {
+    heap: &'a mut BinaryHeap<T, A>,
+    modified: bool,
+}
+
+impl<'a, T: Ord, A: Allocator> PeekMut<'a, T, A> {
+    /// Removes the peeked element from the heap
+    pub fn pop(mut this: PeekMut<'a, T, A>) -> T {
+        // Removed anyway, no need to sift it first
+        this.modified = false;
+        this.heap.pop().unwrap()
+    }
+}
+
+impl<T: Ord, A: Allocator> Deref for PeekMut<'_, T, A> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.heap.data.as_slice()[0]
+    }
+}
+
+impl<T: Ord, A: Allocator> DerefMut for PeekMut<'_, T, A> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.modified = true;
+        &mut self.heap.data.as_mut_slice()[0]
+    }
+}
+
+impl<T: Ord, A: Allocator> Drop for PeekMut<'_, T, A> {
+    fn drop(&mut self) {
+        if self.modified {
+            let len = self.heap.len();
+            self.heap.sift_down_range(0, len);
+        }
+    }
+}
+
+impl<T: Ord + fmt::Debug, A: Allocator> fmt::Debug for PeekMut<'_, T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PeekMut").field(&**self).finish()
+    }
+}
+
+// ======== STD TRAITS ========
+
+impl<T: Ord> Default for BinaryHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone, A: Allocator + Clone> Clone for BinaryHeap<T, A> {
+    fn clone(&self) -> Self {
+        let mut data = Vector::with_capacity_in(self.len(), self.data.allocator().clone());
+        for elem in self.iter() {
+            data.push(elem.clone());
+        }
+        Self { data }
+    }
+}
+
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for BinaryHeap<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Ord> FromIterator<T> for BinaryHeap<T> {
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> Self {
+        Self::from_vector(iter.into_iter().collect())
+    }
+}
+
+impl<T: Ord, A: Allocator> Extend<T> for BinaryHeap<T, A> {
+    fn extend<I: IntoIterator<Item=T>>(&mut self, iter: I) {
+        let start = self.data.len();
+        for elem in iter {
+            self.data.push(elem);
+        }
+        self.rebuild_tail(start);
+    }
+}
+
+impl<'a, T: Ord + Copy + 'a, A: Allocator> Extend<&'a T> for BinaryHeap<T, A> {
+    fn extend<I: IntoIterator<Item=&'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+impl<T, A: Allocator> IntoIterator for BinaryHeap<T, A> {
+    type Item = T;
+    type IntoIter = vector::IntoIter<T, A>;
+
+    /// Heap order, not sorted
+    fn into_iter(self) -> vector::IntoIter<T, A> {
+        self.data.into_iter()
+    }
+}
+
+impl<'a, T, A: Allocator> IntoIterator for &'a BinaryHeap<T, A> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> slice::Iter<'a, T> {
+        self.iter()
+    }
+}
+
+// ======== FROM & INTO ========
+
+impl<T: Ord, A: Allocator> From<Vector<T, A>> for BinaryHeap<T, A> {
+    fn from(vec: Vector<T, A>) -> Self {
+        Self::from_vector(vec)
+    }
+}
+
+impl<T, A: Allocator> From<BinaryHeap<T, A>> for Vector<T, A> {
+    fn from(heap: BinaryHeap<T, A>) -> Self {
+        heap.data
+    }
+}
+
+impl<T: Ord, const N: usize> From<[T; N]> for BinaryHeap<T> {
+    fn from(arr: [T; N]) -> Self {
+        Self::from_vector(Vector::from(arr))
+    }
+}
@@ -0,0 +1,1675 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::{FusedIterator, Peekable};
+use std::marker::PhantomData;
+use std::mem::{self, ManuallyDrop, MaybeUninit};
+use std::ops::{Bound, Index, RangeBounds};
+use std::ptr::{self, NonNull};
+use std::slice;
+
+use crate::allocator::{Allocator, Global, handle_alloc_error};
+use crate::chunks::{array_alloc, array_dealloc};
+use crate::vector::Vector;
+
+// Every node but the root holds MIN_LEN..=CAPACITY entries
+const B: usize = 6;
+const CAPACITY: usize = 2 * B - 1;
+const MIN_LEN: usize = B - 1;
+// Nodes on a border being repaired are topped up to this, so that a merge
+// of their own children still leaves them at MIN_LEN
+const PLENTIFUL: usize = MIN_LEN + 1;
+
+/// Ordered map in a B-tree: every node keeps up to 11 sorted entries in
+/// one fixed-capacity allocation, so a lookup touches a handful of short arrays
+/// instead of one allocation per entry.
+///
+/// All leaves are at the same depth. Bulk operations (`append`, `split_off`,
+/// `from_iter`) build or cut the tree directly instead of inserting one by one.
+pub struct BTreeMap<K, V, A: Allocator = Global> {
+    // None until the first insert
+    root: Option<NonNull<LeafNode<K, V>>>,
+    height: usize,
+    length: usize,
+    alloc: A,
+    marker: PhantomData<(K, V)>,
+}
+
+// The map owns its nodes and entries like a `Box` would
+unsafe impl<K: Send, V: Send, A: Allocator + Send> Send for BTreeMap<K, V, A> {}
+unsafe impl<K: Sync, V: Sync, A: Allocator + Sync> Sync for BTreeMap<K, V, A> {}
+
+// The first `len` slots hold the entries in key order. Nodes above the leaves
+// are `InternalNode`s; which kind a pointer is follows from its height.
+struct LeafNode<K, V> {
+    parent: Option<NonNull<InternalNode<K, V>>>,
+    // Slot of this node in `parent.edges`
+    parent_idx: u16,
+    len: u16,
+    keys: [MaybeUninit<K>; CAPACITY],
+    vals: [MaybeUninit<V>; CAPACITY],
+}
+
+// `data` comes first, so a pointer to the node also points to its `LeafNode`
+#[repr(C)]
+struct InternalNode<K, V> {
+    data: LeafNode<K, V>,
+    // The first `len + 1` are children, one level lower
+    edges: [MaybeUninit<NonNull<LeafNode<K, V>>>; CAPACITY + 1],
+}
+
+impl<K, V> LeafNode<K, V> {
+    // Constructor
+    fn new() -> Self {
+        Self {
+            parent: None,
+            parent_idx: 0,
+            len: 0,
+            keys: [const { MaybeUninit::uninit() }; CAPACITY],
+            vals: [const { MaybeUninit::uninit() }; CAPACITY],
+        }
+    }
+}
+
+// A node and its height above the leaves
+struct NodeRef<K, V> {
+    node: NonNull<LeafNode<K, V>>,
+    height: usize,
+}
+
+impl<K, V> Clone for NodeRef<K, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K, V> Copy for NodeRef<K, V> {}
+
+impl<K, V> NodeRef<K, V> {
+    // Constructor
+    fn new_leaf<A: Allocator>(alloc: &A) -> Self {
+        let node = array_alloc::<LeafNode<K, V>, A>(alloc, 1).unwrap_or_else(|err| handle_alloc_error(err));
+        unsafe {
+            node.write(LeafNode::new());
+            Self { node: NonNull::new_unchecked(node), height: 0 }
+        }
+    }
+
+    // Constructor: no edges yet
+    fn new_internal<A: Allocator>(height: usize, alloc: &A) -> Self {
+        let node = array_alloc::<InternalNode<K, V>, A>(alloc, 1).unwrap_or_else(|err| handle_alloc_error(err));
+        unsafe {
+            node.write(InternalNode { data: LeafNode::new(), edges: [const { MaybeUninit::uninit() }; CAPACITY + 1] });
+            Self { node: NonNull::new_unchecked(node).cast(), height }
+        }
+    }
+
+    // Constructor: a node one level up with `child` as its only edge
+    fn new_root<A: Allocator>(child: Self, alloc: &A) -> Self {
+        let node = Self::new_internal(child.height + 1, alloc);
+        unsafe {
+            *node.edge_ptr(0) = child.node;
+            node.correct_parent_links(0, 0);
+        }
+        node
+    }
+
+    // Constructor: a chain of empty nodes down to a leaf, `height` levels below
+    fn new_empty_tree<A: Allocator>(height: usize, alloc: &A) -> Self {
+        if height == 0 {
+            return Self::new_leaf(alloc);
+        }
+        Self::new_root(Self::new_empty_tree(height - 1, alloc), alloc)
+    }
+
+    fn new_like<A: Allocator>(self, alloc: &A) -> Self {
+        if self.is_leaf() {
+            Self::new_leaf(alloc)
+        } else {
+            Self::new_internal(self.height, alloc)
+        }
+    }
+
+    // Frees this node alone. Its entries and children must have been moved out.
+    unsafe fn dealloc<A: Allocator>(self, alloc: &A) {
+        if self.is_leaf() {
+            array_dealloc(alloc, self.node.as_ptr(), 1);
+        } else {
+            array_dealloc(alloc, self.node.cast::<InternalNode<K, V>>().as_ptr(), 1);
+        }
+    }
+
+    // Frees all nodes of the subtree without dropping any entry
+    unsafe fn dealloc_tree<A: Allocator>(self, alloc: &A) {
+        unsafe {
+            if !self.is_leaf() {
+                for i in 0..=self.len() {
+                    self.edge(i).dealloc_tree(alloc);
+                }
+            }
+            self.dealloc(alloc);
+        }
+    }
+
+    // Safety for all below: the node is alive, `i` is in bounds,
+    // and `edge` methods are only used on internal nodes
+
+    unsafe fn len(self) -> usize {
+        unsafe {
+            (*self.node.as_ptr()).len as usize
+        }
+    }
+
+    unsafe fn set_len(self, len: usize) {
+        unsafe {
+            (*self.node.as_ptr()).len = len as u16;
+        }
+    }
+
+    fn is_leaf(self) -> bool {
+        self.height == 0
+    }
+
+    // Slot pointers are made without references to the arrays, so they don't
+    // invalidate references handed out to other entries
+    unsafe fn key(self, i: usize) -> *mut K {
+        unsafe {
+            (&raw mut (*self.node.as_ptr()).keys).cast::<K>().add(i)
+        }
+    }
+
+    unsafe fn val(self, i: usize) -> *mut V {
+        unsafe {
+            (&raw mut (*self.node.as_ptr()).vals).cast::<V>().add(i)
+        }
+    }
+
+    unsafe fn edge_ptr(self, i: usize) -> *mut NonNull<LeafNode<K, V>> {
+        unsafe {
+            (&raw mut (*self.node.cast::<InternalNode<K, V>>().as_ptr()).edges).cast::<NonNull<LeafNode<K, V>>>().add(i)
+        }
+    }
+
+    unsafe fn edge(self, i: usize) -> Self {
+        unsafe {
+            Self { node: *self.edge_ptr(i), height: self.height - 1 }
+        }
+    }
+
+    // The parent and the slot of this node in it, none for the root
+    unsafe fn parent(self) -> Option<(Self, usize)> {
+        unsafe {
+            let parent = (*self.node.as_ptr()).parent?;
+            Some((Self { node: parent.cast(), height: self.height + 1 }, (*self.node.as_ptr()).parent_idx as usize))
+        }
+    }
+
+    // Points the children in slots `from..=to` back at this node
+    unsafe fn correct_parent_links(self, from: usize, to: usize) {
+        unsafe {
+            for i in from..=to {
+                let child = *self.edge_ptr(i);
+                (*child.as_ptr()).parent = Some(self.node.cast());
+                (*child.as_ptr()).parent_idx = i as u16;
+            }
+        }
+    }
+
+    unsafe fn search<Q: Ord + ?Sized>(self, key: &Q) -> Result<usize, usize>
+    where
+        K: Borrow<Q>,
+    {
+        unsafe {
+            let keys = slice::from_raw_parts(self.key(0), self.len());
+            keys.binary_search_by(|k| k.borrow().cmp(key))
+        }
+    }
+
+    // Inserts an entry at `i` and, in internal nodes, `edge` right after it
+    unsafe fn insert_at(self, i: usize, key: K, val: V, edge: Option<Self>) {
+        unsafe {
+            let len = self.len();
+            slice_insert(self.key(0), len, i, key);
+            slice_insert(self.val(0), len, i, val);
+            if let Some(edge) = edge {
+                slice_insert(self.edge_ptr(0), len + 1, i + 1, edge.node);
+                self.correct_parent_links(i + 1, len + 1);
+            }
+            self.set_len(len + 1);
+        }
+    }
+
+    unsafe fn push_back(self, key: K, val: V, edge: Option<Self>) {
+        unsafe {
+            self.insert_at(self.len(), key, val, edge);
+        }
+    }
+
+    // Inserts an entry and, in internal nodes, an edge before all others
+    unsafe fn push_front(self, key: K, val: V, edge: Option<Self>) {
+        unsafe {
+            let len = self.len();
+            slice_insert(self.key(0), len, 0, key);
+            slice_insert(self.val(0), len, 0, val);
+            if let Some(edge) = edge {
+                slice_insert(self.edge_ptr(0), len + 1, 0, edge.node);
+                self.correct_parent_links(0, len + 1);
+            }
+            self.set_len(len + 1);
+        }
+    }
+
+    // Removes the entry at `i` and, in internal nodes, the edge right after it
+    unsafe fn remove_at(self, i: usize) -> (K, V, Option<Self>) {
+        unsafe {
+            let len = self.len();
+            let key = slice_remove(self.key(0), len, i);
+            let val = slice_remove(self.val(0), len, i);
+            let edge = if self.is_leaf() {
+                None
+            } else {
+                let edge = slice_remove(self.edge_ptr(0), len + 1, i + 1);
+                self.correct_parent_links(i + 1, len - 1);
+                Some(Self { node: edge, height: self.height - 1 })
+            };
+            self.set_len(len - 1);
+            (key, val, edge)
+        }
+    }
+
+    unsafe fn pop_back(self) -> (K, V, Option<Self>) {
+        unsafe {
+            self.remove_at(self.len() - 1)
+        }
+    }
+
+    unsafe fn pop_front(self) -> (K, V, Option<Self>) {
+        unsafe {
+            let len = self.len();
+            let key = slice_remove(self.key(0), len, 0);
+            let val = slice_remove(self.val(0), len, 0);
+            let edge = if self.is_leaf() {
+                None
+            } else {
+                let edge = slice_remove(self.edge_ptr(0), len + 1, 0);
+                self.correct_parent_links(0, len - 1);
+                Some(Self { node: edge, height: self.height - 1 })
+            };
+            self.set_len(len - 1);
+            (key, val, edge)
+        }
+    }
+
+    // Moves the upper half of the full child `i` into a new sibling and its median into `self`
+    unsafe fn split_child<A: Allocator>(self, i: usize, alloc: &A) {
+        unsafe {
+            let child = self.edge(i);
+            let right = child.new_like(alloc);
+            let moved = CAPACITY - B;
+            ptr::copy_nonoverlapping(child.key(B), right.key(0), moved);
+            ptr::copy_nonoverlapping(child.val(B), right.val(0), moved);
+            if !child.is_leaf() {
+                ptr::copy_nonoverlapping(child.edge_ptr(B), right.edge_ptr(0), moved + 1);
+                right.correct_parent_links(0, moved);
+            }
+            right.set_len(moved);
+            let key = ptr::read(child.key(B - 1));
+            let val = ptr::read(child.val(B - 1));
+            child.set_len(B - 1);
+            self.insert_at(i, key, val, Some(right));
+        }
+    }
+
+    // Splits full nodes on the way down, so there is always room below.
+    // `self` must not be full.
+    unsafe fn insert<A: Allocator>(self, key: K, value: V, alloc: &A) -> Option<V>
+    where
+        K: Ord,
+    {
+        let mut node = self;
+        unsafe {
+            loop {
+                let mut i = match node.search(&key) {
+                    Ok(i) => return Some(mem::replace(&mut *node.val(i), value)),
+                    Err(i) => i,
+                };
+                if node.is_leaf() {
+                    node.insert_at(i, key, value, None);
+                    return None;
+                }
+                if node.edge(i).len() == CAPACITY {
+                    node.split_child(i, alloc);
+                    match key.cmp(&*node.key(i)) {
+                        Ordering::Less => {}
+                        Ordering::Equal => return Some(mem::replace(&mut *node.val(i), value)),
+                        Ordering::Greater => i += 1,
+                    }
+                }
+                node = node.edge(i);
+            }
+        }
+    }
+
+    // Finds the node and slot holding `key`
+    unsafe fn find<Q: Ord + ?Sized>(self, key: &Q) -> Option<(Self, usize)>
+    where
+        K: Borrow<Q>,
+    {
+        let mut node = self;
+        unsafe {
+            loop {
+                match node.search(key) {
+                    Ok(i) => return Some((node, i)),
+                    Err(_) if node.is_leaf() => return None,
+                    Err(i) => node = node.edge(i),
+                }
+            }
+        }
+    }
+
+    // Cuts the subtree at `key`: entries from `key` on go to the returned tree.
+    // Nodes along the cut are left with any number of entries, see `fix_right_border`.
+    unsafe fn split_off<Q: Ord + ?Sized, A: Allocator>(self, key: &Q, alloc: &A) -> Self
+    where
+        K: Borrow<Q>,
+    {
+        unsafe {
+            let (Ok(i) | Err(i)) = self.search(key);
+            // Cut below first: once `key` has been compared everywhere, nothing can panic midway
+            let child = if self.is_leaf() {
+                None
+            } else {
+                Some(self.edge(i).split_off(key, alloc))
+            };
+            let len = self.len();
+            let right = self.new_like(alloc);
+            ptr::copy_nonoverlapping(self.key(i), right.key(0), len - i);
+            ptr::copy_nonoverlapping(self.val(i), right.val(0), len - i);
+            if let Some(child) = child {
+                *right.edge_ptr(0) = child.node;
+                ptr::copy_nonoverlapping(self.edge_ptr(i + 1), right.edge_ptr(1), len - i);
+                right.correct_parent_links(0, len - i);
+            }
+            right.set_len(len - i);
+            self.set_len(i);
+            right
+        }
+    }
+
+    unsafe fn count(self) -> usize {
+        unsafe {
+            let mut count = self.len();
+            if !self.is_leaf() {
+                for i in 0..=self.len() {
+                    count += self.edge(i).count();
+                }
+            }
+            count
+        }
+    }
+
+    // Moves the last entry of child `i - 1` through the separator into child `i`
+    unsafe fn rotate_right(self, i: usize) {
+        unsafe {
+            let (left, child) = (self.edge(i - 1), self.edge(i));
+            let (key, val, edge) = left.pop_back();
+            let key = mem::replace(&mut *self.key(i - 1), key);
+            let val = mem::replace(&mut *self.val(i - 1), val);
+            child.push_front(key, val, edge);
+        }
+    }
+
+    // Moves the first entry of child `i + 1` through the separator into child `i`
+    unsafe fn rotate_left(self, i: usize) {
+        unsafe {
+            let (child, right) = (self.edge(i), self.edge(i + 1));
+            let (key, val, edge) = right.pop_front();
+            let key = mem::replace(&mut *self.key(i), key);
+            let val = mem::replace(&mut *self.val(i), val);
+            child.push_back(key, val, edge);
+        }
+    }
+
+    // Joins child `i + 1` and the separator into child `i` and frees the former. They must fit.
+    unsafe fn merge<A: Allocator>(self, i: usize, alloc: &A) {
+        unsafe {
+            let left = self.edge(i);
+            let (key, val, right) = self.remove_at(i);
+            let right = right.unwrap();
+            let (left_len, right_len) = (left.len(), right.len());
+            left.key(left_len).write(key);
+            left.val(left_len).write(val);
+            ptr::copy_nonoverlapping(right.key(0), left.key(left_len + 1), right_len);
+            ptr::copy_nonoverlapping(right.val(0), left.val(left_len + 1), right_len);
+            if !left.is_leaf() {
+                ptr::copy_nonoverlapping(right.edge_ptr(0), left.edge_ptr(left_len + 1), right_len + 1);
+                left.correct_parent_links(left_len + 1, left_len + 1 + right_len);
+            }
+            left.set_len(left_len + 1 + right_len);
+            right.dealloc(alloc);
+        }
+    }
+
+    // Child `i` may have lost one entry: borrows from a sibling or merges with it.
+    // Returns whether `self` lost an entry to a merge.
+    unsafe fn fix_child<A: Allocator>(self, i: usize, alloc: &A) -> bool {
+        unsafe {
+            if self.edge(i).len() >= MIN_LEN {
+                return false;
+            }
+            if i > 0 && self.edge(i - 1).len() > MIN_LEN {
+                self.rotate_right(i);
+            } else if i < self.len() && self.edge(i + 1).len() > MIN_LEN {
+                self.rotate_left(i);
+            } else if i > 0 {
+                self.merge(i - 1, alloc);
+                return true;
+            } else {
+                self.merge(i, alloc);
+                return true;
+            }
+            false
+        }
+    }
+
+    // Removes the entry at slot `i` of `self`, then repairs the nodes up from the leaf it
+    // came out of. The root may be left empty, see `trim`.
+    unsafe fn remove_kv<A: Allocator>(self, i: usize, alloc: &A) -> (K, V) {
+        unsafe {
+            let (key, val, leaf) = if self.is_leaf() {
+                let (key, val, _) = self.remove_at(i);
+                (key, val, self)
+            } else {
+                // The predecessor takes the place of the removed entry
+                let mut leaf = self.edge(i);
+                while !leaf.is_leaf() {
+                    leaf = leaf.edge(leaf.len());
+                }
+                let (k, v, _) = leaf.pop_back();
+                (mem::replace(&mut *self.key(i), k), mem::replace(&mut *self.val(i), v), leaf)
+            };
+            let mut node = leaf;
+            while node.len() < MIN_LEN
+                && let Some((parent, idx)) = node.parent()
+                && parent.fix_child(idx, alloc)
+            {
+                node = parent;
+            }
+            (key, val)
+        }
+    }
+
+    // Drops empty roots: the only child takes their place
+    unsafe fn trim<A: Allocator>(&mut self, alloc: &A) {
+        unsafe {
+            while self.len() == 0 && !self.is_leaf() {
+                let child = self.edge(0);
+                self.dealloc(alloc);
+                (*child.node.as_ptr()).parent = None;
+                *self = child;
+            }
+        }
+    }
+
+    // Repairs the nodes along the right border, which may hold any number of
+    // entries after a cut or bulk push. Every other node must be valid.
+    unsafe fn fix_right_border<A: Allocator>(&mut self, alloc: &A) {
+        unsafe {
+            self.trim(alloc);
+            let mut node = *self;
+            while !node.is_leaf() {
+                let last = node.len();
+                let child_len = node.edge(last).len();
+                if child_len < PLENTIFUL {
+                    if node.edge(last - 1).len() + 1 + child_len <= CAPACITY {
+                        node.merge(last - 1, alloc);
+                    } else {
+                        for _ in child_len..PLENTIFUL {
+                            node.rotate_right(last);
+                        }
+                    }
+                }
+                node = node.edge(node.len());
+            }
+            self.trim(alloc);
+        }
+    }
+
+    unsafe fn fix_left_border<A: Allocator>(&mut self, alloc: &A) {
+        unsafe {
+            self.trim(alloc);
+            let mut node = *self;
+            while !node.is_leaf() {
+                let child_len = node.edge(0).len();
+                if child_len < PLENTIFUL {
+                    if node.edge(1).len() + 1 + child_len <= CAPACITY {
+                        node.merge(0, alloc);
+                    } else {
+                        for _ in child_len..PLENTIFUL {
+                            node.rotate_left(0);
+                        }
+                    }
+                }
+                node = node.edge(0);
+            }
+            self.trim(alloc);
+        }
+    }
+
+    unsafe fn first_leaf_edge(self) -> LeafEdge<K, V> {
+        let mut node = self;
+        unsafe {
+            while !node.is_leaf() {
+                node = node.edge(0);
+            }
+        }
+        LeafEdge { node: node.node, idx: 0 }
+    }
+
+    unsafe fn last_leaf_edge(self) -> LeafEdge<K, V> {
+        let mut node = self;
+        unsafe {
+            while !node.is_leaf() {
+                node = node.edge(node.len());
+            }
+            LeafEdge { node: node.node, idx: node.len() }
+        }
+    }
+
+    // Goes down the edges picked by `index` to a leaf edge
+    unsafe fn descend(self, mut index: impl FnMut(Self) -> usize) -> LeafEdge<K, V> {
+        let mut node = self;
+        unsafe {
+            loop {
+                let i = index(node);
+                if node.is_leaf() {
+                    return LeafEdge { node: node.node, idx: i };
+                }
+                node = node.edge(i);
+            }
+        }
+    }
+
+    // The leaf edge right after entry `i`
+    unsafe fn leaf_edge_after(self, i: usize) -> LeafEdge<K, V> {
+        if self.is_leaf() {
+            return LeafEdge { node: self.node, idx: i + 1 };
+        }
+        unsafe {
+            self.edge(i + 1).first_leaf_edge()
+        }
+    }
+
+    // The leaf edge right before entry `i`
+    unsafe fn leaf_edge_before(self, i: usize) -> LeafEdge<K, V> {
+        if self.is_leaf() {
+            return LeafEdge { node: self.node, idx: i };
+        }
+        unsafe {
+            self.edge(i).last_leaf_edge()
+        }
+    }
+}
+
+// Safety: `ptr` has room for `len + 1` elements
+unsafe fn slice_insert<T>(ptr: *mut T, len: usize, i: usize, value: T) {
+    unsafe {
+        ptr::copy(ptr.add(i), ptr.add(i + 1), len - i);
+        ptr.add(i).write(value);
+    }
+}
+
+unsafe fn slice_remove<T>(ptr: *mut T, len: usize, i: usize) -> T {
+    unsafe {
+        let value = ptr::read(ptr.add(i));
+        ptr::copy(ptr.add(i + 1), ptr.add(i), len - i - 1);
+        value
+    }
+}
+
+impl<K, V> BTreeMap<K, V> {
+    // Constructor
+    pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+}
+
+impl<K, V, A: Allocator> BTreeMap<K, V, A> {
+    // Constructor: no allocation until the first insert
+    pub fn new_in(alloc: A) -> Self {
+        Self { root: None, height: 0, length: 0, alloc, marker: PhantomData }
+    }
+
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
+    fn root_node(&self) -> Option<NodeRef<K, V>> {
+        self.root.map(|node| NodeRef { node, height: self.height })
+    }
+
+    fn set_root(&mut self, root: NodeRef<K, V>) {
+        self.root = Some(root.node);
+        self.height = root.height;
+    }
+
+    /// Removes all entries and frees the nodes
+    pub fn clear(&mut self) {
+        let Some(root) = self.root_node() else {
+            return;
+        };
+        self.root = None;
+        self.height = 0;
+        self.length = 0;
+        unsafe {
+            drop_tree(&mut RawRange::full(Some(root)), root, &self.alloc);
+        }
+    }
+
+    /// Entries in key order
+    pub fn iter(&self) -> Iter<'_, K, V, A> {
+        Iter { range: RawRange::full(self.root_node()), length: self.length, marker: PhantomData }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V, A> {
+        IterMut { range: RawRange::full(self.root_node()), length: self.length, marker: PhantomData }
+    }
+
+    pub fn keys(&self) -> Keys<'_, K, V, A> {
+        Keys { inner: self.iter() }
+    }
+
+    pub fn values(&self) -> Values<'_, K, V, A> {
+        Values { inner: self.iter() }
+    }
+
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V, A> {
+        ValuesMut { inner: self.iter_mut() }
+    }
+
+    pub fn into_keys(self) -> IntoKeys<K, V, A> {
+        IntoKeys { inner: self.into_iter() }
+    }
+
+    pub fn into_values(self) -> IntoValues<K, V, A> {
+        IntoValues { inner: self.into_iter() }
+    }
+
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        unsafe {
+            let entry = self.root_node()?.first_leaf_edge().next_kv()?;
+            Some(entry_at(entry))
+        }
+    }
+
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        unsafe {
+            let entry = self.root_node()?.last_leaf_edge().next_back_kv()?;
+            Some(entry_at(entry))
+        }
+    }
+
+    pub fn pop_first(&mut self) -> Option<(K, V)> {
+        unsafe {
+            let entry = self.root_node()?.first_leaf_edge().next_kv()?;
+            Some(self.remove_at(entry))
+        }
+    }
+
+    pub fn pop_last(&mut self) -> Option<(K, V)> {
+        unsafe {
+            let entry = self.root_node()?.last_leaf_edge().next_back_kv()?;
+            Some(self.remove_at(entry))
+        }
+    }
+
+    // Safety: the entry is in this map
+    unsafe fn remove_at(&mut self, (node, i): (NodeRef<K, V>, usize)) -> (K, V) {
+        unsafe {
+            let entry = node.remove_kv(i, &self.alloc);
+            let mut root = self.root_node().unwrap_unchecked();
+            root.trim(&self.alloc);
+            self.set_root(root);
+            self.length -= 1;
+            entry
+        }
+    }
+
+    // Builds from entries in strictly ascending key order in O(n)
+    fn bulk_build<I: Iterator<Item=(K, V)>>(iter: I, alloc: A) -> Self {
+        // A valid map throughout, dropped as usual if `iter` panics
+        let mut map = Self::new_in(alloc);
+        let mut leaf = NodeRef::new_leaf(&map.alloc);
+        map.set_root(leaf);
+        for (key, value) in iter {
+            unsafe {
+                if leaf.len() < CAPACITY {
+                    leaf.push_back(key, value, None);
+                } else {
+                    // Climbs to the lowest node on the right border with room, or grows a level
+                    let mut open = leaf;
+                    loop {
+                        match open.parent() {
+                            Some((parent, _)) => {
+                                open = parent;
+                                if open.len() < CAPACITY {
+                                    break;
+                                }
+                            }
+                            None => {
+                                open = NodeRef::new_root(open, &map.alloc);
+                                map.set_root(open);
+                                break;
+                            }
+                        }
+                    }
+                    let tree = NodeRef::new_empty_tree(open.height - 1, &map.alloc);
+                    open.push_back(key, value, Some(tree));
+                    leaf = NodeRef { node: tree.first_leaf_edge().node, height: 0 };
+                }
+            }
+            map.length += 1;
+        }
+        unsafe {
+            let mut root = map.root_node().unwrap_unchecked();
+            root.fix_right_border(&map.alloc);
+            map.set_root(root);
+        }
+        map
+    }
+}
+
+impl<K: Ord, V, A: Allocator> BTreeMap<K, V, A> {
+    pub fn contains_key<Q: Ord + ?Sized>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.get_key_value(key).is_some()
+    }
+
+    pub fn get<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+    {
+        self.get_key_value(key).map(|(_, v)| v)
+    }
+
+    pub fn get_key_value<Q: Ord + ?Sized>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+    {
+        unsafe {
+            let entry = self.root_node()?.find(key)?;
+            Some(entry_at(entry))
+        }
+    }
+
+    pub fn get_mut<Q: Ord + ?Sized>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+    {
+        unsafe {
+            let entry = self.root_node()?.find(key)?;
+            Some(entry_mut_at(entry).1)
+        }
+    }
+
+    pub fn remove<Q: Ord + ?Sized>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+    {
+        self.remove_entry(key).map(|(_, v)| v)
+    }
+
+    pub fn remove_entry<Q: Ord + ?Sized>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+    {
+        unsafe {
+            let entry = self.root_node()?.find(key)?;
+            Some(self.remove_at(entry))
+        }
+    }
+
+    /// Entries with keys in `range`, in key order.
+    /// Panics if the start is after the end, or both are equal and excluded.
+    pub fn range<Q: Ord + ?Sized, R: RangeBounds<Q>>(&self, range: R) -> Range<'_, K, V, A>
+    where
+        K: Borrow<Q>,
+    {
+        check_range(&range);
+        Range { range: RawRange::range(self.root_node(), range.start_bound(), range.end_bound()), marker: PhantomData }
+    }
+
+    pub fn range_mut<Q: Ord + ?Sized, R: RangeBounds<Q>>(&mut self, range: R) -> RangeMut<'_, K, V, A>
+    where
+        K: Borrow<Q>,
+    {
+        check_range(&range);
+        RangeMut { range: RawRange::range(self.root_node(), range.start_bound(), range.end_bound()), marker: PhantomData }
+    }
+
+    /// Returns the previous value of `key`. The stored key itself is not replaced.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let root = match self.root_node() {
+            Some(root) => root,
+            None => {
+                let root = NodeRef::new_leaf(&self.alloc);
+                self.set_root(root);
+                root
+            }
+        };
+        unsafe {
+            let root = if root.len() == CAPACITY {
+                let root = NodeRef::new_root(root, &self.alloc);
+                root.split_child(0, &self.alloc);
+                self.set_root(root);
+                root
+            } else {
+                root
+            };
+            let old = root.insert(key, value, &self.alloc);
+            self.length += old.is_none() as usize;
+            old
+        }
+    }
+
+    /// Keeps the entries for which `f` returns true, in place.
+    /// Each removal costs O(log n); if `f` panics, the unvisited entries stay.
+    pub fn retain<F: FnMut(&K, &mut V) -> bool>(&mut self, mut f: F) {
+        let Some(root) = self.root_node() else {
+            return;
+        };
+        unsafe {
+            let mut front = root.first_leaf_edge();
+            while let Some((node, i)) = front.next_kv() {
+                if f(&*node.key(i), &mut *node.val(i)) {
+                    front = node.leaf_edge_after(i);
+                    continue;
+                }
+                let (key, value) = self.remove_at((node, i));
+                // The repair may have moved entries around: find the next one by key
+                let root = self.root_node().unwrap_unchecked();
+                front = root.descend(|node| {
+                    let (Ok(i) | Err(i)) = node.search(&key);
+                    i
+                });
+                drop((key, value));
+            }
+        }
+    }
+}
+
+impl<K: Ord, V, A: Allocator + Clone> BTreeMap<K, V, A> {
+    /// Moves all entries of `other` into `self` in O(n + m).
+    /// Values of `other` win for keys in both.
+    pub fn append(&mut self, other: &mut Self) {
+        if other.is_empty() {
+            return;
+        }
+        if self.is_empty() {
+            mem::swap(self, other);
+            return;
+        }
+        let ours = mem::replace(self, Self::new_in(self.alloc.clone()));
+        let theirs = mem::replace(other, Self::new_in(other.alloc.clone()));
+        let merged = MergeIter { a: ours.into_iter().peekable(), b: theirs.into_iter().peekable() };
+        *self = Self::bulk_build(merged, self.alloc.clone());
+    }
+
+    /// Moves the entries from `key` on into a new map.
+    /// Cuts the tree along one path: O(log n) plus counting the entries of the shorter side.
+    pub fn split_off<Q: Ord + ?Sized>(&mut self, key: &Q) -> Self
+    where
+        K: Borrow<Q>,
+    {
+        let mut right = Self::new_in(self.alloc.clone());
+        let Some(mut left) = self.root_node() else {
+            return right;
+        };
+        unsafe {
+            let mut cut = left.split_off(key, &self.alloc);
+            left.fix_right_border(&self.alloc);
+            cut.fix_left_border(&self.alloc);
+            self.set_root(left);
+            right.set_root(cut);
+
+            right.length = if cut.height <= left.height {
+                cut.count()
+            } else {
+                self.length - left.count()
+            };
+        }
+        self.length -= right.length;
+        right
+    }
+}
+
+impl<K, V, A: Allocator> Drop for BTreeMap<K, V, A> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+fn check_range<Q: Ord + ?Sized, R: RangeBounds<Q>>(range: &R) {
+    match (range.start_bound(), range.end_bound()) {
+        (Bound::Excluded(s), Bound::Excluded(e)) if s == e => {
+            panic!("range start and end are equal and excluded in BTreeMap")
+        }
+        (Bound::Included(s) | Bound::Excluded(s), Bound::Included(e) | Bound::Excluded(e)) if s > e => {
+            panic!("range start is greater than range end in BTreeMap")
+        }
+        _ => {}
+    }
+}
+
+// Merges two sorted iterators, taking `b`'s entry for keys in both
+struct MergeIter<K, V, I: Iterator<Item=(K, V)>> {
+    a: Peekable<I>,
+    b: Peekable<I>,
+}
+
+impl<K: Ord, V, I: Iterator<Item=(K, V)>> Iterator for MergeIter<K, V, I> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        let ordering = match (self.a.peek(), self.b.peek()) {
+            (Some(x), Some(y)) => x.0.cmp(&y.0),
+            (Some(_), None) => Ordering::Less,
+            (None, _) => Ordering::Greater,
+        };
+        match ordering {
+            Ordering::Less => self.a.next(),
+            Ordering::Greater => self.b.next(),
+            Ordering::Equal => {
+                self.a.next();
+                self.b.next()
+            }
+        }
+    }
+}
+
+// Skips all but the last of each run of equal keys
+struct DedupSortedIter<K, V, I: Iterator<Item=(K, V)>> {
+    iter: Peekable<I>,
+}
+
+impl<K: Eq, V, I: Iterator<Item=(K, V)>> Iterator for DedupSortedIter<K, V, I> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        loop {
+            let next = self.iter.next()?;
+            match self.iter.peek() {
+                Some(peeked) if peeked.0 == next.0 => continue,
+                _ => return Some(next),
+            }
+        }
+    }
+}
+
+// ======== ITERATORS ========
+
+// A gap between two neighbouring entries of a leaf, or at either end of it.
+// Every position between two entries of the tree is exactly one leaf edge.
+struct LeafEdge<K, V> {
+    node: NonNull<LeafNode<K, V>>,
+    idx: usize,
+}
+
+impl<K, V> Clone for LeafEdge<K, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K, V> Copy for LeafEdge<K, V> {}
+
+impl<K, V> PartialEq for LeafEdge<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.node == other.node && self.idx == other.idx
+    }
+}
+
+impl<K, V> LeafEdge<K, V> {
+    // The entry right after this edge, climbing out of used up nodes through the parents
+    unsafe fn next_kv(self) -> Option<(NodeRef<K, V>, usize)> {
+        let mut node = NodeRef { node: self.node, height: 0 };
+        let mut i = self.idx;
+        unsafe {
+            while i >= node.len() {
+                (node, i) = node.parent()?;
+            }
+        }
+        Some((node, i))
+    }
+
+    unsafe fn next_back_kv(self) -> Option<(NodeRef<K, V>, usize)> {
+        let mut node = NodeRef { node: self.node, height: 0 };
+        let mut i = self.idx;
+        unsafe {
+            while i == 0 {
+                (node, i) = node.parent()?;
+            }
+        }
+        Some((node, i - 1))
+    }
+}
+
+// The leaf edges before the next entry at either end: no heap memory, and
+// moving to the next entry is O(1) amortized. Empty once the ends meet.
+struct RawRange<K, V> {
+    front: LeafEdge<K, V>,
+    back: LeafEdge<K, V>,
+}
+
+impl<K, V> RawRange<K, V> {
+    // Constructor
+    fn empty() -> Self {
+        // Never followed: the ends have already met
+        let edge = LeafEdge { node: NonNull::dangling(), idx: 0 };
+        Self { front: edge, back: edge }
+    }
+
+    // Constructor
+    fn full(root: Option<NodeRef<K, V>>) -> Self {
+        let Some(root) = root else {
+            return Self::empty();
+        };
+        unsafe {
+            Self { front: root.first_leaf_edge(), back: root.last_leaf_edge() }
+        }
+    }
+
+    // Constructor: the bounds must be checked, so that `front` comes no later than `back`
+    fn range<Q: Ord + ?Sized>(root: Option<NodeRef<K, V>>, start: Bound<&Q>, end: Bound<&Q>) -> Self
+    where
+        K: Borrow<Q> + Ord,
+    {
+        let Some(root) = root else {
+            return Self::empty();
+        };
+        unsafe {
+            // Before the first entry at or after `start`
+            let front = root.descend(|node| match start {
+                Bound::Included(key) => {
+                    let (Ok(i) | Err(i)) = node.search(key);
+                    i
+                }
+                Bound::Excluded(key) => match node.search(key) {
+                    Ok(i) => i + 1,
+                    Err(i) => i,
+                },
+                Bound::Unbounded => 0,
+            });
+            // After the last entry at or before `end`
+            let back = root.descend(|node| match end {
+                Bound::Included(key) => match node.search(key) {
+                    Ok(i) => i + 1,
+                    Err(i) => i,
+                },
+                Bound::Excluded(key) => {
+                    let (Ok(i) | Err(i)) = node.search(key);
+                    i
+                }
+                Bound::Unbounded => node.len(),
+            });
+            Self { front, back }
+        }
+    }
+
+    // Safety: the nodes are alive and not restructured since the range was made
+    unsafe fn next(&mut self) -> Option<(NodeRef<K, V>, usize)> {
+        if self.front == self.back {
+            return None;
+        }
+        unsafe {
+            let (node, i) = self.front.next_kv()?;
+            self.front = node.leaf_edge_after(i);
+            Some((node, i))
+        }
+    }
+
+    unsafe fn next_back(&mut self) -> Option<(NodeRef<K, V>, usize)> {
+        if self.front == self.back {
+            return None;
+        }
+        unsafe {
+            let (node, i) = self.back.next_back_kv()?;
+            self.back = node.leaf_edge_before(i);
+            Some((node, i))
+        }
+    }
+}
+
+impl<K, V> Clone for RawRange<K, V> {
+    fn clone(&self) -> Self {
+        Self { front: self.front, back: self.back }
+    }
+}
+
+unsafe fn entry_at<'a, K, V>((node, i): (NodeRef<K, V>, usize)) -> (&'a K, &'a V) {
+    unsafe {
+        (&*node.key(i), &*node.val(i))
+    }
+}
+
+unsafe fn entry_mut_at<'a, K, V>((node, i): (NodeRef<K, V>, usize)) -> (&'a K, &'a mut V) {
+    unsafe {
+        (&*node.key(i), &mut *node.val(i))
+    }
+}
+
+// Moves the entry out, the slot must not be read again
+unsafe fn take_entry<K, V>((node, i): (NodeRef<K, V>, usize)) -> (K, V) {
+    unsafe {
+        (ptr::read(node.key(i)), ptr::read(node.val(i)))
+    }
+}
+
+// Drops the entries left in `range`, then frees all nodes of the tree.
+// Keeps going if a destructor panics.
+unsafe fn drop_tree<K, V, A: Allocator>(range: &mut RawRange<K, V>, root: NodeRef<K, V>, alloc: &A) {
+    struct Guard<'a, K, V, A: Allocator> {
+        range: &'a mut RawRange<K, V>,
+        root: NodeRef<K, V>,
+        alloc: &'a A,
+    }
+
+    impl<K, V, A: Allocator> Drop for Guard<'_, K, V, A> {
+        fn drop(&mut self) {
+            unsafe {
+                while let Some(entry) = self.range.next() {
+                    drop(take_entry(entry));
+                }
+                self.root.dealloc_tree(self.alloc);
+            }
+        }
+    }
+
+    let guard = Guard { range, root, alloc };
+    unsafe {
+        while let Some(entry) = guard.range.next() {
+            drop(take_entry(entry));
+        }
+    }
+    // The guard frees the nodes
+    drop(guard);
+}
+
+/// Entries in key order, created by `BTreeMap::iter()`
+pub struct Iter<'a, K, V, A: Allocator = Global> {
+    range: RawRange<K, V>,
+    length: usize,
+    marker: PhantomData<&'a BTreeMap<K, V, A>>,
+}
+
+// Shares the map like the reference it stands for
+unsafe impl<K: Sync, V: Sync, A: Allocator + Sync> Send for Iter<'_, K, V, A> {}
+unsafe impl<K: Sync, V: Sync, A: Allocator + Sync> Sync for Iter<'_, K, V, A> {}
+
+impl<'a, K, V, A: Allocator> Iterator for Iter<'a, K, V, A> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let entry = unsafe {
+            self.range.next()?
+        };
+        self.length -= 1;
+        Some(unsafe { entry_at(entry) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.length, Some(self.length))
+    }
+}
+
+impl<K, V, A: Allocator> DoubleEndedIterator for Iter<'_, K, V, A> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let entry = unsafe {
+            self.range.next_back()?
+        };
+        self.length -= 1;
+        Some(unsafe { entry_at(entry) })
+    }
+}
+
+impl<K, V, A: Allocator> ExactSizeIterator for Iter<'_, K, V, A> {}
+
+impl<K, V, A: Allocator> FusedIterator for Iter<'_, K, V, A> {}
+
+impl<K, V, A: Allocator> Clone for Iter<'_, K, V, A> {
+    fn clone(&self) -> Self {
+        Iter { range: self.range.clone(), length: self.length, marker: PhantomData }
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug, A: Allocator> fmt::Debug for Iter<'_, K, V, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+/// Entries in key order with mutable values, created by `BTreeMap::iter_mut()`
+pub struct IterMut<'a, K, V, A: Allocator = Global> {
+    range: RawRange<K, V>,
+    length: usize,
+    marker: PhantomData<&'a mut BTreeMap<K, V, A>>,
+}
+
+unsafe impl<K: Send, V: Send, A: Allocator + Send> Send for IterMut<'_, K, V, A> {}
+unsafe impl<K: Sync, V: Sync, A: Allocator + Sync> Sync for IterMut<'_, K, V, A> {}
+
+impl<'a, K, V, A: Allocator> Iterator for IterMut<'a, K, V, A> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let entry = unsafe {
+            self.range.next()?
+        };
+        self.length -= 1;
+        Some(unsafe { entry_mut_at(entry) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.length, Some(self.length))
+    }
+}
+
+impl<K, V, A: Allocator> DoubleEndedIterator for IterMut<'_, K, V, A> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let entry = unsafe {
+            self.range.next_back()?
+        };
+        self.length -= 1;
+        Some(unsafe { entry_mut_at(entry) })
+    }
+}
+
+impl<K, V, A: Allocator> ExactSizeIterator for IterMut<'_, K, V, A> {}
+
+impl<K, V, A: Allocator> FusedIterator for IterMut<'_, K, V, A> {}
+
+/// Entries with keys in a range, created by `BTreeMap::range()`
+pub struct Range<'a, K, V, A: Allocator = Global> {
+    range: RawRange<K, V>,
+    marker: PhantomData<&'a BTreeMap<K, V, A>>,
+}
+
+unsafe impl<K: Sync, V: Sync, A: Allocator + Sync> Send for Range<'_, K, V, A> {}
+unsafe impl<K: Sync, V: Sync, A: Allocator + Sync> Sync for Range<'_, K, V, A> {}
+
+impl<'a, K, V, A: Allocator> Iterator for Range<'a, K, V, A> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        unsafe {
+            self.range.next().map(|entry| entry_at(entry))
+        }
+    }
+}
+
+impl<K, V, A: Allocator> DoubleEndedIterator for Range<'_, K, V, A> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        unsafe {
+            self.range.next_back().map(|entry| entry_at(entry))
+        }
+    }
+}
+
+impl<K, V, A: Allocator> FusedIterator for Range<'_, K, V, A> {}
+
+impl<K, V, A: Allocator> Clone for Range<'_, K, V, A> {
+    fn clone(&self) -> Self {
+        Range { range: self.range.clone(), marker: PhantomData }
+    }
+}
+
+/// Entries with keys in a range and mutable values, created by `BTreeMap::range_mut()`
+pub struct RangeMut<'a, K, V, A: Allocator = Global> {
+    range: RawRange<K, V>,
+    marker: PhantomData<&'a mut BTreeMap<K, V, A>>,
+}
+
+unsafe impl<K: Send, V: Send, A: Allocator + Send> Send for RangeMut<'_, K, V, A> {}
+unsafe impl<K: Sync, V: Sync, A: Allocator + Sync> Sync for RangeMut<'_, K, V, A> {}
+
+impl<'a, K, V, A: Allocator> Iterator for RangeMut<'a, K, V, A> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        unsafe {
+            self.range.next().map(|entry| entry_mut_at(entry))
+        }
+    }
+}
+
+impl<K, V, A: Allocator> DoubleEndedIterator for RangeMut<'_, K, V, A> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        unsafe {
+            self.range.next_back().map(|entry| entry_mut_at(entry))
+        }
+    }
+}
+
+impl<K, V, A: Allocator> FusedIterator for RangeMut<'_, K, V, A> {}
+
+/// Moves entries out in key order, created by `BTreeMap::into_iter()`.
+/// Entries not yielded are dropped with it.
+pub struct IntoIter<K, V, A: Allocator = Global> {
+    // Only freed on drop: reading an entry out leaves the nodes in place
+    root: Option<NodeRef<K, V>>,
+    range: RawRange<K, V>,
+    length: usize,
+    alloc: A,
+    marker: PhantomData<(K, V)>,
+}
+
+unsafe impl<K: Send, V: Send, A: Allocator + Send> Send for IntoIter<K, V, A> {}
+unsafe impl<K: Sync, V: Sync, A: Allocator + Sync> Sync for IntoIter<K, V, A> {}
+
+impl<K, V, A: Allocator> Iterator for IntoIter<K, V, A> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        let entry = unsafe {
+            self.range.next()?
+        };
+        self.length -= 1;
+        Some(unsafe { take_entry(entry) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.length, Some(self.length))
+    }
+}
+
+impl<K, V, A: Allocator> DoubleEndedIterator for IntoIter<K, V, A> {
+    fn next_back(&mut self) -> Option<(K, V)> {
+        let entry = unsafe {
+            self.range.next_back()?
+        };
+        self.length -= 1;
+        Some(unsafe { take_entry(entry) })
+    }
+}
+
+impl<K, V, A: Allocator> ExactSizeIterator for IntoIter<K, V, A> {}
+
+impl<K, V, A: Allocator> FusedIterator for IntoIter<K, V, A> {}
+
+impl<K, V, A: Allocator> Drop for IntoIter<K, V, A> {
+    fn drop(&mut self) {
+        if let Some(root) = self.root {
+            unsafe {
+                drop_tree(&mut self.range, root, &self.alloc);
+            }
+        }
+    }
+}
+
+/// Created by `BTreeMap::keys()`
+pub struct Keys<'a, K, V, A: Allocator = Global> {
+    inner: Iter<'a, K, V, A>,
+}
+
+impl<'a, K, V, A: Allocator> Iterator for Keys<'a, K, V, A> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<&'a K> {
+        self.inner.next().map(|(k, _)| k)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V, A: Allocator> DoubleEndedIterator for Keys<'_, K, V, A> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(k, _)| k)
+    }
+}
+
+impl<K, V, A: Allocator> ExactSizeIterator for Keys<'_, K, V, A> {}
+
+impl<K, V, A: Allocator> FusedIterator for Keys<'_, K, V, A> {}
+
+impl<K, V, A: Allocator> Clone for Keys<'_, K, V, A> {
+    fn clone(&self) -> Self {
+        Keys { inner: self.inner.clone() }
+    }
+}
+
+/// Created by `BTreeMap::values()`
+pub struct Values<'a, K, V, A: Allocator = Global> {
+    inner: Iter<'a, K, V, A>,
+}
+
+impl<'a, K, V, A: Allocator> Iterator for Values<'a, K, V, A> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<&'a V> {
+        self.inner.next().map(|(_, v)| v)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V, A: Allocator> DoubleEndedIterator for Values<'_, K, V, A> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(_, v)| v)
+    }
+}
+
+impl<K, V, A: Allocator> ExactSizeIterator for Values<'_, K, V, A> {}
+
+impl<K, V, A: Allocator> FusedIterator for Values<'_, K, V, A> {}
+
+/// Created by `BTreeMap::values_mut()`
+pub struct ValuesMut<'a, K, V, A: Allocator = Global> {
+    inner: IterMut<'a, K, V, A>,
+}
+
+impl<'a, K, V, A: Allocator> Iterator for ValuesMut<'a, K, V, A> {
+    type Item = &'a mut V;
+
+    fn next(&mut self) -> Option<&'a mut V> {
+        self.inner.next().map(|(_, v)| v)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V, A: Allocator> DoubleEndedIterator for ValuesMut<'_, K, V, A> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(_, v)| v)
+    }
+}
+
+impl<K, V, A: Allocator> ExactSizeIterator for ValuesMut<'_, K, V, A> {}
+
+impl<K, V, A: Allocator> FusedIterator for ValuesMut<'_, K, V, A> {}
+
+/// Created by `BTreeMap::into_keys()`
+pub struct IntoKeys<K, V, A: Allocator = Global> {
+    inner: IntoIter<K, V, A>,
+}
+
+impl<K, V, A: Allocator> Iterator for IntoKeys<K, V, A> {
+    type Item = K;
+
+    fn next(&mut self) -> Option<K> {
+        self.inner.next().map(|(k, _)| k)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V, A: Allocator> DoubleEndedIterator for IntoKeys<K, V, A> {
+    fn next_back(&mut self) -> Option<K> {
+        self.inner.next_back().map(|(k, _)| k)
+    }
+}
+
+impl<K, V, A: Allocator> ExactSizeIterator for IntoKeys<K, V, A> {}
+
+impl<K, V, A: Allocator> FusedIterator for IntoKeys<K, V, A> {}
+
+/// Created by `BTreeMap::into_values()`
+pub struct IntoValues<K, V, A: Allocator = Global> {
+    inner: IntoIter<K, V, A>,
+}
+
+impl<K, V, A: Allocator> Iterator for IntoValues<K, V, A> {
+    type Item = V;
+
+    fn next(&mut self) -> Option<V> {
+        self.inner.next().map(|(_, v)| v)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V, A: Allocator> DoubleEndedIterator for IntoValues<K, V, A> {
+    fn next_back(&mut self) -> Option<V> {
+        self.inner.next_back().map(|(_, v)| v)
+    }
+}
+
+impl<K, V, A: Allocator> ExactSizeIterator for IntoValues<K, V, A> {}
+
+impl<K, V, A: Allocator> FusedIterator for IntoValues<K, V, A> {}
+
+impl<K, V, A: Allocator> IntoIterator for BTreeMap<K, V, A> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V, A>;
+
+    fn into_iter(self) -> IntoIter<K, V, A> {
+        // The iterator takes over the nodes and the allocator
+        let map = ManuallyDrop::new(self);
+        let root = map.root_node();
+        let alloc = unsafe {
+            ptr::read(&map.alloc)
+        };
+        IntoIter { root, range: RawRange::full(root), length: map.length, alloc, marker: PhantomData }
+    }
+}
+
+impl<'a, K, V, A: Allocator> IntoIterator for &'a BTreeMap<K, V, A> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V, A>;
+
+    fn into_iter(self) -> Iter<'a, K, V, A> {
+        self.iter()
+    }
+}
+
+impl<'a, K, V, A: Allocator> IntoIterator for &'a mut BTreeMap<K, V, A> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V, A>;
+
+    fn into_iter(self) -> IterMut<'a, K, V, A> {
+        self.iter_mut()
+    }
+}
+
+// Sorts all pairs, then builds the tree bottom-up. The last value wins for repeated keys.
+impl<K: Ord, V> FromIterator<(K, V)> for BTreeMap<K, V> {
+    fn from_iter<I: IntoIterator<Item=(K, V)>>(iter: I) -> Self {
+        let mut pairs: Vector<(K, V)> = iter.into_iter().collect();
+        // Stable: equal keys keep their order for the dedup
+        pairs.sort_by(|a, b| a.0.cmp(&b.0));
+        Self::bulk_build(DedupSortedIter { iter: pairs.into_iter().peekable() }, Global)
+    }
+}
+
+impl<K: Ord, V, A: Allocator + Clone> Extend<(K, V)> for BTreeMap<K, V, A> {
+    fn extend<I: IntoIterator<Item=(K, V)>>(&mut self, iter: I) {
+        for (k, v) in iter {
+            self.insert(k, v);
+        }
+    }
+}
+
+impl<'a, K, V, A> Extend<(&'a K, &'a V)> for BTreeMap<K, V, A>
+where
+    K: Ord + Copy + 'a,
+    V: Copy + 'a,
+    A: Allocator + Clone,
+{
+    fn extend<I: IntoIterator<Item=(&'a K, &'a V)>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().map(|(&k, &v)| (k, v)));
+    }
+}
+
+// ======== STD TRAITS ========
+
+impl<K, V> Default for BTreeMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Clone, V: Clone, A: Allocator + Clone> Clone for BTreeMap<K, V, A> {
+    fn clone(&self) -> Self {
+        // Panic safe: a partial copy is a valid map and gets dropped
+        Self::bulk_build(self.iter().map(|(k, v)| (k.clone(), v.clone())), self.alloc.clone())
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug, A: Allocator> fmt::Debug for BTreeMap<K, V, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: PartialEq, V: PartialEq, A1: Allocator, A2: Allocator> PartialEq<BTreeMap<K, V, A2>> for BTreeMap<K, V, A1> {
+    fn eq(&self, other: &BTreeMap<K, V, A2>) -> bool {
+        self.len() == other.len() && self.iter().zip(other.iter()).all(|(a, b)| a == b)
+    }
+}
+
+impl<K: Eq, V: Eq, A: Allocator> Eq for BTreeMap<K, V, A> {}
+
+impl<K: Hash, V: Hash, A: Allocator> Hash for BTreeMap<K, V, A> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len());
+        for (k, v) in self.iter() {
+            k.hash(state);
+            v.hash(state);
+        }
+    }
+}
+
+/// Panics if the key is missing
+impl<K: Ord + Borrow<Q>, Q: Ord + ?Sized, V, A: Allocator> Index<&Q> for BTreeMap<K, V, A> {
+    type Output = V;
+
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("key not found")
+    }
+}
+
+impl<K: Ord, V, const N: usize> From<[(K, V); N]> for BTreeMap<K, V> {
+    fn from(arr: [(K, V); N]) -> Self {
+        Self::from_iter(arr)
+    }
+}
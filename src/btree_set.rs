@@ -0,0 +1,497 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::{FusedIterator, Peekable};
+use std::ops::RangeBounds;
+
+use crate::allocator::{Allocator, Global};
+use crate::btree_map::{self, BTreeMap};
+
+/// Ordered set of unique elements in a `BTreeMap` with `()` values, which take no space
+pub struct BTreeSet<T, A: Allocator = Global> {
+    map: BTreeMap<T, (), A>,
+}
+
+impl<T> BTreeSet<T> {
+    // Constructor
+    pub fn new() -> Self {
+        Self { map: BTreeMap::new() }
+    }
+}
+
+impl<T, A: Allocator> BTreeSet<T, A> {
+    // Constructor
+    pub fn new_in(alloc: A) -> Self {
+        Self { map: BTreeMap::new_in(alloc) }
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn allocator(&self) -> &A {
+        self.map.allocator()
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    /// Elements in ascending order
+    pub fn iter(&self) -> Iter<'_, T, A> {
+        Iter { inner: self.map.keys() }
+    }
+
+    pub fn first(&self) -> Option<&T> {
+        self.map.first_key_value().map(|(k, _)| k)
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        self.map.last_key_value().map(|(k, _)| k)
+    }
+
+    pub fn pop_first(&mut self) -> Option<T> {
+        self.map.pop_first().map(|(k, _)| k)
+    }
+
+    pub fn pop_last(&mut self) -> Option<T> {
+        self.map.pop_last().map(|(k, _)| k)
+    }
+}
+
+impl<T: Ord, A: Allocator> BTreeSet<T, A> {
+    pub fn contains<Q: Ord + ?Sized>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+    {
+        self.map.contains_key(value)
+    }
+
+    pub fn get<Q: Ord + ?Sized>(&self, value: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+    {
+        self.map.get_key_value(value).map(|(k, _)| k)
+    }
+
+    pub fn remove<Q: Ord + ?Sized>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+    {
+        self.map.remove(value).is_some()
+    }
+
+    pub fn take<Q: Ord + ?Sized>(&mut self, value: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+    {
+        self.map.remove_entry(value).map(|(k, _)| k)
+    }
+
+    /// Elements in `range`, in ascending order.
+    /// Panics if the start is after the end, or both are equal and excluded.
+    pub fn range<Q: Ord + ?Sized, R: RangeBounds<Q>>(&self, range: R) -> Range<'_, T, A>
+    where
+        T: Borrow<Q>,
+    {
+        Range { inner: self.map.range(range) }
+    }
+
+    // Set algebra: merges of the two ordered iterations, O(n + m), yielding in ascending order
+
+    pub fn union<'a, A2: Allocator>(&'a self, other: &'a BTreeSet<T, A2>) -> Union<'a, T, A, A2> {
+        Union { a: self.iter().peekable(), b: other.iter().peekable() }
+    }
+
+    pub fn intersection<'a, A2: Allocator>(&'a self, other: &'a BTreeSet<T, A2>) -> Intersection<'a, T, A, A2> {
+        Intersection { a: self.iter().peekable(), b: other.iter().peekable() }
+    }
+
+    pub fn difference<'a, A2: Allocator>(&'a self, other: &'a BTreeSet<T, A2>) -> Difference<'a, T, A, A2> {
+        Difference { a: self.iter().peekable(), b: other.iter().peekable() }
+    }
+
+    pub fn symmetric_difference<'a, A2: Allocator>(
+        &'a self,
+        other: &'a BTreeSet<T, A2>,
+    ) -> SymmetricDifference<'a, T, A, A2> {
+        SymmetricDifference { a: self.iter().peekable(), b: other.iter().peekable() }
+    }
+
+    pub fn is_subset<A2: Allocator>(&self, other: &BTreeSet<T, A2>) -> bool {
+        self.len() <= other.len() && self.difference(other).next().is_none()
+    }
+
+    pub fn is_superset<A2: Allocator>(&self, other: &BTreeSet<T, A2>) -> bool {
+        other.is_subset(self)
+    }
+
+    pub fn is_disjoint<A2: Allocator>(&self, other: &BTreeSet<T, A2>) -> bool {
+        self.intersection(other).next().is_none()
+    }
+
+    /// Keeps the elements for which `f` returns true, in place.
+    /// If `f` panics, the unvisited elements stay.
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut f: F) {
+        self.map.retain(|k, _| f(k));
+    }
+}
+
+impl<T: Ord, A: Allocator + Clone> BTreeSet<T, A> {
+    /// Returns false if an equal element is present; it's kept.
+    pub fn insert(&mut self, value: T) -> bool {
+        self.map.insert(value, ()).is_none()
+    }
+
+    /// Moves all elements of `other` into `self` in O(n + m)
+    pub fn append(&mut self, other: &mut Self) {
+        self.map.append(&mut other.map);
+    }
+
+    /// Moves the elements from `value` on into a new set
+    pub fn split_off<Q: Ord + ?Sized>(&mut self, value: &Q) -> Self
+    where
+        T: Borrow<Q>,
+    {
+        Self { map: self.map.split_off(value) }
+    }
+}
+
+// ======== ITERATORS ========
+
+/// Elements in ascending order, created by `BTreeSet::iter()`
+pub struct Iter<'a, T, A: Allocator = Global> {
+    inner: btree_map::Keys<'a, T, (), A>,
+}
+
+impl<T, A: Allocator> Clone for Iter<'_, T, A> {
+    fn clone(&self) -> Self {
+        Iter { inner: self.inner.clone() }
+    }
+}
+
+impl<'a, T, A: Allocator> Iterator for Iter<'a, T, A> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T, A: Allocator> DoubleEndedIterator for Iter<'_, T, A> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl<T, A: Allocator> ExactSizeIterator for Iter<'_, T, A> {}
+
+impl<T, A: Allocator> FusedIterator for Iter<'_, T, A> {}
+
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for Iter<'_, T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+/// Moves elements out in ascending order, created by `BTreeSet::into_iter()`
+pub struct IntoIter<T, A: Allocator = Global> {
+    inner: btree_map::IntoKeys<T, (), A>,
+}
+
+impl<T, A: Allocator> Iterator for IntoIter<T, A> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T, A: Allocator> DoubleEndedIterator for IntoIter<T, A> {
+    fn next_back(&mut self) -> Option<T> {
+        self.inner.next_back()
+    }
+}
+
+impl<T, A: Allocator> ExactSizeIterator for IntoIter<T, A> {}
+
+impl<T, A: Allocator> FusedIterator for IntoIter<T, A> {}
+
+/// Elements in a range, created by `BTreeSet::range()`
+pub struct Range<'a, T, A: Allocator = Global> {
+    inner: btree_map::Range<'a, T, (), A>,
+}
+
+impl<T, A: Allocator> Clone for Range<'_, T, A> {
+    fn clone(&self) -> Self {
+        Range { inner: self.inner.clone() }
+    }
+}
+
+impl<'a, T, A: Allocator> Iterator for Range<'a, T, A> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.inner.next().map(|(k, _)| k)
+    }
+}
+
+impl<T, A: Allocator> DoubleEndedIterator for Range<'_, T, A> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(k, _)| k)
+    }
+}
+
+impl<T, A: Allocator> FusedIterator for Range<'_, T, A> {}
+
+// ======== SET ITERATORS ========
+
+/// Elements of either set, created by `BTreeSet::union()`
+pub struct Union<'a, T, A: Allocator, A2: Allocator> {
+    a: Peekable<Iter<'a, T, A>>,
+    b: Peekable<Iter<'a, T, A2>>,
+}
+
+impl<'a, T: Ord, A: Allocator, A2: Allocator> Iterator for Union<'a, T, A, A2> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let ordering = match (self.a.peek(), self.b.peek()) {
+            (Some(x), Some(y)) => x.cmp(y),
+            (Some(_), None) => Ordering::Less,
+            (None, _) => Ordering::Greater,
+        };
+        match ordering {
+            Ordering::Less => self.a.next(),
+            Ordering::Greater => self.b.next(),
+            Ordering::Equal => {
+                self.b.next();
+                self.a.next()
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (a, b) = (self.a.len(), self.b.len());
+        (a.max(b), Some(a + b))
+    }
+}
+
+impl<T: Ord, A: Allocator, A2: Allocator> FusedIterator for Union<'_, T, A, A2> {}
+
+impl<T, A: Allocator, A2: Allocator> Clone for Union<'_, T, A, A2> {
+    fn clone(&self) -> Self {
+        Union { a: self.a.clone(), b: self.b.clone() }
+    }
+}
+
+/// Elements of both sets, created by `BTreeSet::intersection()`
+pub struct Intersection<'a, T, A: Allocator, A2: Allocator> {
+    a: Peekable<Iter<'a, T, A>>,
+    b: Peekable<Iter<'a, T, A2>>,
+}
+
+impl<'a, T: Ord, A: Allocator, A2: Allocator> Iterator for Intersection<'a, T, A, A2> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        loop {
+            match self.a.peek()?.cmp(self.b.peek()?) {
+                Ordering::Less => {
+                    self.a.next();
+                }
+                Ordering::Greater => {
+                    self.b.next();
+                }
+                Ordering::Equal => {
+                    self.b.next();
+                    return self.a.next();
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.a.len().min(self.b.len())))
+    }
+}
+
+impl<T: Ord, A: Allocator, A2: Allocator> FusedIterator for Intersection<'_, T, A, A2> {}
+
+impl<T, A: Allocator, A2: Allocator> Clone for Intersection<'_, T, A, A2> {
+    fn clone(&self) -> Self {
+        Intersection { a: self.a.clone(), b: self.b.clone() }
+    }
+}
+
+/// Elements of the first set missing from the second, created by `BTreeSet::difference()`
+pub struct Difference<'a, T, A: Allocator, A2: Allocator> {
+    a: Peekable<Iter<'a, T, A>>,
+    b: Peekable<Iter<'a, T, A2>>,
+}
+
+impl<'a, T: Ord, A: Allocator, A2: Allocator> Iterator for Difference<'a, T, A, A2> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        loop {
+            let x = *self.a.peek()?;
+            let Some(y) = self.b.peek() else {
+                return self.a.next();
+            };
+            match x.cmp(y) {
+                Ordering::Less => return self.a.next(),
+                Ordering::Greater => {
+                    self.b.next();
+                }
+                Ordering::Equal => {
+                    self.a.next();
+                    self.b.next();
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.a.len().saturating_sub(self.b.len()), Some(self.a.len()))
+    }
+}
+
+impl<T: Ord, A: Allocator, A2: Allocator> FusedIterator for Difference<'_, T, A, A2> {}
+
+impl<T, A: Allocator, A2: Allocator> Clone for Difference<'_, T, A, A2> {
+    fn clone(&self) -> Self {
+        Difference { a: self.a.clone(), b: self.b.clone() }
+    }
+}
+
+/// Elements in exactly one of the sets, created by `BTreeSet::symmetric_difference()`
+pub struct SymmetricDifference<'a, T, A: Allocator, A2: Allocator> {
+    a: Peekable<Iter<'a, T, A>>,
+    b: Peekable<Iter<'a, T, A2>>,
+}
+
+impl<'a, T: Ord, A: Allocator, A2: Allocator> Iterator for SymmetricDifference<'a, T, A, A2> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        loop {
+            let ordering = match (self.a.peek(), self.b.peek()) {
+                (Some(x), Some(y)) => x.cmp(y),
+                (Some(_), None) => Ordering::Less,
+                (None, _) => Ordering::Greater,
+            };
+            match ordering {
+                Ordering::Less => return self.a.next(),
+                Ordering::Greater => return self.b.next(),
+                Ordering::Equal => {
+                    self.a.next();
+                    self.b.next();
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.a.len() + self.b.len()))
+    }
+}
+
+impl<T: Ord, A: Allocator, A2: Allocator> FusedIterator for SymmetricDifference<'_, T, A, A2> {}
+
+impl<T, A: Allocator, A2: Allocator> Clone for SymmetricDifference<'_, T, A, A2> {
+    fn clone(&self) -> Self {
+        SymmetricDifference { a: self.a.clone(), b: self.b.clone() }
+    }
+}
+
+impl<T, A: Allocator> IntoIterator for BTreeSet<T, A> {
+    type Item = T;
+    type IntoIter = IntoIter<T, A>;
+
+    fn into_iter(self) -> IntoIter<T, A> {
+        IntoIter { inner: self.map.into_keys() }
+    }
+}
+
+impl<'a, T, A: Allocator> IntoIterator for &'a BTreeSet<T, A> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T, A>;
+
+    fn into_iter(self) -> Iter<'a, T, A> {
+        self.iter()
+    }
+}
+
+impl<T: Ord> FromIterator<T> for BTreeSet<T> {
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> Self {
+        Self { map: iter.into_iter().map(|x| (x, ())).collect() }
+    }
+}
+
+impl<T: Ord, A: Allocator + Clone> Extend<T> for BTreeSet<T, A> {
+    fn extend<I: IntoIterator<Item=T>>(&mut self, iter: I) {
+        for x in iter {
+            self.insert(x);
+        }
+    }
+}
+
+impl<'a, T: Ord + Copy + 'a, A: Allocator + Clone> Extend<&'a T> for BTreeSet<T, A> {
+    fn extend<I: IntoIterator<Item=&'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+// ======== STD TRAITS ========
+
+impl<T> Default for BTreeSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone, A: Allocator + Clone> Clone for BTreeSet<T, A> {
+    fn clone(&self) -> Self {
+        Self { map: self.map.clone() }
+    }
+}
+
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for BTreeSet<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq, A1: Allocator, A2: Allocator> PartialEq<BTreeSet<T, A2>> for BTreeSet<T, A1> {
+    fn eq(&self, other: &BTreeSet<T, A2>) -> bool {
+        self.map == other.map
+    }
+}
+
+impl<T: Eq, A: Allocator> Eq for BTreeSet<T, A> {}
+
+impl<T: Hash, A: Allocator> Hash for BTreeSet<T, A> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.map.hash(state);
+    }
+}
+
+impl<T: Ord, const N: usize> From<[T; N]> for BTreeSet<T> {
+    fn from(arr: [T; N]) -> Self {
+        Self::from_iter(arr)
+    }
+}
@@ -1,7 +1,12 @@
 use crate::vector as my;
 use crate::chunks::Chunks;
+use crate::allocator::{Allocator, Global};
 use std::fmt;
 use std::mem;
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+use std::slice;
 
 // === Formats ===
 // bytes: (&[u8]) [x01, xf3, x7d, x19]
@@ -30,22 +35,22 @@ fn hex_to_u8(h: &str) -> u8 {
 type Byte = u8;
 
 #[derive(Debug)]
-pub struct Bytes<const BIG_ENDIAN: bool = true>{
+pub struct Bytes<const BIG_ENDIAN: bool = true, A: Allocator = Global>{
     // TODO Switch to Chunks<Byte>
-    pub vec: my::Vector<Byte>
+    pub vec: my::Vector<Byte, A>
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Hex(pub String);
 
 pub type Bin = String;
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct Bins(pub my::Vector<Bin>);
 
-impl Into<String> for &Bins {
-    fn into(self) -> String {
-        self.0.join(" ")
+impl From<&Bins> for String {
+    fn from(value: &Bins) -> Self {
+        value.0.join(" ")
     }
 }
 
@@ -72,13 +77,12 @@ impl DebugBytes for Bytes<true> {
 
 impl DebugBytes for i128 {
     fn print(&self) {
-        let p: *const i128 = &*self;
+        let p: *const i128 = self;
 
-        let view: Chunks<Byte> = Chunks {
-            ptr: p as *mut Byte,
-            count: 16
+        let view: Chunks<Byte> = unsafe {
+            Chunks::from_raw_parts(p as *mut Byte, 16)
         };
-        println!("-> i128: {:?}; {:?}", self, view);
+        println!("-> i128: {:?}; {:?}", self, view.as_slice());
         std::mem::forget(view);
     }
 }
@@ -102,7 +106,7 @@ impl DebugBytes for Bins {
     }
 }
 
-impl<const BE: bool> Bytes<BE> {
+impl<const BE: bool, A: Allocator> Bytes<BE, A> {
     pub fn as_slice(&self) -> &[Byte] {
         self.vec.as_slice()
     }
@@ -115,16 +119,15 @@ impl<const BE: bool> Bytes<BE> {
             return Err("Bytes length is too big".to_string());
         }
 
-        let ptr: *const i128 = self.vec.as_ptr() as *const i128;
-        unsafe {
-            Ok(*ptr)
-        }
+        // Shorter inputs are zero-padded instead of reading past the buffer
+        let mut buf = [0u8; 16];
+        buf[..s2].copy_from_slice(self.as_slice());
+        Ok(i128::from_ne_bytes(buf))
     }
 
     pub fn to_bin(&self) -> Bins {
         let v: my::Vector<Bin> = self.vec.iter().map(u8_to_bin).collect();
-        let a = Bins(v);
-        a
+        Bins(v)
     }
 
     pub fn to_hex(&self) -> Hex {
@@ -133,7 +136,9 @@ impl<const BE: bool> Bytes<BE> {
             .collect::<String>();
         Hex(hex_string)
     }
+}
 
+impl<const BE: bool> Bytes<BE> {
     pub fn from_bytes(from: &[Byte]) -> Self {
         Self {
             vec: my::Vector::from_slice_copy(from)
@@ -193,17 +198,17 @@ impl<const BE: bool> Bytes<BE> {
     }
 }
 
-impl From<my::Vector<u8>> for Bytes {
-    fn from(value: my::Vector<u8>) -> Self {
+impl<A: Allocator> From<my::Vector<u8, A>> for Bytes<true, A> {
+    fn from(value: my::Vector<u8, A>) -> Self {
         Self {
             vec: value
         }
     }
 }
 
-impl Into<my::Vector<u8>> for Bytes {
-    fn into(self) -> my::Vector<u8> {
-        self.vec
+impl<A: Allocator> From<Bytes<true, A>> for my::Vector<u8, A> {
+    fn from(value: Bytes<true, A>) -> Self {
+        value.vec
     }
 }
 
@@ -215,8 +220,115 @@ impl From<Vec<u8>> for Bytes {
     }
 }
 
-impl Into<Vec<u8>> for Bytes {
-    fn into(self) -> Vec<u8> {
-        self.vec.into()
+impl From<Bytes> for Vec<u8> {
+    fn from(value: Bytes) -> Self {
+        value.vec.into()
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for Bytes {
+    fn from(value: [u8; N]) -> Self {
+        Self::from_bytes(&value)
+    }
+}
+
+impl From<&[u8]> for Bytes {
+    fn from(value: &[u8]) -> Self {
+        Self::from_bytes(value)
+    }
+}
+
+impl From<Box<[u8]>> for Bytes {
+    fn from(value: Box<[u8]>) -> Self {
+        Self {
+            vec: my::Vector::from(value)
+        }
+    }
+}
+
+/// Fails with the original Bytes if its length isn't exactly `N`
+impl<const BE: bool, A: Allocator, const N: usize> TryFrom<Bytes<BE, A>> for [u8; N] {
+    type Error = Bytes<BE, A>;
+
+    fn try_from(value: Bytes<BE, A>) -> Result<Self, Self::Error> {
+        <[u8; N]>::try_from(value.as_slice()).map_err(|_| value)
+    }
+}
+
+// === Std traits ===
+// Bytes behave as their byte slice
+
+impl<const BE: bool, A: Allocator + Clone> Clone for Bytes<BE, A> {
+    fn clone(&self) -> Self {
+        Self {
+            vec: self.vec.clone()
+        }
+    }
+}
+
+impl Default for Bytes {
+    fn default() -> Self {
+        Self {
+            vec: my::Vector::new()
+        }
+    }
+}
+
+impl<const BE: bool, A1: Allocator, A2: Allocator> PartialEq<Bytes<BE, A2>> for Bytes<BE, A1> {
+    fn eq(&self, other: &Bytes<BE, A2>) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<const BE: bool, A: Allocator> Eq for Bytes<BE, A> {}
+
+impl<const BE: bool, A: Allocator> PartialOrd for Bytes<BE, A> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const BE: bool, A: Allocator> Ord for Bytes<BE, A> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_slice().cmp(other.as_slice())
+    }
+}
+
+impl<const BE: bool, A: Allocator> Hash for Bytes<BE, A> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state);
+    }
+}
+
+impl<const BE: bool, A: Allocator> AsRef<[u8]> for Bytes<BE, A> {
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl<const BE: bool, A: Allocator> Borrow<[u8]> for Bytes<BE, A> {
+    fn borrow(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl<const BE: bool, A: Allocator> Extend<u8> for Bytes<BE, A> {
+    fn extend<I: IntoIterator<Item=u8>>(&mut self, iter: I) {
+        self.vec.extend(iter);
+    }
+}
+
+impl<'a, const BE: bool, A: Allocator> Extend<&'a u8> for Bytes<BE, A> {
+    fn extend<I: IntoIterator<Item=&'a u8>>(&mut self, iter: I) {
+        self.vec.extend(iter);
+    }
+}
+
+impl<'a, const BE: bool, A: Allocator> IntoIterator for &'a Bytes<BE, A> {
+    type Item = &'a u8;
+    type IntoIter = slice::Iter<'a, u8>;
+
+    fn into_iter(self) -> slice::Iter<'a, u8> {
+        self.as_slice().iter()
     }
 }
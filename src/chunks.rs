@@ -1,74 +1,124 @@
 use std::alloc;
 use std::ptr;
 use std::mem;
+use std::mem::MaybeUninit;
 use std::fmt;
 use core::slice;
 use std::ops::{Index, IndexMut};
-use std::fmt::Display;
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+use crate::allocator::{Allocator, AllocError, Global, handle_alloc_error};
 
 type Layout = alloc::Layout;
 
-fn array_layout<T>(count: usize) -> Layout {
-    let layout = alloc::Layout::array::<T>(count).unwrap();
-    assert_ne!(layout.size(), 0);
+fn array_layout<T>(count: usize) -> Result<Layout, AllocError> {
+    let layout = alloc::Layout::array::<T>(count)
+        .map_err(|_| AllocError::CapacityOverflow)?;
     assert_eq!(layout.size(), count * mem::size_of::<T>());
-    layout
+    Ok(layout)
 }
 
-// TODO Add Option for error case
-fn array_alloc<T>(count: usize) -> *mut T {
-    let layout = array_layout::<T>(count);
+/// Zero-sized layouts (ZST elements or zero count) never reach the allocator:
+/// they are served by a dangling, well-aligned pointer instead
+fn dangling<T>() -> *mut T {
+    ptr::NonNull::<T>::dangling().as_ptr()
+}
 
-    unsafe {
-        alloc::alloc(layout) as *mut T
+pub(crate) fn array_alloc<T, A: Allocator>(alloc: &A, count: usize) -> Result<*mut T, AllocError> {
+    let layout = array_layout::<T>(count)?;
+    if layout.size() == 0 {
+        return Ok(dangling());
+    }
+
+    let ptr = alloc.allocate(layout) as *mut T;
+    if ptr.is_null() {
+        return Err(AllocError::OutOfMemory { layout });
     }
+    Ok(ptr)
 }
 
-fn array_realloc<T>(ptr: *mut T, count: usize, new_count: usize) -> *mut T {
+fn array_realloc<T, A: Allocator>(
+    alloc: &A,
+    ptr: *mut T,
+    count: usize,
+    new_count: usize
+) -> Result<*mut T, AllocError> {
     if new_count == count {
-        return ptr;
+        return Ok(ptr);
     }
 
-    let layout = array_layout::<T>(count);
+    // Layout of an existing allocation is always valid
+    let layout = array_layout::<T>(count).unwrap();
+    let new_layout = array_layout::<T>(new_count)?;
 
-    unsafe {
-        alloc::realloc(
+    if layout.size() == 0 {
+        return array_alloc(alloc, new_count);
+    }
+    if new_layout.size() == 0 {
+        array_dealloc(alloc, ptr, count);
+        return Ok(dangling());
+    }
+
+    // Safety: on failure the old block stays untouched and owned by the caller
+    let new_ptr = unsafe {
+        alloc.reallocate(
             ptr as *mut u8,
             layout,
-            array_layout::<T>(new_count).size()
+            new_layout.size()
         ) as *mut T
+    };
+    if new_ptr.is_null() {
+        return Err(AllocError::OutOfMemory { layout: new_layout });
     }
+    Ok(new_ptr)
 }
 
-fn array_dealloc<T>(ptr: *mut T, count: usize) {
+pub(crate) fn array_dealloc<T, A: Allocator>(alloc: &A, ptr: *mut T, count: usize) {
+    let layout = array_layout::<T>(count).unwrap();
+    if layout.size() == 0 {
+        return;
+    }
+
     // Safety: memory was allocated with same pointer and layout alignment
     unsafe {
-        alloc::dealloc(
+        alloc.deallocate(
             ptr as *mut u8,
-            array_layout::<T>(count)
+            layout
         )
     }
 }
 
-
-pub struct Chunks<T: Clone, const BOUNDS_CHECK: bool = true>
+/// Raw buffer of `count` slots of `T`, i.e. a `[MaybeUninit<T>]` on the heap.
+///
+/// `Chunks` doesn't know which slots hold live values: it never reads or drops
+/// elements, and its `Drop` only frees memory. Owners (like `Vector`) track
+/// the initialized part themselves. Element accessors (`Index`, `as_slice`, ...)
+/// assume that the slots touched were written before.
+pub struct Chunks<T, const BOUNDS_CHECK: bool = true, A: Allocator = Global>
 {
     pub ptr: *mut T,
     pub count: usize,
+    alloc: A,
 }
 
 impl<
-    T: Copy + Clone,
+    T: Copy,
     const BC: bool,
-> Chunks<T, BC> {
-
+    A: Allocator,
+> Chunks<T, BC, A> {
     pub fn memset_copy(&mut self, value: T) {
         for i in 0..self.count {
             // ptr::write(self.ptr.add(i), value);
             self[i] = value;
         }
     }
+}
 
+impl<
+    T: Copy,
+    const BC: bool,
+> Chunks<T, BC> {
     // Constructor
     pub fn filled_copy(value: T, count: usize) -> Self {
         let mut c: Self = Self::alloc(count);
@@ -92,9 +142,10 @@ impl<
 }
 
 impl<
-    T: Clone + Display,
+    T: Clone,
     const BC: bool,
-> Chunks<T, BC> {
+    A: Allocator,
+> Chunks<T, BC, A> {
     pub fn memset_clone(&mut self, value: T) {
         for i in 0..self.count {
             // Err: invalid memory reference
@@ -103,7 +154,12 @@ impl<
             self.write_index(i, value.clone());
         }
     }
+}
 
+impl<
+    T: Clone,
+    const BC: bool,
+> Chunks<T, BC> {
     // Constructor
     // TODO Can we reuse one by another
     pub fn filled_clone(value: T, count: usize) -> Self {
@@ -115,7 +171,7 @@ impl<
 }
 
 impl<
-    T: Clone + Display,
+    T: Clone,
     const BC: bool,
 > Chunks<T, BC> {
     pub fn from_slice_clone(from: &[T]) -> Self {
@@ -130,10 +186,10 @@ impl<
         //}
         // std::mem::forget(from.as_ptr());
 
-        for i in 0..count {
+        for (i, elem) in from.iter().enumerate() {
             // We are obliged to clone
             // Using write_index because memory is zeroed
-            c.write_index(i, from[i].clone())
+            c.write_index(i, elem.clone())
         }
 
         c
@@ -142,20 +198,68 @@ impl<
 
 
 impl<
-    T: Clone,
+    T,
     const BC: bool,
 > Chunks<T, BC> {
     // Constructor
     pub fn alloc(count: usize) -> Self {
+        Self::alloc_in(count, Global)
+    }
+
+    // Constructor
+    pub fn try_alloc(count: usize) -> Result<Self, AllocError> {
+        Self::try_alloc_in(count, Global)
+    }
+
+    /// Wraps memory that was allocated by the global allocator (e.g. by `Vec`).
+    /// No allocation happens, but the memory is freed on drop.
+    ///
+    /// # Safety
+    /// `ptr` must come from the global allocator with a layout of `count` elements of `T`,
+    /// or `Chunks` must be forgotten (`mem::forget`) instead of dropped.
+    pub unsafe fn from_raw_parts(ptr: *mut T, count: usize) -> Self {
+        unsafe {
+            Self::from_raw_parts_in(ptr, count, Global)
+        }
+    }
+}
+
+impl<
+    T,
+    const BC: bool,
+    A: Allocator,
+> Chunks<T, BC, A> {
+    // Constructor
+    pub fn alloc_in(count: usize, alloc: A) -> Self {
+        Self::try_alloc_in(count, alloc).unwrap_or_else(|err| handle_alloc_error(err))
+    }
+
+    // Constructor
+    pub fn try_alloc_in(count: usize, alloc: A) -> Result<Self, AllocError> {
+        Ok(Self {
+            ptr: array_alloc::<T, A>(&alloc, count)?,
+            count,
+            alloc,
+        })
+    }
+
+    /// # Safety
+    /// Same as for `from_raw_parts`, with `alloc` instead of the global allocator.
+    pub unsafe fn from_raw_parts_in(ptr: *mut T, count: usize, alloc: A) -> Self {
         Self {
-            ptr: array_alloc::<T>(count),
+            ptr,
             count,
+            alloc,
         }
     }
 
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
     pub fn dealloc(&mut self) {
         if self.allocated() {
-            array_dealloc(self.ptr, self.count);
+            array_dealloc(&self.alloc, self.ptr, self.count);
         }
 
         self.ptr = ptr::null::<T>() as *mut T;
@@ -163,21 +267,32 @@ impl<
     }
 
     pub fn realloc(&mut self, new_count: usize) {
-        if self.allocated() {
-            self.ptr = array_realloc(self.ptr, self.count, new_count);
-        } else {
-            self.ptr = array_alloc(new_count);
+        if let Err(err) = self.try_realloc(new_count) {
+            handle_alloc_error(err);
         }
+    }
+
+    /// On error `Chunks` keeps its previous memory and count
+    pub fn try_realloc(&mut self, new_count: usize) -> Result<(), AllocError> {
+        self.ptr = if self.allocated() {
+            array_realloc(&self.alloc, self.ptr, self.count, new_count)?
+        } else {
+            array_alloc(&self.alloc, new_count)?
+        };
         self.count = new_count;
+        Ok(())
     }
 
     pub fn grow(&mut self, delta: usize) {
-        if !self.allocated() {
-            // Copy is in action? How efficiently?
-            // self = Self::alloc(delta);
-            return;
+        if let Err(err) = self.try_grow(delta) {
+            handle_alloc_error(err);
         }
-        self.realloc(self.count + delta);
+    }
+
+    pub fn try_grow(&mut self, delta: usize) -> Result<(), AllocError> {
+        let new_count = self.count.checked_add(delta)
+            .ok_or(AllocError::CapacityOverflow)?;
+        self.try_realloc(new_count)
     }
 
     pub fn allocated(&self) -> bool {
@@ -206,17 +321,43 @@ impl<
     }
 
     pub fn as_slice(&self) -> &[T] {
+        // Null after dealloc(); slices require a non-null pointer even when empty
+        if self.ptr.is_null() {
+            return &[];
+        }
         unsafe {
             slice::from_raw_parts(self.ptr, self.count)
         }
     }
 
     pub fn as_mut_slice(&mut self) -> &mut [T] {
+        if self.ptr.is_null() {
+            return &mut [];
+        }
         unsafe {
             std::slice::from_raw_parts_mut(self.ptr, self.count)
         }
     }
 
+    /// View of all slots that doesn't assume they are initialized
+    pub fn as_uninit_slice(&self) -> &[MaybeUninit<T>] {
+        if self.ptr.is_null() {
+            return &[];
+        }
+        unsafe {
+            slice::from_raw_parts(self.ptr as *const MaybeUninit<T>, self.count)
+        }
+    }
+
+    pub fn as_uninit_mut_slice(&mut self) -> &mut [MaybeUninit<T>] {
+        if self.ptr.is_null() {
+            return &mut [];
+        }
+        unsafe {
+            slice::from_raw_parts_mut(self.ptr as *mut MaybeUninit<T>, self.count)
+        }
+    }
+
     pub fn indices(&self) -> std::ops::Range<usize> {
         0..self.count
     }
@@ -225,7 +366,7 @@ impl<
     fn bounds(&self, index: usize) -> bool {
         match BC {
             false => true,
-            true => 0 <= index && index < self.count,
+            true => index < self.count,
         }
     }
 
@@ -269,39 +410,6 @@ impl<
         }
     }
 
-    //
-    //fn get(&self, index: usize) -> Result<&T, &'static str> {
-    //    // Safety: Out-of-bounds is checked
-    //    if self.bounds(index) {
-    //        unsafe {
-    //            // TODO Maybe return by reference?
-    //            // Does &mut *... is a borrowing, i.e. moving?
-    //            // Looks like overhead of moving twice
-    //            Ok(&*self.ptr.add(index))
-    //        }
-    //    } else {
-    //        Err("Index out of bounds")
-    //    }
-    //}
-
-    fn get_mut(&mut self, index: usize) -> Result<&mut T, &'static str> {
-        // TODO Check overheads of such solution
-        // May it be done better?
-        //match self.get(index) {
-        //    // === Error: [E0605]: non-primitive cast: `&T` as `&mut T`
-        //    Ok(value) => Ok(value as &mut T),
-        //    Err(err) => panic!("{}", err),
-        //}
-
-        if self.bounds(index) {
-            // Safety: Out-of-bounds is checked
-            unsafe {
-                Ok(&mut *self.ptr.add(index))
-            }
-        } else {
-            Err("Index out of bounds")
-        }
-    }
     // TODO is it right approach in Rust to have mut & const function's duplicates?
 }
 
@@ -310,9 +418,10 @@ impl<
 // Because returning a reference to a non-existing type value
 
 impl<
-    T: Clone,
+    T,
     const BC: bool,
-> Index<usize> for Chunks<T, BC> {
+    A: Allocator,
+> Index<usize> for Chunks<T, BC, A> {
     type Output = T;
 
     fn index(&self, index: usize) -> &Self::Output {
@@ -323,9 +432,10 @@ impl<
 }
 
 impl<
-    T: Clone,
+    T,
     const BC: bool,
-> IndexMut<usize> for Chunks<T, BC> {
+    A: Allocator,
+> IndexMut<usize> for Chunks<T, BC, A> {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
         unsafe {
             &mut *self.get_mut_ptr(index).unwrap()
@@ -335,44 +445,284 @@ impl<
 
 // ================== FMT ==================
 
+// Slots may be uninitialized, so only the buffer itself is shown.
+// Use `as_slice()` to print contents known to be written.
 impl<
-    T: Display + Clone,
+    T,
     const BC: bool,
-> fmt::Debug for Chunks<T, BC> {
+    A: Allocator,
+> fmt::Debug for Chunks<T, BC, A> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Chunks: [").unwrap();
-        for i in 0..self.count {
-            if i > 0 {
-                write!(f, ", ").unwrap();
+        f.debug_struct("Chunks")
+            .field("ptr", &self.ptr)
+            .field("count", &self.count)
+            .finish()
+    }
+}
+
+// ================== CLONE & DEFAULT ==================
+
+// Slots are copied bitwise, written or not: only sound for `Copy` elements
+impl<
+    T: Copy,
+    const BC: bool,
+    A: Allocator + Clone,
+> Clone for Chunks<T, BC, A> {
+    fn clone(&self) -> Self {
+        let c = Self::alloc_in(self.count, self.alloc.clone());
+        unsafe {
+            ptr::copy_nonoverlapping(self.ptr, c.ptr, self.count);
+        }
+        c
+    }
+}
+
+impl<
+    T,
+    const BC: bool,
+    A: Allocator + Default,
+> Default for Chunks<T, BC, A> {
+    fn default() -> Self {
+        Self::alloc_in(0, A::default())
+    }
+}
+
+// ================== CMP & HASH ==================
+// Like `as_slice`, these read every slot: all of them must be written
+
+impl<
+    T: PartialEq,
+    const BC: bool,
+    A: Allocator,
+> PartialEq for Chunks<T, BC, A> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<
+    T: Eq,
+    const BC: bool,
+    A: Allocator,
+> Eq for Chunks<T, BC, A> {}
+
+impl<
+    T: PartialOrd,
+    const BC: bool,
+    A: Allocator,
+> PartialOrd for Chunks<T, BC, A> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.as_slice().partial_cmp(other.as_slice())
+    }
+}
+
+impl<
+    T: Ord,
+    const BC: bool,
+    A: Allocator,
+> Ord for Chunks<T, BC, A> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_slice().cmp(other.as_slice())
+    }
+}
+
+impl<
+    T: Hash,
+    const BC: bool,
+    A: Allocator,
+> Hash for Chunks<T, BC, A> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state);
+    }
+}
+
+// ================== AS_REF & BORROW ==================
+
+impl<
+    T,
+    const BC: bool,
+    A: Allocator,
+> AsRef<[T]> for Chunks<T, BC, A> {
+    fn as_ref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<
+    T,
+    const BC: bool,
+    A: Allocator,
+> AsMut<[T]> for Chunks<T, BC, A> {
+    fn as_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<
+    T,
+    const BC: bool,
+    A: Allocator,
+> Borrow<[T]> for Chunks<T, BC, A> {
+    fn borrow(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+// ================== ITER ==================
+
+impl<
+    'a,
+    T,
+    const BC: bool,
+    A: Allocator,
+> IntoIterator for &'a Chunks<T, BC, A> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> slice::Iter<'a, T> {
+        self.as_slice().iter()
+    }
+}
+
+impl<
+    'a,
+    T,
+    const BC: bool,
+    A: Allocator,
+> IntoIterator for &'a mut Chunks<T, BC, A> {
+    type Item = &'a mut T;
+    type IntoIter = slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> slice::IterMut<'a, T> {
+        self.as_mut_slice().iter_mut()
+    }
+}
+
+// Appends new slots after the existing `count` ones, written in place.
+// Grows by the iterator's size hint, so an exact one reallocates once.
+impl<
+    T,
+    const BC: bool,
+    A: Allocator,
+> Extend<T> for Chunks<T, BC, A> {
+    fn extend<I: IntoIterator<Item=T>>(&mut self, iter: I) {
+        // Slots from `written` on are only reserved: they are cut off at the end,
+        // also if `iter` panics, so no uninitialized slot is left behind
+        struct Guard<'a, T, const BC: bool, A: Allocator> {
+            chunks: &'a mut Chunks<T, BC, A>,
+            written: usize,
+        }
+
+        impl<T, const BC: bool, A: Allocator> Drop for Guard<'_, T, BC, A> {
+            fn drop(&mut self) {
+                if self.written != self.chunks.count {
+                    self.chunks.realloc(self.written);
+                }
+            }
+        }
+
+        let mut iter = iter.into_iter();
+        let old_count = self.count;
+        let mut guard = Guard { written: old_count, chunks: self };
+        while let Some(item) = iter.next() {
+            if guard.written == guard.chunks.count {
+                // At least doubles what was appended so far, for iterators that hint low
+                let (lower, _) = iter.size_hint();
+                let extra = lower.saturating_add(1).max(guard.written - old_count);
+                guard.chunks.grow(extra);
+            }
+            unsafe {
+                guard.chunks.ptr.add(guard.written).write(item);
             }
-            // --> Works improperly; Seems that it frees a pointer obtained, and this cuases an
-            // --> issue for Clone types
-            // unsafe {
-            //     write!(f, "{:}", self.get_ptr(i).unwrap().read()).unwrap();
-            // }
+            guard.written += 1;
+        }
+    }
+}
+
+impl<
+    'a,
+    T: Copy + 'a,
+    const BC: bool,
+    A: Allocator,
+> Extend<&'a T> for Chunks<T, BC, A> {
+    fn extend<I: IntoIterator<Item=&'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+// ================== FROM & INTO ==================
+
+impl<
+    T: Clone,
+    const BC: bool,
+> From<&[T]> for Chunks<T, BC> {
+    fn from(value: &[T]) -> Self {
+        Self::from_slice_clone(value)
+    }
+}
+
+impl<
+    T,
+    const BC: bool,
+    const N: usize,
+> From<[T; N]> for Chunks<T, BC> {
+    fn from(value: [T; N]) -> Self {
+        let c = Self::alloc(N);
+        // Elements are moved into the slots, the array must not drop them
+        let value = mem::ManuallyDrop::new(value);
+        unsafe {
+            ptr::copy_nonoverlapping(value.as_ptr(), c.ptr, N);
+        }
+        c
+    }
+}
 
-            // --> Also doesn't work, because data may be zeroed
-            // write!(f, "{}", self[i]).unwrap();
-            // --> TODO Add check for whether current memory is assigned with values or not
+/// Moves all `count` slots out, so they must all be written.
+/// Fails with the original Chunks if `count` isn't exactly `N`.
+impl<
+    T,
+    const BC: bool,
+    A: Allocator,
+    const N: usize,
+> TryFrom<Chunks<T, BC, A>> for [T; N] {
+    type Error = Chunks<T, BC, A>;
+
+    fn try_from(value: Chunks<T, BC, A>) -> Result<Self, Self::Error> {
+        if value.count != N {
+            return Err(value);
+        }
+        // `ptr` may be null here (e.g. after `dealloc`), which must not be read through
+        if N == 0 {
+            return Ok(core::array::from_fn(|_| unreachable!()));
+        }
 
-            let val: T = unsafe {
-                self.ptr.add(i).read()
-            };
-            write!(f, "{}", val).unwrap();
+        // Chunks never drops its slots, only the memory is freed
+        unsafe {
+            Ok(ptr::read(value.ptr as *const [T; N]))
+        }
+    }
+}
 
-            // --> We don't wont to drop a value
-            std::mem::forget(val);
+impl<
+    T,
+    const BC: bool,
+> From<Box<[T]>> for Chunks<T, BC> {
+    fn from(value: Box<[T]>) -> Self {
+        let count = value.len();
+        // Box<[T]> is allocated by the global allocator with the layout of `count` elements
+        unsafe {
+            Self::from_raw_parts(Box::into_raw(value) as *mut T, count)
         }
-        write!(f, "]")
     }
 }
 
 // ================== DROP ==================
 
 impl<
-    T: Clone,
+    T,
     const BC: bool,
-> Drop for Chunks<T, BC> {
+    A: Allocator,
+> Drop for Chunks<T, BC, A> {
     fn drop(&mut self) {
         if self.allocated() {
             self.dealloc();
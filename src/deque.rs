@@ -0,0 +1,639 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::FusedIterator;
+use std::mem;
+use std::ops::{Index, IndexMut, Range, RangeBounds};
+use std::ptr;
+use std::slice;
+
+use crate::allocator::{Allocator, AllocError, Global, handle_alloc_error};
+use crate::chunks::Chunks;
+use crate::growth::{Doubling, GrowthPolicy};
+use crate::vector::{slice_range, Vector};
+
+/// Double-ended queue in a ring buffer.
+///
+/// The `len` elements start at slot `head` of the `Chunks` and wrap around its end,
+/// so both ends push and pop in O(1). The other slots are uninitialized.
+pub struct Deque<T, A: Allocator = Global> {
+    buf: Chunks<T, true, A>,
+    head: usize,
+    len: usize,
+}
+
+impl<T> Deque<T> {
+    // Constructor
+    pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+
+    // Constructor
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_in(capacity, Global)
+    }
+}
+
+impl<T, A: Allocator> Deque<T, A> {
+    // Constructor
+    pub fn new_in(alloc: A) -> Self {
+        Self::with_capacity_in(0, alloc)
+    }
+
+    // Constructor
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        Self {
+            buf: Chunks::alloc_in(capacity, alloc),
+            head: 0,
+            len: 0,
+        }
+    }
+
+    pub fn allocator(&self) -> &A {
+        self.buf.allocator()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.buf.count
+    }
+
+    // Slot of the logical index `index`, which may be up to `capacity` past the end
+    fn to_physical(&self, index: usize) -> usize {
+        let i = self.head + index;
+        if i >= self.capacity() {
+            i - self.capacity()
+        } else {
+            i
+        }
+    }
+
+    fn slot(&self, physical: usize) -> *mut T {
+        unsafe {
+            self.buf.as_mut_ptr().add(physical)
+        }
+    }
+
+    // Slot ranges of the two parts: from `head` up to the buffer end, and the wrapped rest
+    fn slice_ranges(&self) -> (Range<usize>, Range<usize>) {
+        let head_len = self.capacity() - self.head;
+        if self.len <= head_len {
+            (self.head..self.head + self.len, 0..0)
+        } else {
+            (self.head..self.capacity(), 0..self.len - head_len)
+        }
+    }
+
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), AllocError> {
+        let required = self.len.checked_add(additional)
+            .ok_or(AllocError::CapacityOverflow)?;
+        if required <= self.capacity() {
+            return Ok(());
+        }
+
+        let new_capacity = Doubling::grow(self.capacity(), required, mem::size_of::<T>());
+        self.try_grow_to(new_capacity)
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        if let Err(err) = self.try_reserve(additional) {
+            handle_alloc_error(err);
+        }
+    }
+
+    // Reallocates, then restores the ring: a wrapped tail would now be followed
+    // by new empty slots instead of the head
+    fn try_grow_to(&mut self, new_capacity: usize) -> Result<(), AllocError> {
+        let old_capacity = self.capacity();
+        self.buf.try_realloc(new_capacity)?;
+
+        let head_len = old_capacity - self.head;
+        if self.len <= head_len {
+            return Ok(());
+        }
+
+        let tail_len = self.len - head_len;
+        unsafe {
+            if tail_len <= head_len && new_capacity - old_capacity >= tail_len {
+                // D E A B C . . . . .  ->  . . A B C D E . . .   (the shorter tail follows the old end)
+                ptr::copy_nonoverlapping(self.slot(0), self.slot(old_capacity), tail_len);
+            } else {
+                // F G H A B . . .  ->  F G H . . . A B   (the head part moves to the new end)
+                let new_head = new_capacity - head_len;
+                ptr::copy(self.slot(self.head), self.slot(new_head), head_len);
+                self.head = new_head;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn push_back(&mut self, value: T) {
+        if self.len == self.capacity() {
+            self.reserve(1);
+        }
+        unsafe {
+            ptr::write(self.slot(self.to_physical(self.len)), value);
+        }
+        self.len += 1;
+    }
+
+    pub fn push_front(&mut self, value: T) {
+        if self.len == self.capacity() {
+            self.reserve(1);
+        }
+        self.head = self.to_physical(self.capacity() - 1);
+        unsafe {
+            ptr::write(self.slot(self.head), value);
+        }
+        self.len += 1;
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        let old_head = self.head;
+        self.head = self.to_physical(1);
+        self.len -= 1;
+        unsafe {
+            Some(ptr::read(self.slot(old_head)))
+        }
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        unsafe {
+            Some(ptr::read(self.slot(self.to_physical(self.len))))
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index < self.len {
+            unsafe {
+                Some(&*self.slot(self.to_physical(index)))
+            }
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index < self.len {
+            unsafe {
+                Some(&mut *self.slot(self.to_physical(index)))
+            }
+        } else {
+            None
+        }
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.get(self.len.wrapping_sub(1))
+    }
+
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.get_mut(0)
+    }
+
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.get_mut(self.len.wrapping_sub(1))
+    }
+
+    /// Panics if either index is out of bounds
+    pub fn swap(&mut self, i: usize, j: usize) {
+        assert!(i < self.len && j < self.len, "swap index out of bounds");
+        unsafe {
+            ptr::swap(self.slot(self.to_physical(i)), self.slot(self.to_physical(j)));
+        }
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        while self.len > len {
+            // Drops one by one from the back: a panicking drop leaves a valid Deque
+            self.pop_back();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.truncate(0);
+        self.head = 0;
+    }
+
+    /// The contents in order: the part from `head` to the buffer end, then the wrapped part
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let (a, b) = self.slice_ranges();
+        unsafe {
+            (
+                slice::from_raw_parts(self.slot(a.start), a.len()),
+                slice::from_raw_parts(self.slot(b.start), b.len()),
+            )
+        }
+    }
+
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        let (a, b) = self.slice_ranges();
+        unsafe {
+            (
+                slice::from_raw_parts_mut(self.slot(a.start), a.len()),
+                slice::from_raw_parts_mut(self.slot(b.start), b.len()),
+            )
+        }
+    }
+
+    /// Unwraps the ring in place so that all elements form one slice. O(n), no allocation.
+    pub fn make_contiguous(&mut self) -> &mut [T] {
+        let capacity = self.capacity();
+        let head_len = capacity - self.head;
+        if self.len > head_len {
+            let tail_len = self.len - head_len;
+            let free = capacity - self.len;
+            unsafe {
+                if free >= head_len {
+                    // F G . . . . . A B C  ->  A B C F G . . . . .
+                    ptr::copy(self.slot(0), self.slot(head_len), tail_len);
+                    ptr::copy_nonoverlapping(self.slot(self.head), self.slot(0), head_len);
+                    self.head = 0;
+                } else if free >= tail_len {
+                    // F . . . A B C D E  ->  . A B C D E F . .
+                    ptr::copy(self.slot(self.head), self.slot(tail_len), head_len);
+                    ptr::copy_nonoverlapping(self.slot(0), self.slot(self.len), tail_len);
+                    self.head = tail_len;
+                } else {
+                    // F G H I J . A B C D E  ->  F G H I J A B C D E .  ->  A B C D E F G H I J .
+                    ptr::copy(self.slot(self.head), self.slot(tail_len), head_len);
+                    slice::from_raw_parts_mut(self.slot(0), self.len).rotate_left(tail_len);
+                    self.head = 0;
+                }
+            }
+        }
+
+        unsafe {
+            slice::from_raw_parts_mut(self.slot(self.head), self.len)
+        }
+    }
+
+    /// Rotates so that the element at `n` becomes the front.
+    /// Moves `min(n, len - n)` elements. Panics if `n > len`.
+    pub fn rotate_left(&mut self, n: usize) {
+        assert!(n <= self.len, "rotate amount {} out of range for length {}", n, self.len);
+        if n <= self.len - n {
+            self.rotate_front_to_back(n);
+        } else {
+            self.rotate_back_to_front(self.len - n);
+        }
+    }
+
+    /// Rotates so that the element at `len - n` becomes the front. Panics if `n > len`.
+    pub fn rotate_right(&mut self, n: usize) {
+        assert!(n <= self.len, "rotate amount {} out of range for length {}", n, self.len);
+        if n <= self.len - n {
+            self.rotate_back_to_front(n);
+        } else {
+            self.rotate_front_to_back(self.len - n);
+        }
+    }
+
+    // The ring has at least one free slot, or is full and only `head` moves
+    fn rotate_front_to_back(&mut self, n: usize) {
+        for _ in 0..n {
+            let src = self.head;
+            let dst = self.to_physical(self.len);
+            unsafe {
+                ptr::copy(self.slot(src), self.slot(dst), 1);
+            }
+            self.head = self.to_physical(1);
+        }
+    }
+
+    fn rotate_back_to_front(&mut self, n: usize) {
+        for _ in 0..n {
+            let src = self.to_physical(self.len - 1);
+            self.head = self.to_physical(self.capacity() - 1);
+            unsafe {
+                ptr::copy(self.slot(src), self.slot(self.head), 1);
+            }
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        let (a, b) = self.as_slices();
+        Iter { a: a.iter(), b: b.iter() }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        let (a, b) = self.as_mut_slices();
+        IterMut { a: a.iter_mut(), b: b.iter_mut() }
+    }
+
+    // Splits the logical `range` across the two slices
+    fn split_range(&self, range: Range<usize>) -> (Range<usize>, Range<usize>) {
+        let a_len = self.as_slices().0.len();
+        (
+            range.start.min(a_len)..range.end.min(a_len),
+            range.start.max(a_len) - a_len..range.end.max(a_len) - a_len,
+        )
+    }
+
+    /// Iterates over `range` of logical indices. Panics if it's out of bounds.
+    pub fn range<R: RangeBounds<usize>>(&self, range: R) -> Iter<'_, T> {
+        let (ra, rb) = self.split_range(slice_range(range, self.len));
+        let (a, b) = self.as_slices();
+        Iter { a: a[ra].iter(), b: b[rb].iter() }
+    }
+
+    pub fn range_mut<R: RangeBounds<usize>>(&mut self, range: R) -> IterMut<'_, T> {
+        let (ra, rb) = self.split_range(slice_range(range, self.len));
+        let (a, b) = self.as_mut_slices();
+        IterMut { a: a[ra].iter_mut(), b: b[rb].iter_mut() }
+    }
+}
+
+// ======== ITERATORS ========
+
+/// Borrows elements front to back, created by `Deque::iter()` and `Deque::range()`
+#[derive(Clone, Debug)]
+pub struct Iter<'a, T> {
+    a: slice::Iter<'a, T>,
+    b: slice::Iter<'a, T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.a.next().or_else(|| self.b.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.a.len() + self.b.len();
+        (len, Some(len))
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.b.next_back().or_else(|| self.a.next_back())
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+/// Mutably borrows elements front to back, created by `Deque::iter_mut()` and `Deque::range_mut()`
+#[derive(Debug)]
+pub struct IterMut<'a, T> {
+    a: slice::IterMut<'a, T>,
+    b: slice::IterMut<'a, T>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        self.a.next().or_else(|| self.b.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.a.len() + self.b.len();
+        (len, Some(len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.b.next_back().or_else(|| self.a.next_back())
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+impl<T> FusedIterator for IterMut<'_, T> {}
+
+/// Moves elements out front to back, created by `Deque::into_iter()`
+pub struct IntoIter<T, A: Allocator = Global> {
+    inner: Deque<T, A>,
+}
+
+impl<T, A: Allocator> Iterator for IntoIter<T, A> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.inner.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.inner.len, Some(self.inner.len))
+    }
+}
+
+impl<T, A: Allocator> DoubleEndedIterator for IntoIter<T, A> {
+    fn next_back(&mut self) -> Option<T> {
+        self.inner.pop_back()
+    }
+}
+
+impl<T, A: Allocator> ExactSizeIterator for IntoIter<T, A> {}
+
+impl<T, A: Allocator> FusedIterator for IntoIter<T, A> {}
+
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for IntoIter<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IntoIter").field(&self.inner).finish()
+    }
+}
+
+impl<T, A: Allocator> IntoIterator for Deque<T, A> {
+    type Item = T;
+    type IntoIter = IntoIter<T, A>;
+
+    fn into_iter(self) -> IntoIter<T, A> {
+        IntoIter { inner: self }
+    }
+}
+
+impl<'a, T, A: Allocator> IntoIterator for &'a Deque<T, A> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T, A: Allocator> IntoIterator for &'a mut Deque<T, A> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+impl<T> FromIterator<T> for Deque<T> {
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> Self {
+        Self::from(iter.into_iter().collect::<Vector<T>>())
+    }
+}
+
+impl<T, A: Allocator> Extend<T> for Deque<T, A> {
+    fn extend<I: IntoIterator<Item=T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for elem in iter {
+            self.push_back(elem);
+        }
+    }
+}
+
+impl<'a, T: Copy + 'a, A: Allocator> Extend<&'a T> for Deque<T, A> {
+    fn extend<I: IntoIterator<Item=&'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+// ======== INDEX ========
+
+impl<T, A: Allocator> Index<usize> for Deque<T, A> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        self.get(index).expect("Out of bounds access")
+    }
+}
+
+impl<T, A: Allocator> IndexMut<usize> for Deque<T, A> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        self.get_mut(index).expect("Out of bounds access")
+    }
+}
+
+// ======== STD TRAITS ========
+
+impl<T> Default for Deque<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone, A: Allocator + Clone> Clone for Deque<T, A> {
+    fn clone(&self) -> Self {
+        let mut c = Self::with_capacity_in(self.len, self.allocator().clone());
+        c.extend(self.iter().cloned());
+        c
+    }
+}
+
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for Deque<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+// Compared element by element: the position of the ring in the buffer doesn't matter
+impl<T: PartialEq, A1: Allocator, A2: Allocator> PartialEq<Deque<T, A2>> for Deque<T, A1> {
+    fn eq(&self, other: &Deque<T, A2>) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq, A: Allocator> Eq for Deque<T, A> {}
+
+impl<T: PartialOrd, A: Allocator> PartialOrd for Deque<T, A> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord, A: Allocator> Ord for Deque<T, A> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Hash, A: Allocator> Hash for Deque<T, A> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len);
+        for elem in self.iter() {
+            elem.hash(state);
+        }
+    }
+}
+
+// ======== FROM & INTO ========
+
+/// O(1): the Vector's buffer becomes the ring, starting at slot 0
+impl<T, A: Allocator, G: GrowthPolicy> From<Vector<T, A, G>> for Deque<T, A> {
+    fn from(value: Vector<T, A, G>) -> Self {
+        let (buf, len) = value.into_chunks();
+        Self {
+            buf,
+            head: 0,
+            len,
+        }
+    }
+}
+
+/// O(1) if the ring already starts at slot 0, otherwise the elements are moved in place
+impl<T, A: Allocator> From<Deque<T, A>> for Vector<T, A> {
+    fn from(mut value: Deque<T, A>) -> Self {
+        value.make_contiguous();
+        if value.head != 0 {
+            unsafe {
+                ptr::copy(value.slot(value.head), value.slot(0), value.len);
+            }
+            value.head = 0;
+        }
+
+        // Elements now belong to the Vector: the Deque must not be dropped
+        let me = mem::ManuallyDrop::new(value);
+        unsafe {
+            Vector::from_chunks(ptr::read(&me.buf), me.len)
+        }
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for Deque<T> {
+    fn from(value: [T; N]) -> Self {
+        Self::from(Vector::from(value))
+    }
+}
+
+// ======== DROP ========
+
+impl<T, A: Allocator> Drop for Deque<T, A> {
+    fn drop(&mut self) {
+        // Drops the back slice even if dropping an element of the front one panics
+        struct Dropper<T>(*mut [T]);
+
+        impl<T> Drop for Dropper<T> {
+            fn drop(&mut self) {
+                unsafe {
+                    ptr::drop_in_place(self.0);
+                }
+            }
+        }
+
+        let (front, back) = self.as_mut_slices();
+        let (front, back): (*mut [T], *mut [T]) = (front, back);
+        let _back = Dropper(back);
+        unsafe {
+            ptr::drop_in_place(front);
+        }
+        // `buf` frees the memory
+    }
+}
@@ -0,0 +1,625 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::{FusedIterator, Zip};
+use std::mem;
+use std::ops::{Bound, Index, Range, RangeBounds};
+use std::ptr;
+use std::slice;
+
+use crate::allocator::{Allocator, Global};
+use crate::vector::{self, Vector};
+
+/// Map with keys kept in ascending order, stored in two parallel `Vector`s.
+///
+/// Binary search only touches the keys, so lookups in maps of a few hundred
+/// entries stay within a handful of cache lines. Inserts and removals shift the tails.
+pub struct FlatMap<K, V, A: Allocator = Global> {
+    keys: Vector<K, A>,
+    values: Vector<V, A>,
+}
+
+impl<K, V> FlatMap<K, V> {
+    // Constructor
+    pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+
+    // Constructor
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_in(capacity, Global)
+    }
+}
+
+impl<K, V, A: Allocator + Clone> FlatMap<K, V, A> {
+    // Constructor
+    pub fn new_in(alloc: A) -> Self {
+        Self {
+            keys: Vector::new_in(alloc.clone()),
+            values: Vector::new_in(alloc),
+        }
+    }
+
+    // Constructor
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        Self {
+            keys: Vector::with_capacity_in(capacity, alloc.clone()),
+            values: Vector::with_capacity_in(capacity, alloc),
+        }
+    }
+}
+
+impl<K, V, A: Allocator> FlatMap<K, V, A> {
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.keys.capacity().min(self.values.capacity())
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        self.keys.reserve(additional);
+        self.values.reserve(additional);
+    }
+
+    pub fn clear(&mut self) {
+        self.keys.clear();
+        self.values.clear();
+    }
+
+    /// All keys in ascending order
+    pub fn keys(&self) -> &[K] {
+        self.keys.as_slice()
+    }
+
+    /// Values in the order of their keys
+    pub fn values(&self) -> &[V] {
+        self.values.as_slice()
+    }
+
+    pub fn values_mut(&mut self) -> &mut [V] {
+        self.values.as_mut_slice()
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter { inner: self.keys.iter().zip(self.values.iter()) }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut { inner: self.keys.iter().zip(self.values.as_mut_slice().iter_mut()) }
+    }
+
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        self.iter().next()
+    }
+
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        self.iter().next_back()
+    }
+
+    pub fn pop_first(&mut self) -> Option<(K, V)> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.remove_at(0))
+        }
+    }
+
+    pub fn pop_last(&mut self) -> Option<(K, V)> {
+        Some((self.keys.pop()?, self.values.pop()?))
+    }
+
+    /// Keeps the entries for which `f` returns true, in order.
+    /// Compacts both Vectors in one pass; if `f` panics, the unvisited entries stay.
+    pub fn retain<F: FnMut(&K, &mut V) -> bool>(&mut self, mut f: F) {
+        let original_len = self.len();
+        // Advanced in lockstep, so keys and values close the same holes
+        let mut keys = self.keys.gap(0);
+        let mut values = self.values.gap(0);
+        let (key_base, value_base) = (keys.base, values.base);
+
+        while keys.read < original_len {
+            unsafe {
+                let (k, v) = (key_base.add(keys.read), value_base.add(keys.read));
+                if !f(&*k, &mut *v) {
+                    // Advance first: a panicking destructor must not see it dropped twice
+                    let entry = (ptr::read(k), ptr::read(v));
+                    keys.read += 1;
+                    values.read += 1;
+                    drop(entry);
+                } else {
+                    if keys.read != keys.write {
+                        ptr::copy_nonoverlapping(k, key_base.add(keys.write), 1);
+                        ptr::copy_nonoverlapping(v, value_base.add(values.write), 1);
+                    }
+                    keys.read += 1;
+                    values.read += 1;
+                    keys.write += 1;
+                    values.write += 1;
+                }
+            }
+        }
+    }
+
+    fn remove_at(&mut self, index: usize) -> (K, V) {
+        (self.keys.remove(index), self.values.remove(index))
+    }
+
+    // Both Vectors get room first, so that the two inserts can't fail halfway
+    fn insert_at(&mut self, index: usize, key: K, value: V) {
+        self.reserve(1);
+        self.keys.insert(index, key);
+        self.values.insert(index, value);
+    }
+}
+
+impl<K: Ord, V, A: Allocator> FlatMap<K, V, A> {
+    // Position of `key`, or where it would be inserted
+    fn search<Q: Ord + ?Sized>(&self, key: &Q) -> Result<usize, usize>
+    where
+        K: Borrow<Q>,
+    {
+        let index = self.keys.partition_point(|k| k.borrow() < key);
+        match self.keys.as_slice().get(index) {
+            Some(k) if k.borrow() == key => Ok(index),
+            _ => Err(index),
+        }
+    }
+
+    pub fn contains_key<Q: Ord + ?Sized>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.search(key).is_ok()
+    }
+
+    pub fn get<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+    {
+        self.search(key).ok().map(|index| &self.values[index])
+    }
+
+    pub fn get_mut<Q: Ord + ?Sized>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+    {
+        self.search(key).ok().map(|index| &mut self.values[index])
+    }
+
+    pub fn get_key_value<Q: Ord + ?Sized>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+    {
+        self.search(key).ok().map(|index| (&self.keys[index], &self.values[index]))
+    }
+
+    /// Returns the previous value of `key`. The stored key itself is not replaced.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self.search(&key) {
+            Ok(index) => Some(mem::replace(&mut self.values[index], value)),
+            Err(index) => {
+                self.insert_at(index, key, value);
+                None
+            }
+        }
+    }
+
+    pub fn remove<Q: Ord + ?Sized>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+    {
+        self.remove_entry(key).map(|(_, v)| v)
+    }
+
+    pub fn remove_entry<Q: Ord + ?Sized>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+    {
+        self.search(key).ok().map(|index| self.remove_at(index))
+    }
+
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, A> {
+        match self.search(&key) {
+            Ok(index) => Entry::Occupied(OccupiedEntry { map: self, index }),
+            Err(index) => Entry::Vacant(VacantEntry { map: self, index, key }),
+        }
+    }
+
+    // Index range of the keys within `range`. A reversed range is empty.
+    fn index_range<Q: Ord + ?Sized, R: RangeBounds<Q>>(&self, range: R) -> Range<usize>
+    where
+        K: Borrow<Q>,
+    {
+        let start = match range.start_bound() {
+            Bound::Included(s) => self.keys.partition_point(|k| k.borrow() < s),
+            Bound::Excluded(s) => self.keys.partition_point(|k| k.borrow() <= s),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(e) => self.keys.partition_point(|k| k.borrow() <= e),
+            Bound::Excluded(e) => self.keys.partition_point(|k| k.borrow() < e),
+            Bound::Unbounded => self.len(),
+        };
+        start..end.max(start)
+    }
+
+    /// Entries with keys within `range`, in key order
+    pub fn range<Q: Ord + ?Sized, R: RangeBounds<Q>>(&self, range: R) -> Iter<'_, K, V>
+    where
+        K: Borrow<Q>,
+    {
+        let r = self.index_range(range);
+        Iter { inner: self.keys.as_slice()[r.clone()].iter().zip(self.values.as_slice()[r].iter()) }
+    }
+
+    pub fn range_mut<Q: Ord + ?Sized, R: RangeBounds<Q>>(&mut self, range: R) -> IterMut<'_, K, V>
+    where
+        K: Borrow<Q>,
+    {
+        let r = self.index_range(range);
+        IterMut { inner: self.keys.as_slice()[r.clone()].iter().zip(self.values.as_mut_slice()[r].iter_mut()) }
+    }
+}
+
+impl<K: Ord, V> FlatMap<K, V> {
+    /// Builds a map from pairs in any order: sorts by key, and for repeated keys
+    /// the last value wins. O(n log n) instead of n shifting inserts.
+    pub fn from_unsorted(mut pairs: Vector<(K, V)>) -> Self {
+        pairs.sort_stable_by(|a, b| a.0.cmp(&b.0));
+
+        let mut map = Self::with_capacity(pairs.len());
+        for (k, v) in pairs {
+            match map.keys.as_slice().last() {
+                // Stable sort: the later pair came later in the input
+                Some(last) if *last == k => {
+                    *map.values.as_mut_slice().last_mut().unwrap() = v;
+                }
+                _ => {
+                    map.keys.push(k);
+                    map.values.push(v);
+                }
+            }
+        }
+        map
+    }
+}
+
+// ======== ENTRY ========
+
+/// A slot of the map, vacant or occupied, created by `FlatMap::entry()`
+pub enum Entry<'a, K, V, A: Allocator = Global> {
+    Occupied(OccupiedEntry<'a, K, V, A>),
+    Vacant(VacantEntry<'a, K, V, A>),
+}
+
+pub struct OccupiedEntry<'a, K, V, A: Allocator = Global> {
+    map: &'a mut FlatMap<K, V, A>,
+    index: usize,
+}
+
+/// Remembers the insertion point found by the search, so inserting doesn't search again
+pub struct VacantEntry<'a, K, V, A: Allocator = Global> {
+    map: &'a mut FlatMap<K, V, A>,
+    index: usize,
+    key: K,
+}
+
+impl<'a, K, V, A: Allocator> Entry<'a, K, V, A> {
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(e) => e.key(),
+            Entry::Vacant(e) => e.key(),
+        }
+    }
+
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        match self {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => e.insert(default),
+        }
+    }
+
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => e.insert(default()),
+        }
+    }
+
+    pub fn or_insert_with_key<F: FnOnce(&K) -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => {
+                let value = default(e.key());
+                e.insert(value)
+            }
+        }
+    }
+
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(e) = &mut self {
+            f(e.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, K, V, A: Allocator> OccupiedEntry<'a, K, V, A> {
+    pub fn key(&self) -> &K {
+        &self.map.keys[self.index]
+    }
+
+    pub fn get(&self) -> &V {
+        &self.map.values[self.index]
+    }
+
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.map.values[self.index]
+    }
+
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.map.values[self.index]
+    }
+
+    pub fn insert(&mut self, value: V) -> V {
+        mem::replace(self.get_mut(), value)
+    }
+
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+
+    pub fn remove_entry(self) -> (K, V) {
+        self.map.remove_at(self.index)
+    }
+}
+
+impl<'a, K, V, A: Allocator> VacantEntry<'a, K, V, A> {
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    pub fn insert(self, value: V) -> &'a mut V {
+        self.map.insert_at(self.index, self.key, value);
+        &mut self.map.values[self.index]
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug, A: Allocator> fmt::Debug for Entry<'_, K, V, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Entry::Occupied(e) => f.debug_tuple("Occupied")
+                .field(e.key())
+                .field(e.get())
+                .finish(),
+            Entry::Vacant(e) => f.debug_tuple("Vacant")
+                .field(e.key())
+                .finish(),
+        }
+    }
+}
+
+// ======== ITERATORS ========
+
+/// Entries in key order, created by `FlatMap::iter()` and `FlatMap::range()`
+#[derive(Clone, Debug)]
+pub struct Iter<'a, K, V> {
+    inner: Zip<slice::Iter<'a, K>, slice::Iter<'a, V>>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Iter<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
+
+/// Entries in key order with mutable values, created by `FlatMap::iter_mut()` and `FlatMap::range_mut()`
+#[derive(Debug)]
+pub struct IterMut<'a, K, V> {
+    inner: Zip<slice::Iter<'a, K>, slice::IterMut<'a, V>>,
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for IterMut<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl<K, V> ExactSizeIterator for IterMut<'_, K, V> {}
+
+impl<K, V> FusedIterator for IterMut<'_, K, V> {}
+
+/// Moves entries out in key order, created by `FlatMap::into_iter()`
+pub struct IntoIter<K, V, A: Allocator = Global> {
+    inner: Zip<vector::IntoIter<K, A>, vector::IntoIter<V, A>>,
+}
+
+impl<K, V, A: Allocator> Iterator for IntoIter<K, V, A> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V, A: Allocator> DoubleEndedIterator for IntoIter<K, V, A> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl<K, V, A: Allocator> ExactSizeIterator for IntoIter<K, V, A> {}
+
+impl<K, V, A: Allocator> FusedIterator for IntoIter<K, V, A> {}
+
+impl<K, V, A: Allocator> IntoIterator for FlatMap<K, V, A> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V, A>;
+
+    fn into_iter(self) -> IntoIter<K, V, A> {
+        IntoIter { inner: self.keys.into_iter().zip(self.values) }
+    }
+}
+
+impl<'a, K, V, A: Allocator> IntoIterator for &'a FlatMap<K, V, A> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Iter<'a, K, V> {
+        self.iter()
+    }
+}
+
+impl<'a, K, V, A: Allocator> IntoIterator for &'a mut FlatMap<K, V, A> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> IterMut<'a, K, V> {
+        self.iter_mut()
+    }
+}
+
+impl<K: Ord, V> FromIterator<(K, V)> for FlatMap<K, V> {
+    fn from_iter<I: IntoIterator<Item=(K, V)>>(iter: I) -> Self {
+        Self::from_unsorted(iter.into_iter().collect())
+    }
+}
+
+// Sorts the new pairs on their own, then merges both sorted sequences in one pass.
+// New values win over present ones, like repeated `insert`.
+impl<K: Ord, V> Extend<(K, V)> for FlatMap<K, V> {
+    fn extend<I: IntoIterator<Item=(K, V)>>(&mut self, iter: I) {
+        let new = Self::from_iter(iter);
+        if new.is_empty() {
+            return;
+        }
+
+        let old = mem::replace(self, Self::with_capacity(self.len() + new.len()));
+        let mut old = old.into_iter().peekable();
+        let mut new = new.into_iter().peekable();
+        loop {
+            let ordering = match (old.peek(), new.peek()) {
+                (Some(a), Some(b)) => a.0.cmp(&b.0),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => break,
+            };
+            let (k, v) = match ordering {
+                Ordering::Less => old.next().unwrap(),
+                Ordering::Greater => new.next().unwrap(),
+                Ordering::Equal => {
+                    let (k, _) = old.next().unwrap();
+                    (k, new.next().unwrap().1)
+                }
+            };
+            self.keys.push(k);
+            self.values.push(v);
+        }
+    }
+}
+
+// ======== STD TRAITS ========
+
+impl<K, V> Default for FlatMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Clone, V: Clone, A: Allocator + Clone> Clone for FlatMap<K, V, A> {
+    fn clone(&self) -> Self {
+        Self {
+            keys: self.keys.clone(),
+            values: self.values.clone(),
+        }
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug, A: Allocator> fmt::Debug for FlatMap<K, V, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: PartialEq, V: PartialEq, A1: Allocator, A2: Allocator> PartialEq<FlatMap<K, V, A2>> for FlatMap<K, V, A1> {
+    fn eq(&self, other: &FlatMap<K, V, A2>) -> bool {
+        self.keys() == other.keys() && self.values() == other.values()
+    }
+}
+
+impl<K: Eq, V: Eq, A: Allocator> Eq for FlatMap<K, V, A> {}
+
+impl<K: Hash, V: Hash, A: Allocator> Hash for FlatMap<K, V, A> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len());
+        for (k, v) in self.iter() {
+            k.hash(state);
+            v.hash(state);
+        }
+    }
+}
+
+/// Panics if the key is missing
+impl<K: Ord + Borrow<Q>, Q: Ord + ?Sized, V, A: Allocator> Index<&Q> for FlatMap<K, V, A> {
+    type Output = V;
+
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("key not found")
+    }
+}
+
+impl<K: Ord, V, const N: usize> From<[(K, V); N]> for FlatMap<K, V> {
+    fn from(value: [(K, V); N]) -> Self {
+        Self::from_unsorted(Vector::from(value))
+    }
+}
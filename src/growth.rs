@@ -0,0 +1,69 @@
+// Smallest non-zero capacity a growing container jumps to
+const MIN_CAPACITY: usize = 4;
+const PAGE_SIZE: usize = 4096;
+
+/// Decides how much memory a `Vector` requests once it runs out of capacity.
+///
+/// Policies are zero-sized marker types selected as a type parameter:
+/// `Vector<T, Global, OneAndHalf>`. Only geometric policies give amortized O(1) `push`.
+pub trait GrowthPolicy {
+    /// Returns the new capacity for elements of `elem_size` bytes.
+    /// Must be at least `required`, which is always greater than `capacity`.
+    fn grow(capacity: usize, required: usize, elem_size: usize) -> usize;
+}
+
+/// Doubles the capacity, like `std::vec::Vec`. The default policy.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Doubling;
+
+impl GrowthPolicy for Doubling {
+    fn grow(capacity: usize, required: usize, _elem_size: usize) -> usize {
+        required
+            .max(capacity.saturating_mul(2))
+            .max(MIN_CAPACITY)
+    }
+}
+
+/// Grows by half of the current capacity: less memory slack, more reallocations
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OneAndHalf;
+
+impl GrowthPolicy for OneAndHalf {
+    fn grow(capacity: usize, required: usize, _elem_size: usize) -> usize {
+        required
+            .max(capacity.saturating_add(capacity / 2))
+            .max(MIN_CAPACITY)
+    }
+}
+
+/// Grows by `STEP` elements at a time. Not amortized: N pushes copy O(N² / STEP) elements.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FixedIncrement<const STEP: usize>;
+
+impl<const STEP: usize> GrowthPolicy for FixedIncrement<STEP> {
+    fn grow(capacity: usize, required: usize, _elem_size: usize) -> usize {
+        required.max(capacity.saturating_add(STEP))
+    }
+}
+
+/// Doubles the capacity, then rounds the allocation up to whole 4 KiB pages
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PageRounded;
+
+impl GrowthPolicy for PageRounded {
+    fn grow(capacity: usize, required: usize, elem_size: usize) -> usize {
+        let new_capacity = Doubling::grow(capacity, required, elem_size);
+        if elem_size == 0 {
+            return new_capacity;
+        }
+
+        match new_capacity
+            .checked_mul(elem_size)
+            .and_then(|bytes| bytes.checked_next_multiple_of(PAGE_SIZE))
+        {
+            Some(bytes) => bytes / elem_size,
+            // Let the allocation itself report the overflow
+            None => new_capacity,
+        }
+    }
+}
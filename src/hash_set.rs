@@ -0,0 +1,488 @@
+use std::borrow::Borrow;
+use std::fmt;
+use std::hash::{BuildHasher, Hash, RandomState};
+use std::iter::{Chain, FusedIterator};
+
+use crate::allocator::{AllocError, Allocator, Global};
+use crate::hash_map::{self, HashMap};
+
+/// Set of unique elements in a `HashMap` with `()` values, which take no space
+pub struct HashSet<T, S = RandomState, A: Allocator = Global> {
+    map: HashMap<T, (), S, A>,
+}
+
+impl<T> HashSet<T> {
+    // Constructor
+    pub fn new() -> Self {
+        Self { map: HashMap::new() }
+    }
+
+    // Constructor
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self { map: HashMap::with_capacity(capacity) }
+    }
+}
+
+impl<T, S> HashSet<T, S> {
+    // Constructor
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self { map: HashMap::with_hasher(hash_builder) }
+    }
+
+    // Constructor
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Self {
+        Self { map: HashMap::with_capacity_and_hasher(capacity, hash_builder) }
+    }
+}
+
+impl<T, S, A: Allocator + Clone> HashSet<T, S, A> {
+    // Constructor
+    pub fn with_hasher_in(hash_builder: S, alloc: A) -> Self {
+        Self { map: HashMap::with_hasher_in(hash_builder, alloc) }
+    }
+
+    // Constructor
+    pub fn with_capacity_and_hasher_in(capacity: usize, hash_builder: S, alloc: A) -> Self {
+        Self { map: HashMap::with_capacity_and_hasher_in(capacity, hash_builder, alloc) }
+    }
+}
+
+impl<T, S, A: Allocator> HashSet<T, S, A> {
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.map.capacity()
+    }
+
+    pub fn hasher(&self) -> &S {
+        self.map.hasher()
+    }
+
+    pub fn allocator(&self) -> &A {
+        self.map.allocator()
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    /// Elements in no particular order
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { inner: self.map.keys() }
+    }
+
+    /// Keeps the elements for which `f` returns true
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut f: F) {
+        self.map.retain(|k, _| f(k));
+    }
+
+    /// Moves all elements out; the set keeps its memory
+    pub fn drain(&mut self) -> Drain<'_, T, A> {
+        Drain { inner: self.map.drain() }
+    }
+}
+
+impl<T: Eq + Hash, S: BuildHasher, A: Allocator + Clone> HashSet<T, S, A> {
+    pub fn reserve(&mut self, additional: usize) {
+        self.map.reserve(additional);
+    }
+
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), AllocError> {
+        self.map.try_reserve(additional)
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        self.map.shrink_to_fit();
+    }
+
+    /// Adds `value` if absent.
+    /// Returns false, keeping the old element, if an equal one is already present.
+    pub fn insert(&mut self, value: T) -> bool {
+        match self.map.entry(value) {
+            hash_map::Entry::Occupied(_) => false,
+            hash_map::Entry::Vacant(e) => {
+                e.insert(());
+                true
+            }
+        }
+    }
+
+    /// Adds `value`, returning the equal element it replaced
+    pub fn replace(&mut self, value: T) -> Option<T> {
+        match self.map.replace_key(value) {
+            Ok(old) => Some(old),
+            Err(value) => {
+                self.map.insert(value, ());
+                None
+            }
+        }
+    }
+
+    pub fn contains<Q: Hash + Eq + ?Sized>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+    {
+        self.map.contains_key(value)
+    }
+
+    /// The stored element equal to `value`
+    pub fn get<Q: Hash + Eq + ?Sized>(&self, value: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+    {
+        self.map.get_key_value(value).map(|(k, _)| k)
+    }
+
+    pub fn remove<Q: Hash + Eq + ?Sized>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+    {
+        self.map.remove(value).is_some()
+    }
+
+    /// Removes and returns the stored element equal to `value`
+    pub fn take<Q: Hash + Eq + ?Sized>(&mut self, value: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+    {
+        self.map.remove_entry(value).map(|(k, _)| k)
+    }
+
+    /// Elements of either set: all of `self`, then those only in `other`
+    pub fn union<'a, S2: BuildHasher, A2: Allocator + Clone>(
+        &'a self,
+        other: &'a HashSet<T, S2, A2>,
+    ) -> Union<'a, T, S, A> {
+        Union { inner: self.iter().chain(other.difference(self)) }
+    }
+
+    /// Elements of both sets, found by probing the larger one with the smaller
+    pub fn intersection<'a, S2: BuildHasher, A2: Allocator + Clone>(
+        &'a self,
+        other: &'a HashSet<T, S2, A2>,
+    ) -> Intersection<'a, T, S, A, S2, A2> {
+        if self.len() <= other.len() {
+            Intersection { inner: Probe::Smaller(self.iter(), other) }
+        } else {
+            Intersection { inner: Probe::Larger(other.iter(), self) }
+        }
+    }
+
+    /// Elements of `self` missing from `other`
+    pub fn difference<'a, S2: BuildHasher, A2: Allocator + Clone>(
+        &'a self,
+        other: &'a HashSet<T, S2, A2>,
+    ) -> Difference<'a, T, S2, A2> {
+        Difference { iter: self.iter(), other }
+    }
+
+    /// Elements in exactly one of the sets
+    pub fn symmetric_difference<'a, S2: BuildHasher, A2: Allocator + Clone>(
+        &'a self,
+        other: &'a HashSet<T, S2, A2>,
+    ) -> SymmetricDifference<'a, T, S, A, S2, A2> {
+        SymmetricDifference { inner: self.difference(other).chain(other.difference(self)) }
+    }
+
+    /// Every element of `self` is in `other`
+    pub fn is_subset<S2: BuildHasher, A2: Allocator + Clone>(&self, other: &HashSet<T, S2, A2>) -> bool {
+        self.len() <= other.len() && self.iter().all(|x| other.contains(x))
+    }
+
+    pub fn is_superset<S2: BuildHasher, A2: Allocator + Clone>(&self, other: &HashSet<T, S2, A2>) -> bool {
+        other.is_subset(self)
+    }
+
+    /// No element is in both sets
+    pub fn is_disjoint<S2: BuildHasher, A2: Allocator + Clone>(&self, other: &HashSet<T, S2, A2>) -> bool {
+        if self.len() <= other.len() {
+            self.iter().all(|x| !other.contains(x))
+        } else {
+            other.iter().all(|x| !self.contains(x))
+        }
+    }
+}
+
+// ======== ITERATORS ========
+
+/// Elements in no particular order, created by `HashSet::iter()`
+pub struct Iter<'a, T> {
+    inner: hash_map::Keys<'a, T, ()>,
+}
+
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Iter { inner: self.inner.clone() }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+impl<T: fmt::Debug> fmt::Debug for Iter<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+/// Moves elements out, created by `HashSet::into_iter()`
+pub struct IntoIter<T, A: Allocator = Global> {
+    inner: hash_map::IntoKeys<T, (), A>,
+}
+
+impl<T, A: Allocator> Iterator for IntoIter<T, A> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T, A: Allocator> ExactSizeIterator for IntoIter<T, A> {}
+
+impl<T, A: Allocator> FusedIterator for IntoIter<T, A> {}
+
+/// Moves elements out of a borrowed set, created by `HashSet::drain()`
+pub struct Drain<'a, T, A: Allocator = Global> {
+    inner: hash_map::Drain<'a, T, (), A>,
+}
+
+impl<T, A: Allocator> Iterator for Drain<'_, T, A> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.inner.next().map(|(k, ())| k)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T, A: Allocator> ExactSizeIterator for Drain<'_, T, A> {}
+
+impl<T, A: Allocator> FusedIterator for Drain<'_, T, A> {}
+
+/// Elements of `iter` missing from `other`, created by `HashSet::difference()`
+pub struct Difference<'a, T, S, A: Allocator = Global> {
+    iter: Iter<'a, T>,
+    other: &'a HashSet<T, S, A>,
+}
+
+impl<'a, T: Eq + Hash, S: BuildHasher, A: Allocator + Clone> Iterator for Difference<'a, T, S, A> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let other = self.other;
+        self.iter.find(|x| !other.contains(*x))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+impl<T: Eq + Hash, S: BuildHasher, A: Allocator + Clone> FusedIterator for Difference<'_, T, S, A> {}
+
+impl<T, S, A: Allocator> Clone for Difference<'_, T, S, A> {
+    fn clone(&self) -> Self {
+        Difference { iter: self.iter.clone(), other: self.other }
+    }
+}
+
+// Iterates one set and keeps what the other contains, whichever way round
+enum Probe<'a, T, S, A: Allocator, S2, A2: Allocator> {
+    Smaller(Iter<'a, T>, &'a HashSet<T, S2, A2>),
+    Larger(Iter<'a, T>, &'a HashSet<T, S, A>),
+}
+
+/// Elements of both sets, created by `HashSet::intersection()`
+pub struct Intersection<'a, T, S, A: Allocator, S2, A2: Allocator> {
+    inner: Probe<'a, T, S, A, S2, A2>,
+}
+
+impl<'a, T, S, A, S2, A2> Iterator for Intersection<'a, T, S, A, S2, A2>
+where
+    T: Eq + Hash,
+    S: BuildHasher,
+    A: Allocator + Clone,
+    S2: BuildHasher,
+    A2: Allocator + Clone,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        match &mut self.inner {
+            Probe::Smaller(iter, other) => {
+                let other = *other;
+                iter.find(|x| other.contains(*x))
+            }
+            Probe::Larger(iter, other) => {
+                let other = *other;
+                iter.find(|x| other.contains(*x))
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.inner {
+            Probe::Smaller(iter, _) | Probe::Larger(iter, _) => (0, iter.size_hint().1),
+        }
+    }
+}
+
+impl<T, S, A, S2, A2> FusedIterator for Intersection<'_, T, S, A, S2, A2>
+where
+    T: Eq + Hash,
+    S: BuildHasher,
+    A: Allocator + Clone,
+    S2: BuildHasher,
+    A2: Allocator + Clone,
+{}
+
+/// Elements of either set, created by `HashSet::union()`
+pub struct Union<'a, T, S, A: Allocator = Global> {
+    inner: Chain<Iter<'a, T>, Difference<'a, T, S, A>>,
+}
+
+impl<'a, T: Eq + Hash, S: BuildHasher, A: Allocator + Clone> Iterator for Union<'a, T, S, A> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T: Eq + Hash, S: BuildHasher, A: Allocator + Clone> FusedIterator for Union<'_, T, S, A> {}
+
+/// Elements in exactly one of the sets, created by `HashSet::symmetric_difference()`
+pub struct SymmetricDifference<'a, T, S, A: Allocator, S2, A2: Allocator> {
+    inner: Chain<Difference<'a, T, S2, A2>, Difference<'a, T, S, A>>,
+}
+
+impl<'a, T, S, A, S2, A2> Iterator for SymmetricDifference<'a, T, S, A, S2, A2>
+where
+    T: Eq + Hash,
+    S: BuildHasher,
+    A: Allocator + Clone,
+    S2: BuildHasher,
+    A2: Allocator + Clone,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T, S, A, S2, A2> FusedIterator for SymmetricDifference<'_, T, S, A, S2, A2>
+where
+    T: Eq + Hash,
+    S: BuildHasher,
+    A: Allocator + Clone,
+    S2: BuildHasher,
+    A2: Allocator + Clone,
+{}
+
+impl<T, S, A: Allocator> IntoIterator for HashSet<T, S, A> {
+    type Item = T;
+    type IntoIter = IntoIter<T, A>;
+
+    fn into_iter(self) -> IntoIter<T, A> {
+        IntoIter { inner: self.map.into_keys() }
+    }
+}
+
+impl<'a, T, S, A: Allocator> IntoIterator for &'a HashSet<T, S, A> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<T: Eq + Hash, S: BuildHasher + Default> FromIterator<T> for HashSet<T, S> {
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> Self {
+        let mut set = Self::with_hasher(S::default());
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T: Eq + Hash, S: BuildHasher, A: Allocator + Clone> Extend<T> for HashSet<T, S, A> {
+    fn extend<I: IntoIterator<Item=T>>(&mut self, iter: I) {
+        self.map.extend(iter.into_iter().map(|x| (x, ())));
+    }
+}
+
+impl<'a, T: Eq + Hash + Copy + 'a, S: BuildHasher, A: Allocator + Clone> Extend<&'a T> for HashSet<T, S, A> {
+    fn extend<I: IntoIterator<Item=&'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+// ======== STD TRAITS ========
+
+impl<T, S: Default> Default for HashSet<T, S> {
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
+impl<T: Clone, S: Clone, A: Allocator + Clone> Clone for HashSet<T, S, A> {
+    fn clone(&self) -> Self {
+        Self { map: self.map.clone() }
+    }
+}
+
+impl<T: fmt::Debug, S, A: Allocator> fmt::Debug for HashSet<T, S, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Eq + Hash, S: BuildHasher, A: Allocator + Clone> PartialEq for HashSet<T, S, A> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.is_subset(other)
+    }
+}
+
+impl<T: Eq + Hash, S: BuildHasher, A: Allocator + Clone> Eq for HashSet<T, S, A> {}
+
+impl<T: Eq + Hash, const N: usize> From<[T; N]> for HashSet<T> {
+    fn from(arr: [T; N]) -> Self {
+        Self::from_iter(arr)
+    }
+}
@@ -0,0 +1,878 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{BuildHasher, Hash, RandomState};
+use std::iter::FusedIterator;
+use std::mem;
+use std::ops::{Index, IndexMut};
+use std::slice;
+
+use crate::allocator::{AllocError, Allocator, Global};
+use crate::hash_map::make_hash;
+use crate::raw_table::RawTable;
+use crate::vector::{self, Vector};
+
+/// Hash map that remembers insertion order.
+///
+/// Entries sit densely in a `Vector` in the order they were inserted; a `RawTable`
+/// maps each key's hash to its position. Iteration is a slice walk and entries
+/// are reachable by position too.
+pub struct IndexMap<K, V, S = RandomState, A: Allocator = Global> {
+    entries: Vector<(K, V), A>,
+    indices: RawTable<usize, A>,
+    hash_builder: S,
+}
+
+impl<K, V> IndexMap<K, V> {
+    // Constructor
+    pub fn new() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+
+    // Constructor
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_and_hasher(capacity, RandomState::new())
+    }
+}
+
+impl<K, V, S> IndexMap<K, V, S> {
+    // Constructor
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self::with_hasher_in(hash_builder, Global)
+    }
+
+    // Constructor
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Self {
+        Self::with_capacity_and_hasher_in(capacity, hash_builder, Global)
+    }
+}
+
+impl<K, V, S, A: Allocator + Clone> IndexMap<K, V, S, A> {
+    // Constructor
+    pub fn with_hasher_in(hash_builder: S, alloc: A) -> Self {
+        Self {
+            entries: Vector::new_in(alloc.clone()),
+            indices: RawTable::new_in(alloc),
+            hash_builder,
+        }
+    }
+
+    // Constructor
+    pub fn with_capacity_and_hasher_in(capacity: usize, hash_builder: S, alloc: A) -> Self {
+        Self {
+            entries: Vector::with_capacity_in(capacity, alloc.clone()),
+            indices: RawTable::with_capacity_in(capacity, alloc),
+            hash_builder,
+        }
+    }
+}
+
+impl<K, V, S, A: Allocator> IndexMap<K, V, S, A> {
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.len() == 0
+    }
+
+    /// Entries held before either the entries or the index grow
+    pub fn capacity(&self) -> usize {
+        self.entries.capacity().min(self.indices.capacity())
+    }
+
+    pub fn hasher(&self) -> &S {
+        &self.hash_builder
+    }
+
+    pub fn allocator(&self) -> &A {
+        self.entries.allocator()
+    }
+
+    /// Removes all entries, keeps the memory
+    pub fn clear(&mut self) {
+        self.indices.clear();
+        self.entries.clear();
+    }
+
+    /// All entries in order
+    pub fn as_slice(&self) -> &[(K, V)] {
+        self.entries.as_slice()
+    }
+
+    /// Entries in order
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter { inner: self.entries.iter() }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut { inner: self.entries.as_mut_slice().iter_mut() }
+    }
+
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys { inner: self.iter() }
+    }
+
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values { inner: self.iter() }
+    }
+
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+        ValuesMut { inner: self.iter_mut() }
+    }
+
+    /// The entry at position `index` in insertion order
+    pub fn get_index(&self, index: usize) -> Option<(&K, &V)> {
+        self.entries.get(index).map(|(k, v)| (k, v))
+    }
+
+    pub fn get_index_mut(&mut self, index: usize) -> Option<(&K, &mut V)> {
+        self.entries.as_mut_slice().get_mut(index).map(|(k, v)| (&*k, v))
+    }
+
+    pub fn first(&self) -> Option<(&K, &V)> {
+        self.get_index(0)
+    }
+
+    pub fn last(&self) -> Option<(&K, &V)> {
+        self.len().checked_sub(1).and_then(|i| self.get_index(i))
+    }
+
+    /// Reverses the order of the entries without rehashing
+    pub fn reverse(&mut self) {
+        let last = self.len().wrapping_sub(1);
+        for index in self.indices.iter_mut() {
+            *index = last - *index;
+        }
+        self.entries.as_mut_slice().reverse();
+    }
+}
+
+// Bookkeeping between the entries and the index, which only needs to hash stored keys
+impl<K: Hash, V, S: BuildHasher, A: Allocator + Clone> IndexMap<K, V, S, A> {
+    fn hash_at(&self, index: usize) -> u64 {
+        make_hash(&self.hash_builder, &self.entries[index].0)
+    }
+
+    // Appends an entry whose key is known to be absent, returns its position
+    fn push_entry(&mut self, hash: u64, key: K, value: V) -> usize {
+        let index = self.entries.len();
+        // Room first, so that the push can't fail after the index has it
+        self.entries.reserve(1);
+        let (entries, hash_builder) = (&self.entries, &self.hash_builder);
+        self.indices.insert(hash, index, |&i| make_hash(hash_builder, &entries[i].0));
+        self.entries.push((key, value));
+        index
+    }
+
+    // Drops position `index` from the index, the entry stays
+    fn erase_index(&mut self, hash: u64, index: usize) {
+        let bucket = self.indices.find(hash, |&i| i == index).expect("entry missing from the index");
+        self.indices.take(bucket);
+    }
+
+    // Moves `from` to `to` in the index
+    fn update_index(&mut self, hash: u64, from: usize, to: usize) {
+        let bucket = self.indices.find(hash, |&i| i == from).expect("entry missing from the index");
+        unsafe {
+            *self.indices.bucket(bucket) = to;
+        }
+    }
+
+    fn swap_remove_at(&mut self, index: usize) -> (K, V) {
+        let last = self.len() - 1;
+        // Both hashes before any change: a panicking hasher leaves the map as it was
+        let hash = self.hash_at(index);
+        let last_hash = self.hash_at(last);
+        self.erase_index(hash, index);
+        if index != last {
+            self.update_index(last_hash, last, index);
+        }
+        self.entries.swap_remove(index)
+    }
+
+    fn shift_remove_at(&mut self, index: usize) -> (K, V) {
+        let hash = self.hash_at(index);
+        self.erase_index(hash, index);
+        for i in self.indices.iter_mut() {
+            if *i > index {
+                *i -= 1;
+            }
+        }
+        self.entries.remove(index)
+    }
+
+    // Runs `f` on the entries, then indexes them again from scratch, even if `f` panics
+    fn with_entries<R>(&mut self, f: impl FnOnce(&mut Vector<(K, V), A>) -> R) -> R {
+        struct Rebuild<'a, K: Hash, V, S: BuildHasher, A: Allocator + Clone>(&'a mut IndexMap<K, V, S, A>);
+
+        impl<K: Hash, V, S: BuildHasher, A: Allocator + Clone> Drop for Rebuild<'_, K, V, S, A> {
+            fn drop(&mut self) {
+                self.0.rebuild_indices();
+            }
+        }
+
+        let guard = Rebuild(self);
+        f(&mut guard.0.entries)
+    }
+
+    fn rebuild_indices(&mut self) {
+        // Drops the entries not indexed yet if the hasher panics
+        struct Truncate<'a, K, V, A: Allocator> {
+            entries: &'a mut Vector<(K, V), A>,
+            indexed: usize,
+        }
+
+        impl<K, V, A: Allocator> Drop for Truncate<'_, K, V, A> {
+            fn drop(&mut self) {
+                self.entries.truncate(self.indexed);
+            }
+        }
+
+        self.indices.clear();
+        let hash_builder = &self.hash_builder;
+        let mut guard = Truncate { entries: &mut self.entries, indexed: 0 };
+        while guard.indexed < guard.entries.len() {
+            let index = guard.indexed;
+            let hash = make_hash(hash_builder, &guard.entries[index].0);
+            let entries = &*guard.entries;
+            self.indices.insert(hash, index, |&i| make_hash(hash_builder, &entries[i].0));
+            guard.indexed += 1;
+        }
+        mem::forget(guard);
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher, A: Allocator + Clone> IndexMap<K, V, S, A> {
+    pub fn reserve(&mut self, additional: usize) {
+        self.entries.reserve(additional);
+        let (entries, hash_builder) = (&self.entries, &self.hash_builder);
+        self.indices.reserve(additional, |&i| make_hash(hash_builder, &entries[i].0));
+    }
+
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), AllocError> {
+        self.entries.try_reserve(additional)?;
+        let (entries, hash_builder) = (&self.entries, &self.hash_builder);
+        self.indices.try_reserve(additional, |&i| make_hash(hash_builder, &entries[i].0))
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        self.entries.shrink_to_fit();
+        let (entries, hash_builder) = (&self.entries, &self.hash_builder);
+        self.indices.shrink_to(0, |&i| make_hash(hash_builder, &entries[i].0));
+    }
+
+    /// Position of `key` in insertion order
+    pub fn get_index_of<Q: Hash + Eq + ?Sized>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+    {
+        if self.is_empty() {
+            return None;
+        }
+        let hash = make_hash(&self.hash_builder, key);
+        self.indices.get(hash, |&i| self.entries[i].0.borrow() == key).copied()
+    }
+
+    pub fn contains_key<Q: Hash + Eq + ?Sized>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.get_index_of(key).is_some()
+    }
+
+    pub fn get<Q: Hash + Eq + ?Sized>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+    {
+        self.get_index_of(key).map(|i| &self.entries[i].1)
+    }
+
+    pub fn get_key_value<Q: Hash + Eq + ?Sized>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+    {
+        self.get_index_of(key).and_then(|i| self.get_index(i))
+    }
+
+    /// Position, key and value of `key`
+    pub fn get_full<Q: Hash + Eq + ?Sized>(&self, key: &Q) -> Option<(usize, &K, &V)>
+    where
+        K: Borrow<Q>,
+    {
+        let index = self.get_index_of(key)?;
+        let (k, v) = &self.entries[index];
+        Some((index, k, v))
+    }
+
+    pub fn get_mut<Q: Hash + Eq + ?Sized>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+    {
+        let index = self.get_index_of(key)?;
+        Some(&mut self.entries[index].1)
+    }
+
+    /// Returns the previous value of `key`, which keeps its position.
+    /// A new key goes to the end.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.insert_full(key, value).1
+    }
+
+    /// Like `insert`, also returns the position of the entry
+    pub fn insert_full(&mut self, key: K, value: V) -> (usize, Option<V>) {
+        match self.entry(key) {
+            Entry::Occupied(mut e) => (e.index(), Some(e.insert(value))),
+            Entry::Vacant(e) => {
+                let index = e.index();
+                e.insert(value);
+                (index, None)
+            }
+        }
+    }
+
+    /// Removes `key` in O(1) by moving the last entry into its place
+    pub fn swap_remove<Q: Hash + Eq + ?Sized>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+    {
+        self.swap_remove_full(key).map(|(_, _, v)| v)
+    }
+
+    pub fn swap_remove_full<Q: Hash + Eq + ?Sized>(&mut self, key: &Q) -> Option<(usize, K, V)>
+    where
+        K: Borrow<Q>,
+    {
+        let index = self.get_index_of(key)?;
+        let (k, v) = self.swap_remove_at(index);
+        Some((index, k, v))
+    }
+
+    /// Removes `key` in O(n), keeping the order of the other entries
+    pub fn shift_remove<Q: Hash + Eq + ?Sized>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+    {
+        self.shift_remove_full(key).map(|(_, _, v)| v)
+    }
+
+    pub fn shift_remove_full<Q: Hash + Eq + ?Sized>(&mut self, key: &Q) -> Option<(usize, K, V)>
+    where
+        K: Borrow<Q>,
+    {
+        let index = self.get_index_of(key)?;
+        let (k, v) = self.shift_remove_at(index);
+        Some((index, k, v))
+    }
+
+    /// Removes the entry at `index`, moving the last entry into its place
+    pub fn swap_remove_index(&mut self, index: usize) -> Option<(K, V)> {
+        (index < self.len()).then(|| self.swap_remove_at(index))
+    }
+
+    /// Removes the entry at `index`, shifting the following ones down
+    pub fn shift_remove_index(&mut self, index: usize) -> Option<(K, V)> {
+        (index < self.len()).then(|| self.shift_remove_at(index))
+    }
+
+    /// Removes the last entry
+    pub fn pop(&mut self) -> Option<(K, V)> {
+        let last = self.len().checked_sub(1)?;
+        let hash = self.hash_at(last);
+        self.erase_index(hash, last);
+        self.entries.pop()
+    }
+
+    /// Keeps the entries for which `f` returns true, in order
+    pub fn retain<F: FnMut(&K, &mut V) -> bool>(&mut self, mut f: F) {
+        self.with_entries(|entries| entries.retain_mut(|(k, v)| f(k, v)));
+    }
+
+    /// Stable sort of the entries, the index follows
+    pub fn sort_by<F: FnMut(&K, &V, &K, &V) -> Ordering>(&mut self, mut cmp: F) {
+        self.with_entries(|entries| {
+            entries.as_mut_slice().sort_by(|a, b| cmp(&a.0, &a.1, &b.0, &b.1));
+        });
+    }
+
+    pub fn sort_keys(&mut self)
+    where
+        K: Ord,
+    {
+        self.sort_by(|k1, _, k2, _| k1.cmp(k2));
+    }
+
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, S, A> {
+        let hash = make_hash(&self.hash_builder, &key);
+        let found = self.indices.get(hash, |&i| self.entries[i].0 == key).copied();
+        match found {
+            Some(index) => Entry::Occupied(OccupiedEntry { map: self, index }),
+            None => Entry::Vacant(VacantEntry { map: self, hash, key }),
+        }
+    }
+}
+
+// ======== ENTRY ========
+
+/// A slot of the map, vacant or occupied, created by `IndexMap::entry()`
+pub enum Entry<'a, K, V, S, A: Allocator = Global> {
+    Occupied(OccupiedEntry<'a, K, V, S, A>),
+    Vacant(VacantEntry<'a, K, V, S, A>),
+}
+
+pub struct OccupiedEntry<'a, K, V, S, A: Allocator = Global> {
+    map: &'a mut IndexMap<K, V, S, A>,
+    index: usize,
+}
+
+/// Remembers the hash of the key, so inserting doesn't hash it again
+pub struct VacantEntry<'a, K, V, S, A: Allocator = Global> {
+    map: &'a mut IndexMap<K, V, S, A>,
+    hash: u64,
+    key: K,
+}
+
+impl<'a, K, V, S, A: Allocator> Entry<'a, K, V, S, A> {
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(e) => e.key(),
+            Entry::Vacant(e) => e.key(),
+        }
+    }
+
+    /// Position of the entry, or the one it gets when inserted
+    pub fn index(&self) -> usize {
+        match self {
+            Entry::Occupied(e) => e.index(),
+            Entry::Vacant(e) => e.index(),
+        }
+    }
+
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(e) = &mut self {
+            f(e.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, K: Hash, V, S: BuildHasher, A: Allocator + Clone> Entry<'a, K, V, S, A> {
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        match self {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => e.insert(default),
+        }
+    }
+
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => e.insert(default()),
+        }
+    }
+
+    pub fn or_insert_with_key<F: FnOnce(&K) -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => {
+                let value = default(e.key());
+                e.insert(value)
+            }
+        }
+    }
+
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+}
+
+impl<'a, K, V, S, A: Allocator> OccupiedEntry<'a, K, V, S, A> {
+    pub fn key(&self) -> &K {
+        &self.map.entries[self.index].0
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn get(&self) -> &V {
+        &self.map.entries[self.index].1
+    }
+
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.map.entries[self.index].1
+    }
+
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.map.entries[self.index].1
+    }
+
+    pub fn insert(&mut self, value: V) -> V {
+        mem::replace(self.get_mut(), value)
+    }
+}
+
+impl<'a, K: Hash, V, S: BuildHasher, A: Allocator + Clone> OccupiedEntry<'a, K, V, S, A> {
+    pub fn swap_remove(self) -> V {
+        self.swap_remove_entry().1
+    }
+
+    pub fn swap_remove_entry(self) -> (K, V) {
+        self.map.swap_remove_at(self.index)
+    }
+
+    pub fn shift_remove(self) -> V {
+        self.shift_remove_entry().1
+    }
+
+    pub fn shift_remove_entry(self) -> (K, V) {
+        self.map.shift_remove_at(self.index)
+    }
+}
+
+impl<'a, K, V, S, A: Allocator> VacantEntry<'a, K, V, S, A> {
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    pub fn index(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn into_key(self) -> K {
+        self.key
+    }
+}
+
+impl<'a, K: Hash, V, S: BuildHasher, A: Allocator + Clone> VacantEntry<'a, K, V, S, A> {
+    /// Appends the entry at the end of the order
+    pub fn insert(self, value: V) -> &'a mut V {
+        let index = self.map.push_entry(self.hash, self.key, value);
+        &mut self.map.entries[index].1
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug, S, A: Allocator> fmt::Debug for Entry<'_, K, V, S, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Entry::Occupied(e) => f.debug_tuple("Occupied")
+                .field(e.key())
+                .field(e.get())
+                .finish(),
+            Entry::Vacant(e) => f.debug_tuple("Vacant")
+                .field(e.key())
+                .finish(),
+        }
+    }
+}
+
+// ======== ITERATORS ========
+
+/// Entries in order, created by `IndexMap::iter()`
+#[derive(Debug)]
+pub struct Iter<'a, K, V> {
+    inner: slice::Iter<'a, (K, V)>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(k, v)| (k, v))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Iter<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(k, v)| (k, v))
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
+
+impl<K, V> Clone for Iter<'_, K, V> {
+    fn clone(&self) -> Self {
+        Iter { inner: self.inner.clone() }
+    }
+}
+
+/// Entries in order with mutable values, created by `IndexMap::iter_mut()`
+#[derive(Debug)]
+pub struct IterMut<'a, K, V> {
+    inner: slice::IterMut<'a, (K, V)>,
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(k, v)| (&*k, v))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for IterMut<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(k, v)| (&*k, v))
+    }
+}
+
+impl<K, V> ExactSizeIterator for IterMut<'_, K, V> {}
+
+impl<K, V> FusedIterator for IterMut<'_, K, V> {}
+
+/// Moves entries out in order, created by `IndexMap::into_iter()`
+pub struct IntoIter<K, V, A: Allocator = Global> {
+    inner: vector::IntoIter<(K, V), A>,
+}
+
+impl<K, V, A: Allocator> Iterator for IntoIter<K, V, A> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V, A: Allocator> DoubleEndedIterator for IntoIter<K, V, A> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl<K, V, A: Allocator> ExactSizeIterator for IntoIter<K, V, A> {}
+
+impl<K, V, A: Allocator> FusedIterator for IntoIter<K, V, A> {}
+
+/// Created by `IndexMap::keys()`
+pub struct Keys<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<&'a K> {
+        self.inner.next().map(|(k, _)| k)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Keys<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(k, _)| k)
+    }
+}
+
+impl<K, V> ExactSizeIterator for Keys<'_, K, V> {}
+
+impl<K, V> FusedIterator for Keys<'_, K, V> {}
+
+/// Created by `IndexMap::values()`
+pub struct Values<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<&'a V> {
+        self.inner.next().map(|(_, v)| v)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Values<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(_, v)| v)
+    }
+}
+
+impl<K, V> ExactSizeIterator for Values<'_, K, V> {}
+
+impl<K, V> FusedIterator for Values<'_, K, V> {}
+
+/// Created by `IndexMap::values_mut()`
+pub struct ValuesMut<'a, K, V> {
+    inner: IterMut<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for ValuesMut<'a, K, V> {
+    type Item = &'a mut V;
+
+    fn next(&mut self) -> Option<&'a mut V> {
+        self.inner.next().map(|(_, v)| v)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for ValuesMut<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(_, v)| v)
+    }
+}
+
+impl<K, V> ExactSizeIterator for ValuesMut<'_, K, V> {}
+
+impl<K, V> FusedIterator for ValuesMut<'_, K, V> {}
+
+impl<K, V, S, A: Allocator> IntoIterator for IndexMap<K, V, S, A> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V, A>;
+
+    fn into_iter(self) -> IntoIter<K, V, A> {
+        IntoIter { inner: self.entries.into_iter() }
+    }
+}
+
+impl<'a, K, V, S, A: Allocator> IntoIterator for &'a IndexMap<K, V, S, A> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Iter<'a, K, V> {
+        self.iter()
+    }
+}
+
+impl<'a, K, V, S, A: Allocator> IntoIterator for &'a mut IndexMap<K, V, S, A> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> IterMut<'a, K, V> {
+        self.iter_mut()
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher + Default> FromIterator<(K, V)> for IndexMap<K, V, S> {
+    fn from_iter<I: IntoIterator<Item=(K, V)>>(iter: I) -> Self {
+        let mut map = Self::with_hasher(S::default());
+        map.extend(iter);
+        map
+    }
+}
+
+// Repeated keys keep their first position and take the last value, like repeated `insert`
+impl<K: Eq + Hash, V, S: BuildHasher, A: Allocator + Clone> Extend<(K, V)> for IndexMap<K, V, S, A> {
+    fn extend<I: IntoIterator<Item=(K, V)>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        // Duplicate keys would over-reserve: only half the hint unless the map is empty
+        let hint = iter.size_hint().0;
+        self.reserve(if self.is_empty() { hint } else { hint.div_ceil(2) });
+        for (k, v) in iter {
+            self.insert(k, v);
+        }
+    }
+}
+
+impl<'a, K, V, S, A> Extend<(&'a K, &'a V)> for IndexMap<K, V, S, A>
+where
+    K: Eq + Hash + Copy + 'a,
+    V: Copy + 'a,
+    S: BuildHasher,
+    A: Allocator + Clone,
+{
+    fn extend<I: IntoIterator<Item=(&'a K, &'a V)>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().map(|(&k, &v)| (k, v)));
+    }
+}
+
+// ======== STD TRAITS ========
+
+impl<K, V, S: Default> Default for IndexMap<K, V, S> {
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
+impl<K: Clone, V: Clone, S: Clone, A: Allocator + Clone> Clone for IndexMap<K, V, S, A> {
+    fn clone(&self) -> Self {
+        Self {
+            entries: self.entries.clone(),
+            indices: self.indices.clone(),
+            hash_builder: self.hash_builder.clone(),
+        }
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug, S, A: Allocator> fmt::Debug for IndexMap<K, V, S, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// Equal when both hold the same entries, in any order.
+/// Compare `as_slice()` to take the order into account.
+impl<K, V, S, A> PartialEq for IndexMap<K, V, S, A>
+where
+    K: Eq + Hash,
+    V: PartialEq,
+    S: BuildHasher,
+    A: Allocator + Clone,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().all(|(k, v)| other.get(k) == Some(v))
+    }
+}
+
+impl<K: Eq + Hash, V: Eq, S: BuildHasher, A: Allocator + Clone> Eq for IndexMap<K, V, S, A> {}
+
+/// Panics if the key is missing
+impl<K, Q, V, S, A> Index<&Q> for IndexMap<K, V, S, A>
+where
+    K: Eq + Hash + Borrow<Q>,
+    Q: Eq + Hash + ?Sized,
+    S: BuildHasher,
+    A: Allocator + Clone,
+{
+    type Output = V;
+
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("key not found")
+    }
+}
+
+/// Value at a position, panics if out of bounds
+impl<K, V, S, A: Allocator> Index<usize> for IndexMap<K, V, S, A> {
+    type Output = V;
+
+    fn index(&self, index: usize) -> &V {
+        &self.entries[index].1
+    }
+}
+
+impl<K, V, S, A: Allocator> IndexMut<usize> for IndexMap<K, V, S, A> {
+    fn index_mut(&mut self, index: usize) -> &mut V {
+        &mut self.entries[index].1
+    }
+}
+
+impl<K: Eq + Hash, V, const N: usize> From<[(K, V); N]> for IndexMap<K, V> {
+    fn from(arr: [(K, V); N]) -> Self {
+        Self::from_iter(arr)
+    }
+}
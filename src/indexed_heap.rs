@@ -0,0 +1,296 @@
+use std::fmt;
+
+use crate::vector::Vector;
+
+// `positions` entry of a handle whose item was popped or removed
+const VACANT: usize = usize::MAX;
+
+/// Refers to an item pushed into an `IndexedHeap`.
+///
+/// Stays valid until the item leaves the heap. Slots of removed items are reused,
+/// a generation counter keeps old handles from reaching the new items.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Handle {
+    index: usize,
+    generation: usize,
+}
+
+struct Node<K, P> {
+    priority: P,
+    key: K,
+    // Index of the handle slot, to update `positions` while sifting
+    slot: usize,
+}
+
+/// Min-priority queue whose items can be found by `Handle`:
+/// `decrease_key`, `increase_key` and `remove` take O(log n), `contains` O(1).
+///
+/// `heap` is a binary heap ordered by priority, `positions[slot]` is where the
+/// item of a handle currently is in it. Every swap during a sift updates both.
+pub struct IndexedHeap<K, P> {
+    heap: Vector<Node<K, P>>,
+    positions: Vector<usize>,
+    generations: Vector<usize>,
+    // Slots of items no longer in the heap
+    free: Vector<usize>,
+}
+
+impl<K, P: Ord> IndexedHeap<K, P> {
+    // Constructor
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    // Constructor
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            heap: Vector::with_capacity(capacity),
+            positions: Vector::with_capacity(capacity),
+            generations: Vector::with_capacity(capacity),
+            free: Vector::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.len() == 0
+    }
+
+    pub fn clear(&mut self) {
+        for node in self.heap.as_slice() {
+            self.positions[node.slot] = VACANT;
+            self.free.push(node.slot);
+        }
+        self.heap.clear();
+    }
+
+    /// Adds `key` with `priority`, returns the handle to reach it later
+    pub fn push(&mut self, key: K, priority: P) -> Handle {
+        let pos = self.heap.len();
+        let slot = match self.free.pop() {
+            Some(slot) => {
+                self.generations[slot] = self.generations[slot].wrapping_add(1);
+                self.positions[slot] = pos;
+                slot
+            }
+            None => {
+                self.positions.push(pos);
+                self.generations.push(0);
+                self.positions.len() - 1
+            }
+        };
+
+        self.heap.push(Node { priority, key, slot });
+        self.sift_up(pos);
+        Handle { index: slot, generation: self.generations[slot] }
+    }
+
+    /// The item with the smallest priority
+    pub fn peek(&self) -> Option<(&K, &P)> {
+        self.heap.as_slice().first().map(|node| (&node.key, &node.priority))
+    }
+
+    pub fn peek_handle(&self) -> Option<Handle> {
+        self.heap.as_slice().first().map(|node| self.handle_of(node.slot))
+    }
+
+    /// Removes the item with the smallest priority
+    pub fn pop(&mut self) -> Option<(K, P)> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.remove_at(0))
+        }
+    }
+
+    /// The item is still in the heap
+    pub fn contains(&self, handle: Handle) -> bool {
+        self.position(handle).is_some()
+    }
+
+    pub fn get(&self, handle: Handle) -> Option<(&K, &P)> {
+        self.position(handle).map(|pos| {
+            let node = &self.heap[pos];
+            (&node.key, &node.priority)
+        })
+    }
+
+    /// The key may change freely, it doesn't affect the order
+    pub fn get_key_mut(&mut self, handle: Handle) -> Option<&mut K> {
+        self.position(handle).map(|pos| &mut self.heap[pos].key)
+    }
+
+    pub fn priority(&self, handle: Handle) -> Option<&P> {
+        self.get(handle).map(|(_, priority)| priority)
+    }
+
+    /// Lowers the priority of an item, returns the old one.
+    /// `None` if the item is gone. Panics if `priority` is greater than the current one.
+    pub fn decrease_key(&mut self, handle: Handle, priority: P) -> Option<P> {
+        let pos = self.position(handle)?;
+        assert!(priority <= self.heap[pos].priority, "decrease_key to a greater priority");
+        let old = std::mem::replace(&mut self.heap[pos].priority, priority);
+        self.sift_up(pos);
+        Some(old)
+    }
+
+    /// Raises the priority of an item, returns the old one.
+    /// `None` if the item is gone. Panics if `priority` is less than the current one.
+    pub fn increase_key(&mut self, handle: Handle, priority: P) -> Option<P> {
+        let pos = self.position(handle)?;
+        assert!(priority >= self.heap[pos].priority, "increase_key to a lesser priority");
+        let old = std::mem::replace(&mut self.heap[pos].priority, priority);
+        self.sift_down(pos);
+        Some(old)
+    }
+
+    /// Sets the priority of an item whichever way it moves, returns the old one
+    pub fn change_priority(&mut self, handle: Handle, priority: P) -> Option<P> {
+        let pos = self.position(handle)?;
+        let old = std::mem::replace(&mut self.heap[pos].priority, priority);
+        let pos = self.sift_up(pos);
+        self.sift_down(pos);
+        Some(old)
+    }
+
+    /// Takes the item out of the heap; the handle is invalid afterwards
+    pub fn remove(&mut self, handle: Handle) -> Option<(K, P)> {
+        let pos = self.position(handle)?;
+        Some(self.remove_at(pos))
+    }
+
+    /// Items in heap order: the smallest priority first, the rest unsorted
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (Handle, &K, &P)> + '_ {
+        self.heap
+            .as_slice()
+            .iter()
+            .map(|node| (self.handle_of(node.slot), &node.key, &node.priority))
+    }
+
+    /// Removes all items, the smallest priority first
+    pub fn into_sorted_vec(mut self) -> Vector<(K, P)> {
+        let mut sorted = Vector::with_capacity(self.len());
+        while let Some(item) = self.pop() {
+            sorted.push(item);
+        }
+        sorted
+    }
+
+    fn handle_of(&self, slot: usize) -> Handle {
+        Handle { index: slot, generation: self.generations[slot] }
+    }
+
+    fn position(&self, handle: Handle) -> Option<usize> {
+        let pos = *self.positions.as_slice().get(handle.index)?;
+        if pos == VACANT || self.generations[handle.index] != handle.generation {
+            None
+        } else {
+            Some(pos)
+        }
+    }
+
+    fn remove_at(&mut self, pos: usize) -> (K, P) {
+        let last = self.heap.len() - 1;
+        self.swap(pos, last);
+        let node = self.heap.pop().unwrap();
+        self.positions[node.slot] = VACANT;
+        self.free.push(node.slot);
+
+        // The former last item may belong further up or down
+        if pos < self.heap.len() {
+            let pos = self.sift_up(pos);
+            self.sift_down(pos);
+        }
+        (node.key, node.priority)
+    }
+
+    // ======== SIFTING ========
+
+    // Swaps two nodes and the positions of their handles
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.as_mut_slice().swap(a, b);
+        self.positions[self.heap[a].slot] = a;
+        self.positions[self.heap[b].slot] = b;
+    }
+
+    fn sift_up(&mut self, mut pos: usize) -> usize {
+        while pos > 0 {
+            let parent = (pos - 1) / 2;
+            if self.heap[parent].priority <= self.heap[pos].priority {
+                break;
+            }
+            self.swap(pos, parent);
+            pos = parent;
+        }
+        pos
+    }
+
+    fn sift_down(&mut self, mut pos: usize) {
+        let len = self.heap.len();
+        loop {
+            let mut child = 2 * pos + 1;
+            if child >= len {
+                return;
+            }
+            // The smaller of both children
+            if child + 1 < len && self.heap[child + 1].priority < self.heap[child].priority {
+                child += 1;
+            }
+            if self.heap[pos].priority <= self.heap[child].priority {
+                return;
+            }
+            self.swap(pos, child);
+            pos = child;
+        }
+    }
+}
+
+// ======== STD TRAITS ========
+
+impl<K, P: Ord> Default for IndexedHeap<K, P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Clone, P: Clone> Clone for IndexedHeap<K, P> {
+    fn clone(&self) -> Self {
+        let mut heap = Vector::with_capacity(self.heap.len());
+        for node in self.heap.as_slice() {
+            heap.push(Node { priority: node.priority.clone(), key: node.key.clone(), slot: node.slot });
+        }
+        Self {
+            heap,
+            positions: self.positions.clone(),
+            generations: self.generations.clone(),
+            free: self.free.clone(),
+        }
+    }
+}
+
+impl<K: fmt::Debug, P: fmt::Debug> fmt::Debug for IndexedHeap<K, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.heap.as_slice().iter().map(|node| (&node.key, &node.priority)))
+            .finish()
+    }
+}
+
+impl<K, P: Ord> Extend<(K, P)> for IndexedHeap<K, P> {
+    fn extend<I: IntoIterator<Item=(K, P)>>(&mut self, iter: I) {
+        for (key, priority) in iter {
+            self.push(key, priority);
+        }
+    }
+}
+
+impl<K, P: Ord> FromIterator<(K, P)> for IndexedHeap<K, P> {
+    fn from_iter<I: IntoIterator<Item=(K, P)>>(iter: I) -> Self {
+        let mut heap = Self::new();
+        heap.extend(iter);
+        heap
+    }
+}
@@ -1,7 +1,38 @@
-mod vector;
+mod allocator;
+mod growth;
+pub mod vector;
 mod chunks;
 mod bytes;
+pub mod sorted_vector;
+pub mod flat_map;
+pub mod deque;
+pub mod ring_buffer;
+pub mod spsc;
+pub mod mpmc;
+pub mod binary_heap;
+pub mod indexed_heap;
+mod raw_table;
+pub mod hash_map;
+pub mod hash_set;
+pub mod index_map;
+pub mod btree_map;
+pub mod btree_set;
+pub mod linked_list;
 
-pub use vector::{Vector, ConsecConstrucor};
+pub use allocator::{Allocator, AllocError, Global};
+pub use growth::{GrowthPolicy, Doubling, OneAndHalf, FixedIncrement, PageRounded};
+pub use vector::{Vector, ConsecConstrucor, RadixKey};
 pub use chunks::Chunks;
 pub use bytes::{Bins, Bytes, Hex, Bin, DebugBytes};
+pub use sorted_vector::SortedVector;
+pub use flat_map::FlatMap;
+pub use deque::Deque;
+pub use ring_buffer::{RingBuffer, HeapRingBuffer};
+pub use binary_heap::{BinaryHeap, MinHeap};
+pub use indexed_heap::IndexedHeap;
+pub use hash_map::HashMap;
+pub use hash_set::HashSet;
+pub use index_map::IndexMap;
+pub use btree_map::BTreeMap;
+pub use btree_set::BTreeSet;
+pub use linked_list::LinkedList;
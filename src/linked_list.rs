@@ -0,0 +1,719 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
+use std::mem;
+use std::ptr::{self, NonNull};
+
+use crate::allocator::{Allocator, Global, handle_alloc_error};
+use crate::chunks::{array_alloc, array_dealloc};
+
+/// Doubly linked list. Each element lives in its own node, allocated one at a
+/// time from the list's allocator, so elements never move and splicing whole
+/// lists in or out is O(1).
+///
+/// `CursorMut` edits the list in place around a position. Past the last element
+/// the cursor rests on a "ghost" position, which wraps around to the first one.
+pub struct LinkedList<T, A: Allocator = Global> {
+    head: Link<T>,
+    tail: Link<T>,
+    len: usize,
+    alloc: A,
+    marker: PhantomData<Node<T>>,
+}
+
+// None stands for either end
+type Link<T> = Option<NonNull<Node<T>>>;
+
+struct Node<T> {
+    prev: Link<T>,
+    next: Link<T>,
+    value: T,
+}
+
+// The list owns its nodes like a `Box` would
+unsafe impl<T: Send, A: Allocator + Send> Send for LinkedList<T, A> {}
+unsafe impl<T: Sync, A: Allocator + Sync> Sync for LinkedList<T, A> {}
+
+impl<T> LinkedList<T> {
+    // Constructor
+    pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+}
+
+impl<T, A: Allocator> LinkedList<T, A> {
+    // Constructor: no allocation until the first push
+    pub fn new_in(alloc: A) -> Self {
+        Self {
+            head: None,
+            tail: None,
+            len: 0,
+            alloc,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
+    fn alloc_node(&self, value: T) -> NonNull<Node<T>> {
+        let node = array_alloc::<Node<T>, A>(&self.alloc, 1).unwrap_or_else(|err| handle_alloc_error(err));
+        unsafe {
+            node.write(Node { prev: None, next: None, value });
+            NonNull::new_unchecked(node)
+        }
+    }
+
+    // Frees an unlinked node and hands out its value
+    unsafe fn free_node(&self, node: NonNull<Node<T>>) -> T {
+        let value = unsafe {
+            ptr::read(&(*node.as_ptr()).value)
+        };
+        array_dealloc(&self.alloc, node.as_ptr(), 1);
+        value
+    }
+
+    // Links `node` in between two neighbours, None standing for either end
+    unsafe fn link_between(&mut self, node: NonNull<Node<T>>, prev: Link<T>, next: Link<T>) {
+        unsafe {
+            (*node.as_ptr()).prev = prev;
+            (*node.as_ptr()).next = next;
+            match prev {
+                None => self.head = Some(node),
+                Some(prev) => (*prev.as_ptr()).next = Some(node),
+            }
+            match next {
+                None => self.tail = Some(node),
+                Some(next) => (*next.as_ptr()).prev = Some(node),
+            }
+        }
+        self.len += 1;
+    }
+
+    unsafe fn unlink(&mut self, node: NonNull<Node<T>>) {
+        unsafe {
+            let Node { prev, next, .. } = *node.as_ptr();
+            match prev {
+                None => self.head = next,
+                Some(prev) => (*prev.as_ptr()).next = next,
+            }
+            match next {
+                None => self.tail = prev,
+                Some(next) => (*next.as_ptr()).prev = prev,
+            }
+        }
+        self.len -= 1;
+    }
+
+    // Moves all elements of `other` in between two neighbours, leaving it empty.
+    // Its nodes are only relinked if `self` can free them, otherwise the elements
+    // move one by one into nodes of our own.
+    unsafe fn splice_between(&mut self, prev: Link<T>, next: Link<T>, other: &mut Self)
+    where
+        A: PartialEq,
+    {
+        let (Some(head), Some(tail)) = (other.head, other.tail) else {
+            return;
+        };
+        if self.alloc != other.alloc {
+            let mut prev = prev;
+            while let Some(value) = other.pop_front() {
+                let node = self.alloc_node(value);
+                unsafe {
+                    self.link_between(node, prev, next);
+                }
+                prev = Some(node);
+            }
+            return;
+        }
+        unsafe {
+            (*head.as_ptr()).prev = prev;
+            (*tail.as_ptr()).next = next;
+            match prev {
+                None => self.head = Some(head),
+                Some(prev) => (*prev.as_ptr()).next = Some(head),
+            }
+            match next {
+                None => self.tail = Some(tail),
+                Some(next) => (*next.as_ptr()).prev = Some(tail),
+            }
+        }
+        self.len += mem::replace(&mut other.len, 0);
+        other.head = None;
+        other.tail = None;
+    }
+
+    // Node at `index` < len, walked to from the nearer end
+    fn node_at(&self, index: usize) -> Link<T> {
+        unsafe {
+            if index < self.len / 2 {
+                let mut node = self.head;
+                for _ in 0..index {
+                    node = node.and_then(|node| (*node.as_ptr()).next);
+                }
+                node
+            } else {
+                let mut node = self.tail;
+                for _ in index + 1..self.len {
+                    node = node.and_then(|node| (*node.as_ptr()).prev);
+                }
+                node
+            }
+        }
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        unsafe {
+            self.head.map(|node| &(*node.as_ptr()).value)
+        }
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        unsafe {
+            self.tail.map(|node| &(*node.as_ptr()).value)
+        }
+    }
+
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        unsafe {
+            self.head.map(|node| &mut (*node.as_ptr()).value)
+        }
+    }
+
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        unsafe {
+            self.tail.map(|node| &mut (*node.as_ptr()).value)
+        }
+    }
+
+    pub fn push_front(&mut self, value: T) {
+        let node = self.alloc_node(value);
+        unsafe {
+            self.link_between(node, None, self.head);
+        }
+    }
+
+    pub fn push_back(&mut self, value: T) {
+        let node = self.alloc_node(value);
+        unsafe {
+            self.link_between(node, self.tail, None);
+        }
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        let node = self.head?;
+        unsafe {
+            self.unlink(node);
+            Some(self.free_node(node))
+        }
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        let node = self.tail?;
+        unsafe {
+            self.unlink(node);
+            Some(self.free_node(node))
+        }
+    }
+
+    /// Drops all elements, front to back. Keeps going if a destructor panics.
+    pub fn clear(&mut self) {
+        struct Guard<'a, T, A: Allocator>(&'a mut LinkedList<T, A>);
+
+        impl<T, A: Allocator> Drop for Guard<'_, T, A> {
+            fn drop(&mut self) {
+                while self.0.pop_front().is_some() {}
+            }
+        }
+
+        while let Some(value) = self.pop_front() {
+            let guard = Guard(self);
+            drop(value);
+            mem::forget(guard);
+        }
+    }
+
+    pub fn contains(&self, value: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.iter().any(|x| x == value)
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { head: self.head, tail: self.tail, len: self.len, marker: PhantomData }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut { head: self.head, tail: self.tail, len: self.len, marker: PhantomData }
+    }
+
+    /// Cursor on the first element, or on the ghost position if empty
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T, A> {
+        CursorMut { current: self.head, index: 0, list: self }
+    }
+
+    /// Cursor on the last element, or on the ghost position if empty
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T, A> {
+        CursorMut { current: self.tail, index: self.len.saturating_sub(1), list: self }
+    }
+}
+
+impl<T, A: Allocator + PartialEq> LinkedList<T, A> {
+    /// Moves all elements of `other` to the back, leaving it empty.
+    /// O(1) if the allocators are equal, otherwise O(len of `other`).
+    pub fn append(&mut self, other: &mut Self) {
+        unsafe {
+            self.splice_between(self.tail, None, other);
+        }
+    }
+}
+
+impl<T, A: Allocator + Clone> LinkedList<T, A> {
+    // Detaches the nodes after `node` (all of them if None), of which `kept` come up to it.
+    // They go to a list on a clone of our allocator, which can free them.
+    unsafe fn split_after_node(&mut self, node: Link<T>, kept: usize) -> Self {
+        let mut rest = Self::new_in(self.alloc.clone());
+        let rest_head = match node {
+            None => self.head,
+            Some(node) => unsafe { (*node.as_ptr()).next },
+        };
+        let Some(rest_head) = rest_head else {
+            return rest;
+        };
+        unsafe {
+            (*rest_head.as_ptr()).prev = None;
+            match node {
+                None => self.head = None,
+                Some(node) => (*node.as_ptr()).next = None,
+            }
+        }
+        rest.head = Some(rest_head);
+        rest.tail = mem::replace(&mut self.tail, node);
+        rest.len = self.len - kept;
+        self.len = kept;
+        rest
+    }
+
+    /// Moves the elements from `at` on into a new list.
+    /// Walks to `at` from the nearer end. Panics if `at > len`.
+    pub fn split_off(&mut self, at: usize) -> Self {
+        assert!(at <= self.len, "split index (is {}) should be <= len (is {})", at, self.len);
+        let node = match at {
+            0 => None,
+            _ => self.node_at(at - 1),
+        };
+        unsafe {
+            self.split_after_node(node, at)
+        }
+    }
+}
+
+// ======== CURSOR ========
+
+/// Position in a `LinkedList` that can edit around itself, created by
+/// `LinkedList::cursor_front_mut()` and `cursor_back_mut()`.
+///
+/// Besides the elements, it can rest on the ghost position between the back
+/// and the front, whose index is `len`.
+pub struct CursorMut<'a, T, A: Allocator = Global> {
+    // None on the ghost position
+    current: Link<T>,
+    index: usize,
+    list: &'a mut LinkedList<T, A>,
+}
+
+unsafe impl<T: Send, A: Allocator + Send> Send for CursorMut<'_, T, A> {}
+unsafe impl<T: Sync, A: Allocator + Sync> Sync for CursorMut<'_, T, A> {}
+
+impl<T, A: Allocator> CursorMut<'_, T, A> {
+    /// None on the ghost position
+    pub fn index(&self) -> Option<usize> {
+        self.current.is_some().then_some(self.index)
+    }
+
+    pub fn current(&mut self) -> Option<&mut T> {
+        unsafe {
+            self.current.map(|node| &mut (*node.as_ptr()).value)
+        }
+    }
+
+    fn next_node(&self) -> Link<T> {
+        match self.current {
+            None => self.list.head,
+            Some(node) => unsafe { (*node.as_ptr()).next },
+        }
+    }
+
+    fn prev_node(&self) -> Link<T> {
+        match self.current {
+            None => self.list.tail,
+            Some(node) => unsafe { (*node.as_ptr()).prev },
+        }
+    }
+
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        unsafe {
+            self.next_node().map(|node| &mut (*node.as_ptr()).value)
+        }
+    }
+
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        unsafe {
+            self.prev_node().map(|node| &mut (*node.as_ptr()).value)
+        }
+    }
+
+    /// Steps towards the back; from the last element onto the ghost, from the ghost onto the first
+    pub fn move_next(&mut self) {
+        self.index = if self.current.is_none() {
+            0
+        } else {
+            self.index + 1
+        };
+        self.current = self.next_node();
+    }
+
+    /// Steps towards the front; from the first element onto the ghost, from the ghost onto the last
+    pub fn move_prev(&mut self) {
+        self.index = if self.current.is_none() {
+            self.list.len.saturating_sub(1)
+        } else {
+            self.index.checked_sub(1).unwrap_or(self.list.len)
+        };
+        self.current = self.prev_node();
+    }
+
+    /// Inserts after the current element, or at the front on the ghost
+    pub fn insert_after(&mut self, value: T) {
+        let node = self.list.alloc_node(value);
+        let next = self.next_node();
+        unsafe {
+            self.list.link_between(node, self.current, next);
+        }
+        if self.current.is_none() {
+            self.index += 1;
+        }
+    }
+
+    /// Inserts before the current element, or at the back on the ghost
+    pub fn insert_before(&mut self, value: T) {
+        let node = self.list.alloc_node(value);
+        let prev = self.prev_node();
+        unsafe {
+            self.list.link_between(node, prev, self.current);
+        }
+        self.index += 1;
+    }
+
+    /// Removes the current element and moves onto the next one.
+    /// Does nothing on the ghost.
+    pub fn remove_current(&mut self) -> Option<T> {
+        let node = self.current?;
+        self.current = unsafe { (*node.as_ptr()).next };
+        unsafe {
+            self.list.unlink(node);
+            Some(self.list.free_node(node))
+        }
+    }
+}
+
+impl<T, A: Allocator + PartialEq> CursorMut<'_, T, A> {
+    /// Moves all elements of `other` after the current element, or to the front on the ghost
+    pub fn splice_after(&mut self, mut other: LinkedList<T, A>) {
+        let added = other.len;
+        let next = self.next_node();
+        unsafe {
+            self.list.splice_between(self.current, next, &mut other);
+        }
+        if self.current.is_none() {
+            self.index += added;
+        }
+    }
+
+    /// Moves all elements of `other` before the current element, or to the back on the ghost
+    pub fn splice_before(&mut self, mut other: LinkedList<T, A>) {
+        let added = other.len;
+        let prev = self.prev_node();
+        unsafe {
+            self.list.splice_between(prev, self.current, &mut other);
+        }
+        self.index += added;
+    }
+}
+
+impl<T, A: Allocator + Clone> CursorMut<'_, T, A> {
+    /// Moves the elements after the current one into a new list; all of them on the ghost
+    pub fn split_after(&mut self) -> LinkedList<T, A> {
+        let kept = if self.current.is_none() {
+            0
+        } else {
+            self.index + 1
+        };
+        if self.current.is_none() {
+            self.index = 0;
+        }
+        unsafe {
+            self.list.split_after_node(self.current, kept)
+        }
+    }
+
+    /// Moves the elements before the current one into a new list; all of them on the ghost.
+    /// The current element becomes the first.
+    pub fn split_before(&mut self) -> LinkedList<T, A> {
+        let rest = unsafe {
+            self.list.split_after_node(self.prev_node(), self.index)
+        };
+        self.index = 0;
+        mem::replace(self.list, rest)
+    }
+}
+
+// ======== ITERATORS ========
+
+/// Elements front to back, created by `LinkedList::iter()`
+pub struct Iter<'a, T> {
+    head: Link<T>,
+    tail: Link<T>,
+    len: usize,
+    marker: PhantomData<&'a Node<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        unsafe {
+            let node = &*self.head?.as_ptr();
+            self.head = node.next;
+            Some(&node.value)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        unsafe {
+            let node = &*self.tail?.as_ptr();
+            self.tail = node.prev;
+            Some(&node.value)
+        }
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+unsafe impl<T: Sync> Send for Iter<'_, T> {}
+unsafe impl<T: Sync> Sync for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Iter { head: self.head, tail: self.tail, len: self.len, marker: PhantomData }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Iter<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+/// Mutable elements front to back, created by `LinkedList::iter_mut()`
+pub struct IterMut<'a, T> {
+    head: Link<T>,
+    tail: Link<T>,
+    len: usize,
+    marker: PhantomData<&'a mut Node<T>>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        unsafe {
+            let node = &mut *self.head?.as_ptr();
+            self.head = node.next;
+            Some(&mut node.value)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        unsafe {
+            let node = &mut *self.tail?.as_ptr();
+            self.tail = node.prev;
+            Some(&mut node.value)
+        }
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+unsafe impl<T: Send> Send for IterMut<'_, T> {}
+unsafe impl<T: Sync> Sync for IterMut<'_, T> {}
+
+impl<T> FusedIterator for IterMut<'_, T> {}
+
+/// Moves elements out front to back, created by `LinkedList::into_iter()`
+pub struct IntoIter<T, A: Allocator = Global> {
+    list: LinkedList<T, A>,
+}
+
+impl<T, A: Allocator> Iterator for IntoIter<T, A> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.list.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.len, Some(self.list.len))
+    }
+}
+
+impl<T, A: Allocator> DoubleEndedIterator for IntoIter<T, A> {
+    fn next_back(&mut self) -> Option<T> {
+        self.list.pop_back()
+    }
+}
+
+impl<T, A: Allocator> ExactSizeIterator for IntoIter<T, A> {}
+
+impl<T, A: Allocator> FusedIterator for IntoIter<T, A> {}
+
+impl<T, A: Allocator> IntoIterator for LinkedList<T, A> {
+    type Item = T;
+    type IntoIter = IntoIter<T, A>;
+
+    fn into_iter(self) -> IntoIter<T, A> {
+        IntoIter { list: self }
+    }
+}
+
+impl<'a, T, A: Allocator> IntoIterator for &'a LinkedList<T, A> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T, A: Allocator> IntoIterator for &'a mut LinkedList<T, A> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+impl<T> FromIterator<T> for LinkedList<T> {
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T, A: Allocator> Extend<T> for LinkedList<T, A> {
+    fn extend<I: IntoIterator<Item=T>>(&mut self, iter: I) {
+        for x in iter {
+            self.push_back(x);
+        }
+    }
+}
+
+impl<'a, T: Copy + 'a, A: Allocator> Extend<&'a T> for LinkedList<T, A> {
+    fn extend<I: IntoIterator<Item=&'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+// ======== STD TRAITS ========
+
+impl<T, A: Allocator> Drop for LinkedList<T, A> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T> Default for LinkedList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone, A: Allocator + Clone> Clone for LinkedList<T, A> {
+    fn clone(&self) -> Self {
+        let mut list = Self::new_in(self.alloc.clone());
+        list.extend(self.iter().cloned());
+        list
+    }
+}
+
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for LinkedList<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq, A1: Allocator, A2: Allocator> PartialEq<LinkedList<T, A2>> for LinkedList<T, A1> {
+    fn eq(&self, other: &LinkedList<T, A2>) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq, A: Allocator> Eq for LinkedList<T, A> {}
+
+impl<T: Hash, A: Allocator> Hash for LinkedList<T, A> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len);
+        for x in self.iter() {
+            x.hash(state);
+        }
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for LinkedList<T> {
+    fn from(arr: [T; N]) -> Self {
+        Self::from_iter(arr)
+    }
+}
@@ -0,0 +1,588 @@
+//! Bounded multi-producer multi-consumer queue.
+//!
+//! `channel(capacity)` gives a `Sender` and a `Receiver`, both cloneable. Slots
+//! carry sequence stamps (Dmitry Vyukov's bounded queue), so `try_send` and
+//! `try_recv` are lock-free. Blocking `send` and `recv` spin briefly, then sleep
+//! on a `Condvar` until the other side makes progress or disconnects.
+
+use std::cell::UnsafeCell;
+use std::error::Error;
+use std::fmt;
+use std::hint;
+use std::mem::MaybeUninit;
+use std::ptr;
+use std::sync::atomic::{self, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+
+use crate::chunks::Chunks;
+use crate::spsc::CachePadded;
+
+// Attempts before a blocking call goes to sleep
+const SPIN_LIMIT: u32 = 64;
+
+// ======== ERRORS ========
+
+/// All receivers are gone; holds the value that could not be sent
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct SendError<T>(pub T);
+
+/// Why `try_send` failed; holds the value that could not be sent
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TrySendError<T> {
+    Full(T),
+    Disconnected(T),
+}
+
+/// All senders are gone and the queue is empty
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RecvError;
+
+/// Why `try_recv` failed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TryRecvError {
+    Empty,
+    Disconnected,
+}
+
+impl<T> SendError<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> TrySendError<T> {
+    pub fn into_inner(self) -> T {
+        match self {
+            TrySendError::Full(value) | TrySendError::Disconnected(value) => value,
+        }
+    }
+
+    pub fn is_full(&self) -> bool {
+        matches!(self, TrySendError::Full(_))
+    }
+
+    pub fn is_disconnected(&self) -> bool {
+        matches!(self, TrySendError::Disconnected(_))
+    }
+}
+
+// Values are not required to be Debug, same as for `std::sync::mpsc`
+impl<T> fmt::Debug for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SendError { .. }")
+    }
+}
+
+impl<T> fmt::Debug for TrySendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrySendError::Full(_) => f.write_str("Full(..)"),
+            TrySendError::Disconnected(_) => f.write_str("Disconnected(..)"),
+        }
+    }
+}
+
+impl<T> fmt::Display for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "sending on a disconnected channel")
+    }
+}
+
+impl<T> fmt::Display for TrySendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrySendError::Full(_) => write!(f, "sending on a full channel"),
+            TrySendError::Disconnected(_) => write!(f, "sending on a disconnected channel"),
+        }
+    }
+}
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "receiving on an empty and disconnected channel")
+    }
+}
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryRecvError::Empty => write!(f, "receiving on an empty channel"),
+            TryRecvError::Disconnected => write!(f, "receiving on an empty and disconnected channel"),
+        }
+    }
+}
+
+impl<T> Error for SendError<T> {}
+impl<T> Error for TrySendError<T> {}
+impl Error for RecvError {}
+impl Error for TryRecvError {}
+
+// ======== QUEUE ========
+
+struct Slot<T> {
+    // `pos` when the slot is free for the sender at `pos`,
+    // `pos + 1` once that value is written and waits for the receiver
+    stamp: AtomicUsize,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+// A position is `lap | index`: the index in the low bits, the number of wraps above them.
+// A lap spans a power of two, so the capacity itself needs not be one.
+struct Shared<T> {
+    // Next position to receive from
+    head: CachePadded<AtomicUsize>,
+    // Next position to send to
+    tail: CachePadded<AtomicUsize>,
+    slots: Chunks<Slot<T>>,
+    one_lap: usize,
+
+    senders: AtomicUsize,
+    receivers: AtomicUsize,
+    // Blocked threads sleep here; the counters let the fast path skip the lock
+    lock: Mutex<()>,
+    not_empty: Condvar,
+    not_full: Condvar,
+    waiting_receivers: AtomicUsize,
+    waiting_senders: AtomicUsize,
+}
+
+unsafe impl<T: Send> Send for Shared<T> {}
+unsafe impl<T: Send> Sync for Shared<T> {}
+
+impl<T> Shared<T> {
+    fn new(capacity: usize) -> Self {
+        let slots = Chunks::<Slot<T>>::alloc(capacity);
+        for i in 0..capacity {
+            unsafe {
+                ptr::write(slots.as_mut_ptr().add(i), Slot {
+                    stamp: AtomicUsize::new(i),
+                    value: UnsafeCell::new(MaybeUninit::uninit()),
+                });
+            }
+        }
+
+        Self {
+            head: CachePadded(AtomicUsize::new(0)),
+            tail: CachePadded(AtomicUsize::new(0)),
+            slots,
+            one_lap: (capacity + 1).next_power_of_two(),
+            senders: AtomicUsize::new(1),
+            receivers: AtomicUsize::new(1),
+            lock: Mutex::new(()),
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+            waiting_receivers: AtomicUsize::new(0),
+            waiting_senders: AtomicUsize::new(0),
+        }
+    }
+
+    fn capacity(&self) -> usize {
+        self.slots.count
+    }
+
+    fn slot(&self, pos: usize) -> &Slot<T> {
+        unsafe {
+            &*self.slots.as_ptr().add(pos & (self.one_lap - 1))
+        }
+    }
+
+    // Position after `pos`, moving to the next lap past the last slot
+    fn next(&self, pos: usize) -> usize {
+        if (pos & (self.one_lap - 1)) + 1 < self.capacity() {
+            pos + 1
+        } else {
+            (pos & !(self.one_lap - 1)).wrapping_add(self.one_lap)
+        }
+    }
+
+    fn try_push(&self, value: T) -> Result<(), T> {
+        let mut tail = self.tail.load(Ordering::Relaxed);
+        loop {
+            let slot = self.slot(tail);
+            let stamp = slot.stamp.load(Ordering::Acquire);
+
+            if stamp == tail {
+                // Free slot: claim it, then publish the value through the stamp
+                match self.tail.compare_exchange_weak(tail, self.next(tail), Ordering::SeqCst, Ordering::Relaxed) {
+                    Ok(_) => {
+                        unsafe {
+                            (*slot.value.get()).write(value);
+                        }
+                        slot.stamp.store(tail + 1, Ordering::Release);
+                        return Ok(());
+                    }
+                    Err(current) => tail = current,
+                }
+            } else if stamp.wrapping_add(self.one_lap) == tail + 1 {
+                // The slot still holds a value from the previous lap
+                atomic::fence(Ordering::SeqCst);
+                let head = self.head.load(Ordering::Relaxed);
+                if head.wrapping_add(self.one_lap) == tail {
+                    return Err(value);
+                }
+                tail = self.tail.load(Ordering::Relaxed);
+            } else {
+                // Another sender is ahead of us
+                hint::spin_loop();
+                tail = self.tail.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    fn try_pop(&self) -> Option<T> {
+        let mut head = self.head.load(Ordering::Relaxed);
+        loop {
+            let slot = self.slot(head);
+            let stamp = slot.stamp.load(Ordering::Acquire);
+
+            if stamp == head + 1 {
+                // Written slot: claim it, then free it for the sender one lap later
+                match self.head.compare_exchange_weak(head, self.next(head), Ordering::SeqCst, Ordering::Relaxed) {
+                    Ok(_) => {
+                        let value = unsafe {
+                            (*slot.value.get()).assume_init_read()
+                        };
+                        slot.stamp.store(head.wrapping_add(self.one_lap), Ordering::Release);
+                        return Some(value);
+                    }
+                    Err(current) => head = current,
+                }
+            } else if stamp == head {
+                // Nothing written here yet
+                atomic::fence(Ordering::SeqCst);
+                let tail = self.tail.load(Ordering::Relaxed);
+                if tail == head {
+                    return None;
+                }
+                head = self.head.load(Ordering::Relaxed);
+            } else {
+                // Another receiver is ahead of us
+                hint::spin_loop();
+                head = self.head.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        loop {
+            let tail = self.tail.load(Ordering::SeqCst);
+            let head = self.head.load(Ordering::SeqCst);
+            // A consistent snapshot: `tail` did not move while `head` was read
+            if self.tail.load(Ordering::SeqCst) == tail {
+                let mask = self.one_lap - 1;
+                let (hix, tix) = (head & mask, tail & mask);
+                return if hix < tix {
+                    tix - hix
+                } else if hix > tix {
+                    self.capacity() - hix + tix
+                } else if tail == head {
+                    0
+                } else {
+                    self.capacity()
+                };
+            }
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.tail.load(Ordering::SeqCst) == self.head.load(Ordering::SeqCst)
+    }
+
+    fn is_full(&self) -> bool {
+        let tail = self.tail.load(Ordering::SeqCst);
+        self.head.load(Ordering::SeqCst).wrapping_add(self.one_lap) == tail
+    }
+
+    // Wakes one thread sleeping on `cvar`, if any
+    fn notify(&self, waiting: &AtomicUsize, cvar: &Condvar) {
+        if waiting.load(Ordering::SeqCst) > 0 {
+            let _guard = self.lock.lock().unwrap();
+            cvar.notify_one();
+        }
+    }
+
+    // Sleeps on `cvar` while `blocked()` holds; checked under the lock,
+    // so a `notify` from the other side cannot slip in between
+    fn wait(&self, waiting: &AtomicUsize, cvar: &Condvar, blocked: impl Fn() -> bool) {
+        let mut guard = self.lock.lock().unwrap();
+        waiting.fetch_add(1, Ordering::SeqCst);
+        while blocked() {
+            guard = cvar.wait(guard).unwrap();
+        }
+        waiting.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    fn disconnected(&self, cvar: &Condvar) {
+        let _guard = self.lock.lock().unwrap();
+        cvar.notify_all();
+    }
+}
+
+impl<T> Drop for Shared<T> {
+    fn drop(&mut self) {
+        let mut head = *self.head.0.get_mut();
+        let tail = *self.tail.0.get_mut();
+        while head != tail {
+            unsafe {
+                (*self.slot(head).value.get()).assume_init_drop();
+            }
+            head = self.next(head);
+        }
+        // Slots own nothing else, `Chunks` frees the memory
+    }
+}
+
+/// Creates a queue holding up to `capacity` elements. Panics if `capacity` is 0.
+pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(capacity > 0, "mpmc capacity must be non-zero");
+    let shared = Arc::new(Shared::new(capacity));
+    (Sender { shared: shared.clone() }, Receiver { shared })
+}
+
+// ======== SENDER ========
+
+/// Sending end of the queue; clone it for more producers
+pub struct Sender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Sender<T> {
+    /// Appends `value` if there is room right now
+    pub fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        if self.shared.receivers.load(Ordering::SeqCst) == 0 {
+            return Err(TrySendError::Disconnected(value));
+        }
+        match self.shared.try_push(value) {
+            Ok(()) => {
+                self.shared.notify(&self.shared.waiting_receivers, &self.shared.not_empty);
+                Ok(())
+            }
+            Err(value) => Err(TrySendError::Full(value)),
+        }
+    }
+
+    /// Appends `value`, waiting for room. Fails only when all receivers are gone.
+    pub fn send(&self, mut value: T) -> Result<(), SendError<T>> {
+        let shared = &self.shared;
+        let mut spins = 0;
+        loop {
+            match self.try_send(value) {
+                Ok(()) => return Ok(()),
+                Err(TrySendError::Disconnected(v)) => return Err(SendError(v)),
+                Err(TrySendError::Full(v)) => value = v,
+            }
+
+            if spins < SPIN_LIMIT {
+                spins += 1;
+                hint::spin_loop();
+            } else {
+                shared.wait(&shared.waiting_senders, &shared.not_full, || {
+                    shared.is_full() && shared.receivers.load(Ordering::SeqCst) > 0
+                });
+            }
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity()
+    }
+
+    /// Elements in the queue at some point during the call
+    pub fn len(&self) -> usize {
+        self.shared.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shared.is_empty()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.shared.is_full()
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.shared.senders.fetch_add(1, Ordering::SeqCst);
+        Self { shared: self.shared.clone() }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        if self.shared.senders.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.shared.disconnected(&self.shared.not_empty);
+        }
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender")
+            .field("len", &self.len())
+            .field("capacity", &self.capacity())
+            .finish()
+    }
+}
+
+// ======== RECEIVER ========
+
+/// Receiving end of the queue; clone it for more consumers
+pub struct Receiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Receiver<T> {
+    /// Removes the oldest element if there is one right now
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        if let Some(value) = self.shared.try_pop() {
+            self.shared.notify(&self.shared.waiting_senders, &self.shared.not_full);
+            return Ok(value);
+        }
+
+        if self.shared.senders.load(Ordering::SeqCst) > 0 {
+            return Err(TryRecvError::Empty);
+        }
+        // The last sender may have pushed right before leaving
+        match self.shared.try_pop() {
+            Some(value) => Ok(value),
+            None => Err(TryRecvError::Disconnected),
+        }
+    }
+
+    /// Removes the oldest element, waiting for one.
+    /// Fails only when the queue is empty and all senders are gone.
+    pub fn recv(&self) -> Result<T, RecvError> {
+        let shared = &self.shared;
+        let mut spins = 0;
+        loop {
+            match self.try_recv() {
+                Ok(value) => return Ok(value),
+                Err(TryRecvError::Disconnected) => return Err(RecvError),
+                Err(TryRecvError::Empty) => {}
+            }
+
+            if spins < SPIN_LIMIT {
+                spins += 1;
+                hint::spin_loop();
+            } else {
+                shared.wait(&shared.waiting_receivers, &shared.not_empty, || {
+                    shared.is_empty() && shared.senders.load(Ordering::SeqCst) > 0
+                });
+            }
+        }
+    }
+
+    /// Blocking iterator, ends once the queue is empty and all senders are gone
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { receiver: self }
+    }
+
+    /// Drains what is in the queue right now, without blocking
+    pub fn try_iter(&self) -> TryIter<'_, T> {
+        TryIter { receiver: self }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity()
+    }
+
+    /// Elements in the queue at some point during the call
+    pub fn len(&self) -> usize {
+        self.shared.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shared.is_empty()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.shared.is_full()
+    }
+}
+
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Self {
+        self.shared.receivers.fetch_add(1, Ordering::SeqCst);
+        Self { shared: self.shared.clone() }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        if self.shared.receivers.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.shared.disconnected(&self.shared.not_full);
+        }
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver")
+            .field("len", &self.len())
+            .field("capacity", &self.capacity())
+            .finish()
+    }
+}
+
+// ======== ITERATORS ========
+
+/// Created by `Receiver::iter()`
+#[derive(Debug)]
+pub struct Iter<'a, T> {
+    receiver: &'a Receiver<T>,
+}
+
+impl<T> Iterator for Iter<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.receiver.recv().ok()
+    }
+}
+
+/// Created by `Receiver::try_iter()`
+#[derive(Debug)]
+pub struct TryIter<'a, T> {
+    receiver: &'a Receiver<T>,
+}
+
+impl<T> Iterator for TryIter<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+/// Blocking iterator owning the receiver
+#[derive(Debug)]
+pub struct IntoIter<T> {
+    receiver: Receiver<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.receiver.recv().ok()
+    }
+}
+
+impl<T> IntoIterator for Receiver<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { receiver: self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Receiver<T> {
+    type Item = T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
@@ -0,0 +1,667 @@
+//! Open-addressing hash table in the SwissTable layout, shared by the hashed containers.
+//!
+//! Each bucket has a control byte: `EMPTY`, `DELETED` or, for a full bucket,
+//! the top 7 bits of the element's hash (`h2`). Lookups compare a whole group
+//! of control bytes against `h2` at once and only look at the slots that match.
+//! The table stores elements and their positions only: hashing and equality
+//! are passed in by the caller.
+
+use std::marker::PhantomData;
+use std::mem;
+use std::ptr;
+
+use crate::allocator::{AllocError, Allocator, Global, handle_alloc_error};
+use crate::chunks::Chunks;
+
+const EMPTY: u8 = 0b1111_1111;
+const DELETED: u8 = 0b1000_0000;
+
+// ======== GROUP ========
+
+// Control bytes probed at once, as one little-endian u64: byte `i` is bits `8i..8i + 8`
+const GROUP_WIDTH: usize = 8;
+
+const fn repeat(byte: u8) -> u64 {
+    u64::from_ne_bytes([byte; GROUP_WIDTH])
+}
+
+#[derive(Clone, Copy)]
+struct Group(u64);
+
+impl Group {
+    // # Safety: `ptr` must be valid for reading GROUP_WIDTH bytes
+    unsafe fn load(ptr: *const u8) -> Self {
+        unsafe {
+            Group(u64::from_le(ptr::read_unaligned(ptr as *const u64)))
+        }
+    }
+
+    // Bytes equal to `byte`. May also report a byte just above a real match,
+    // callers compare the elements anyway.
+    fn match_byte(self, byte: u8) -> BitMask {
+        let cmp = self.0 ^ repeat(byte);
+        BitMask(cmp.wrapping_sub(repeat(0x01)) & !cmp & repeat(0x80))
+    }
+
+    fn match_empty(self) -> BitMask {
+        // Only EMPTY has both of its top bits set
+        BitMask(self.0 & (self.0 << 1) & repeat(0x80))
+    }
+
+    fn match_empty_or_deleted(self) -> BitMask {
+        BitMask(self.0 & repeat(0x80))
+    }
+
+    fn match_full(self) -> BitMask {
+        BitMask(!self.0 & repeat(0x80))
+    }
+}
+
+// Top bit of each matching byte
+#[derive(Clone, Copy)]
+struct BitMask(u64);
+
+impl BitMask {
+    fn any(self) -> bool {
+        self.0 != 0
+    }
+
+    fn lowest(self) -> Option<usize> {
+        if self.0 == 0 {
+            None
+        } else {
+            Some(self.0.trailing_zeros() as usize / 8)
+        }
+    }
+
+    fn without_lowest(self) -> Self {
+        BitMask(self.0 & (self.0 - 1))
+    }
+
+    // Non-matching bytes at the start and at the end of the group
+    fn leading_bytes(self) -> usize {
+        self.0.leading_zeros() as usize / 8
+    }
+
+    fn trailing_bytes(self) -> usize {
+        self.0.trailing_zeros() as usize / 8
+    }
+}
+
+impl Iterator for BitMask {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let bit = self.lowest()?;
+        *self = self.without_lowest();
+        Some(bit)
+    }
+}
+
+// ======== HASH BITS ========
+
+// Position to start probing at
+fn h1(hash: u64) -> usize {
+    hash as usize
+}
+
+// Stored in the control byte, the top bit stays clear for full buckets
+fn h2(hash: u64) -> u8 {
+    (hash >> (64 - 7)) as u8
+}
+
+fn is_full(ctrl: u8) -> bool {
+    ctrl & 0x80 == 0
+}
+
+// Elements a table of `bucket_mask + 1` buckets takes before it grows: 7/8 of it,
+// and one bucket less for tiny tables, so that a probe always meets an EMPTY byte
+fn bucket_mask_to_capacity(bucket_mask: usize) -> usize {
+    if bucket_mask < 8 {
+        bucket_mask
+    } else {
+        (bucket_mask + 1) / 8 * 7
+    }
+}
+
+fn capacity_to_buckets(capacity: usize) -> Result<usize, AllocError> {
+    if capacity < 4 {
+        return Ok(4);
+    }
+    if capacity < 8 {
+        return Ok(8);
+    }
+    let adjusted = capacity.checked_mul(8).ok_or(AllocError::CapacityOverflow)? / 7;
+    adjusted.checked_next_power_of_two().ok_or(AllocError::CapacityOverflow)
+}
+
+// ======== RAW TABLE ========
+
+/// Buckets of `T` plus their control bytes, both allocated through `Chunks`.
+///
+/// `ctrl` has `GROUP_WIDTH` bytes more than there are buckets: a group load starting
+/// at any bucket stays in bounds, and the extra bytes mirror the first group.
+pub(crate) struct RawTable<T, A: Allocator = Global> {
+    ctrl: Chunks<u8, true, A>,
+    slots: Chunks<T, true, A>,
+    // Buckets - 1, the number of buckets is a power of two
+    bucket_mask: usize,
+    items: usize,
+    // Inserts into EMPTY buckets left before the table must grow
+    growth_left: usize,
+    marker: PhantomData<T>,
+}
+
+// The table owns its elements like a `Vec` would
+unsafe impl<T: Send, A: Allocator + Send> Send for RawTable<T, A> {}
+unsafe impl<T: Sync, A: Allocator + Sync> Sync for RawTable<T, A> {}
+
+impl<T, A: Allocator + Clone> RawTable<T, A> {
+    // Constructor: no allocation until the first insert
+    pub(crate) fn new_in(alloc: A) -> Self {
+        Self {
+            ctrl: Chunks::alloc_in(0, alloc.clone()),
+            slots: Chunks::alloc_in(0, alloc),
+            bucket_mask: 0,
+            items: 0,
+            growth_left: 0,
+            marker: PhantomData,
+        }
+    }
+
+    // Constructor
+    pub(crate) fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        Self::try_with_capacity_in(capacity, alloc).unwrap_or_else(|err| handle_alloc_error(err))
+    }
+
+    fn try_with_capacity_in(capacity: usize, alloc: A) -> Result<Self, AllocError> {
+        if capacity == 0 {
+            return Ok(Self::new_in(alloc));
+        }
+        let buckets = capacity_to_buckets(capacity)?;
+        let mut ctrl = Chunks::try_alloc_in(buckets + GROUP_WIDTH, alloc.clone())?;
+        ctrl.memset_copy(EMPTY);
+        Ok(Self {
+            ctrl,
+            slots: Chunks::try_alloc_in(buckets, alloc)?,
+            bucket_mask: buckets - 1,
+            items: 0,
+            growth_left: bucket_mask_to_capacity(buckets - 1),
+            marker: PhantomData,
+        })
+    }
+
+    /// Inserts an element that is not in the table yet, returns its bucket.
+    /// `hasher` rehashes the elements if the table has to grow.
+    pub(crate) fn insert(&mut self, hash: u64, value: T, hasher: impl Fn(&T) -> u64) -> usize {
+        if self.buckets() == 0 {
+            self.reserve(1, &hasher);
+        }
+        let mut index = self.find_insert_slot(hash);
+        // DELETED buckets are reused without growing
+        if self.growth_left == 0 && unsafe { *self.ctrl(index) } == EMPTY {
+            self.reserve(1, &hasher);
+            index = self.find_insert_slot(hash);
+        }
+
+        unsafe {
+            self.growth_left -= (*self.ctrl(index) == EMPTY) as usize;
+            self.bucket(index).write(value);
+        }
+        self.set_ctrl(index, h2(hash));
+        self.items += 1;
+        index
+    }
+
+    /// Makes room for `additional` more elements without growing again
+    pub(crate) fn reserve(&mut self, additional: usize, hasher: impl Fn(&T) -> u64) {
+        if let Err(err) = self.try_reserve(additional, hasher) {
+            handle_alloc_error(err);
+        }
+    }
+
+    pub(crate) fn try_reserve(&mut self, additional: usize, hasher: impl Fn(&T) -> u64) -> Result<(), AllocError> {
+        if additional <= self.growth_left {
+            return Ok(());
+        }
+
+        let new_items = self.items.checked_add(additional).ok_or(AllocError::CapacityOverflow)?;
+        let full_capacity = bucket_mask_to_capacity(self.bucket_mask);
+        if self.buckets() > 0 && new_items <= full_capacity / 2 {
+            // Mostly tombstones: rebuilding at the same size frees them
+            self.resize(full_capacity, hasher)
+        } else {
+            self.resize(new_items.max(full_capacity + 1), hasher)
+        }
+    }
+
+    /// Shrinks to the smallest table holding `min_capacity` and the current elements
+    pub(crate) fn shrink_to(&mut self, min_capacity: usize, hasher: impl Fn(&T) -> u64) {
+        let capacity = min_capacity.max(self.items);
+        if capacity == 0 {
+            let alloc = self.allocator().clone();
+            *self = Self::new_in(alloc);
+            return;
+        }
+        let buckets = capacity_to_buckets(capacity).unwrap_or_else(|err| handle_alloc_error(err));
+        if buckets < self.buckets()
+            && let Err(err) = self.resize(capacity, hasher)
+        {
+            handle_alloc_error(err);
+        }
+    }
+
+    // Moves every element into a new table for `capacity` elements.
+    // Elements are copied bitwise and stay owned by the old table until all are
+    // rehashed: a panicking `hasher` leaves `self` as it was.
+    fn resize(&mut self, capacity: usize, hasher: impl Fn(&T) -> u64) -> Result<(), AllocError> {
+        // Forgets the copies if the loop unwinds
+        struct Guard<'a, T, A: Allocator>(&'a mut RawTable<T, A>);
+
+        impl<T, A: Allocator> Drop for Guard<'_, T, A> {
+            fn drop(&mut self) {
+                self.0.clear_no_drop();
+            }
+        }
+
+        let mut new = Self::try_with_capacity_in(capacity, self.allocator().clone())?;
+        let guard = Guard(&mut new);
+        for index in self.iter() {
+            let hash = hasher(unsafe { &*self.bucket(index) });
+            guard.0.insert_no_grow(hash, unsafe { ptr::read(self.bucket(index)) });
+        }
+        mem::forget(guard);
+
+        mem::swap(self, &mut new);
+        // The old table only frees its memory now
+        new.clear_no_drop();
+        Ok(())
+    }
+
+    fn insert_no_grow(&mut self, hash: u64, value: T) {
+        let index = self.find_insert_slot(hash);
+        unsafe {
+            self.growth_left -= (*self.ctrl(index) == EMPTY) as usize;
+            self.bucket(index).write(value);
+        }
+        self.set_ctrl(index, h2(hash));
+        self.items += 1;
+    }
+}
+
+impl<T, A: Allocator> RawTable<T, A> {
+    pub(crate) fn allocator(&self) -> &A {
+        self.slots.allocator()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.items
+    }
+
+    pub(crate) fn buckets(&self) -> usize {
+        self.slots.count
+    }
+
+    /// Elements held before the table grows
+    pub(crate) fn capacity(&self) -> usize {
+        self.items + self.growth_left
+    }
+
+    /// Pointer to bucket `index`, initialized only if the bucket is full
+    pub(crate) fn bucket(&self, index: usize) -> *mut T {
+        debug_assert!(index < self.buckets());
+        unsafe {
+            self.slots.as_mut_ptr().add(index)
+        }
+    }
+
+    unsafe fn ctrl(&self, index: usize) -> *mut u8 {
+        unsafe {
+            self.ctrl.as_mut_ptr().add(index)
+        }
+    }
+
+    // Writes the control byte of `index` and its mirror past the end.
+    // For tiny tables the mirror lies at `GROUP_WIDTH + index`, after EMPTY padding.
+    fn set_ctrl(&mut self, index: usize, ctrl: u8) {
+        let mirror = (index.wrapping_sub(GROUP_WIDTH) & self.bucket_mask) + GROUP_WIDTH;
+        unsafe {
+            *self.ctrl(index) = ctrl;
+            *self.ctrl(mirror) = ctrl;
+        }
+    }
+
+    /// Bucket holding an element with `hash` for which `eq` holds
+    pub(crate) fn find(&self, hash: u64, mut eq: impl FnMut(&T) -> bool) -> Option<usize> {
+        if self.buckets() == 0 {
+            return None;
+        }
+
+        let h2 = h2(hash);
+        let mut pos = h1(hash) & self.bucket_mask;
+        let mut stride = 0;
+        loop {
+            let group = unsafe {
+                Group::load(self.ctrl(pos))
+            };
+            for bit in group.match_byte(h2) {
+                let index = (pos + bit) & self.bucket_mask;
+                if eq(unsafe { &*self.bucket(index) }) {
+                    return Some(index);
+                }
+            }
+            // An EMPTY byte ends the probe sequence: the element would have been put there
+            if group.match_empty().any() {
+                return None;
+            }
+            // Triangular probing visits every group once when the bucket count is a power of two
+            stride += GROUP_WIDTH;
+            pos = (pos + stride) & self.bucket_mask;
+        }
+    }
+
+    pub(crate) fn get(&self, hash: u64, eq: impl FnMut(&T) -> bool) -> Option<&T> {
+        self.find(hash, eq).map(|index| unsafe { &*self.bucket(index) })
+    }
+
+    pub(crate) fn get_mut(&mut self, hash: u64, eq: impl FnMut(&T) -> bool) -> Option<&mut T> {
+        self.find(hash, eq).map(|index| unsafe { &mut *self.bucket(index) })
+    }
+
+    // First EMPTY or DELETED bucket on the probe sequence of `hash`; the table must have one
+    fn find_insert_slot(&self, hash: u64) -> usize {
+        let mut pos = h1(hash) & self.bucket_mask;
+        let mut stride = 0;
+        loop {
+            let group = unsafe {
+                Group::load(self.ctrl(pos))
+            };
+            if let Some(bit) = group.match_empty_or_deleted().lowest() {
+                let index = (pos + bit) & self.bucket_mask;
+                // In tiny tables the group may have hit the EMPTY padding
+                // and wrapped onto a full bucket: take the first free one instead
+                if is_full(unsafe { *self.ctrl(index) }) {
+                    let group = unsafe {
+                        Group::load(self.ctrl(0))
+                    };
+                    return group.match_empty_or_deleted().lowest().unwrap();
+                }
+                return index;
+            }
+            stride += GROUP_WIDTH;
+            pos = (pos + stride) & self.bucket_mask;
+        }
+    }
+
+    /// Full buckets, in bucket order
+    pub(crate) fn iter(&self) -> RawIter {
+        RawIter {
+            ctrl: self.ctrl.as_ptr(),
+            current: BitMask(0),
+            next_group: 0,
+            buckets: self.slots.count,
+            items: self.items,
+        }
+    }
+
+    // Marks a full bucket free without dropping its element
+    fn erase_no_drop(&mut self, index: usize) {
+        debug_assert!(is_full(unsafe { *self.ctrl(index) }));
+        let index_before = index.wrapping_sub(GROUP_WIDTH) & self.bucket_mask;
+        let (empty_before, empty_after) = unsafe {
+            (
+                Group::load(self.ctrl(index_before)).match_empty(),
+                Group::load(self.ctrl(index)).match_empty(),
+            )
+        };
+
+        // If some group containing `index` never was full, no probe sequence
+        // went past it: the bucket can become EMPTY again. Otherwise a tombstone
+        // keeps later elements reachable.
+        let ctrl = if empty_before.leading_bytes() + empty_after.trailing_bytes() >= GROUP_WIDTH {
+            DELETED
+        } else {
+            self.growth_left += 1;
+            EMPTY
+        };
+        self.set_ctrl(index, ctrl);
+        self.items -= 1;
+    }
+
+    /// Moves the element out of a full bucket
+    pub(crate) fn take(&mut self, index: usize) -> T {
+        self.erase_no_drop(index);
+        unsafe {
+            ptr::read(self.slots.as_ptr().add(index))
+        }
+    }
+
+    /// Drops the elements for which `f` returns false
+    pub(crate) fn retain(&mut self, mut f: impl FnMut(&mut T) -> bool) {
+        for index in self.iter() {
+            let keep = f(unsafe { &mut *self.slots.as_mut_ptr().add(index) });
+            if !keep {
+                drop(self.take(index));
+            }
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        // Each element is erased before it is dropped: a panicking drop leaks the rest
+        self.drain();
+    }
+
+    // Forgets all elements and tombstones
+    fn clear_no_drop(&mut self) {
+        if self.slots.count > 0 {
+            self.ctrl.memset_copy(EMPTY);
+            self.growth_left = bucket_mask_to_capacity(self.slots.count - 1);
+        }
+        self.items = 0;
+    }
+
+    /// Takes every element out; the table is empty afterwards
+    pub(crate) fn drain(&mut self) -> RawDrain<'_, T, A> {
+        RawDrain { iter: self.iter(), table: self }
+    }
+
+    /// Bucket-by-bucket references
+    pub(crate) fn iter_ref(&self) -> Iter<'_, T> {
+        Iter { iter: self.iter(), data: self.slots.as_ptr(), marker: PhantomData }
+    }
+
+    pub(crate) fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut { iter: self.iter(), data: self.slots.as_mut_ptr(), marker: PhantomData }
+    }
+}
+
+impl<T: Clone, A: Allocator + Clone> Clone for RawTable<T, A> {
+    fn clone(&self) -> Self {
+        let mut new = Self::with_capacity_in(0, self.allocator().clone());
+        if self.buckets() == 0 {
+            return new;
+        }
+        new.ctrl = Chunks::alloc_in(self.ctrl.count, self.allocator().clone());
+        new.ctrl.memset_copy(EMPTY);
+        new.slots = Chunks::alloc_in(self.buckets(), self.allocator().clone());
+        new.bucket_mask = self.bucket_mask;
+
+        // Same buckets as the original; each is marked full only once its clone is written,
+        // so a panicking `clone` leaves a consistent table behind
+        for index in self.iter() {
+            unsafe {
+                new.bucket(index).write((*self.bucket(index)).clone());
+                new.set_ctrl(index, *self.ctrl(index));
+            }
+            new.items += 1;
+        }
+        // Tombstones too, so that probe sequences stay the same
+        unsafe {
+            ptr::copy_nonoverlapping(self.ctrl.as_ptr(), new.ctrl.as_mut_ptr(), self.ctrl.count);
+        }
+        new.growth_left = self.growth_left;
+        new
+    }
+}
+
+impl<T, A: Allocator> Drop for RawTable<T, A> {
+    fn drop(&mut self) {
+        if mem::needs_drop::<T>() {
+            for index in self.iter() {
+                unsafe {
+                    ptr::drop_in_place(self.slots.as_mut_ptr().add(index));
+                }
+            }
+        }
+        // `ctrl` and `slots` free the memory
+    }
+}
+
+// ======== ITERATORS ========
+
+/// Indices of full buckets. Holds no borrow: the table must outlive it
+/// and keep the buckets not yet yielded full.
+#[derive(Clone)]
+pub(crate) struct RawIter {
+    ctrl: *const u8,
+    // Full bytes of the group being walked
+    current: BitMask,
+    next_group: usize,
+    buckets: usize,
+    // Full buckets not yielded yet: scanning stops once all were seen
+    items: usize,
+}
+
+// Only reads control bytes
+unsafe impl Send for RawIter {}
+unsafe impl Sync for RawIter {}
+
+impl Iterator for RawIter {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.items == 0 {
+            return None;
+        }
+        loop {
+            if let Some(bit) = self.current.lowest() {
+                self.current = self.current.without_lowest();
+                self.items -= 1;
+                return Some(self.next_group - GROUP_WIDTH + bit);
+            }
+            // Tiny tables fit in the first group, with EMPTY padding behind the buckets
+            debug_assert!(self.next_group < self.buckets);
+            self.current = unsafe {
+                Group::load(self.ctrl.add(self.next_group)).match_full()
+            };
+            self.next_group += GROUP_WIDTH;
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.items, Some(self.items))
+    }
+}
+
+impl ExactSizeIterator for RawIter {}
+
+/// References to the elements, in bucket order
+pub(crate) struct Iter<'a, T> {
+    iter: RawIter,
+    data: *const T,
+    marker: PhantomData<&'a T>,
+}
+
+unsafe impl<T: Sync> Send for Iter<'_, T> {}
+unsafe impl<T: Sync> Sync for Iter<'_, T> {}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.iter.next().map(|index| unsafe { &*self.data.add(index) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Iter { iter: self.iter.clone(), data: self.data, marker: PhantomData }
+    }
+}
+
+/// Mutable references to the elements, in bucket order
+pub(crate) struct IterMut<'a, T> {
+    iter: RawIter,
+    data: *mut T,
+    marker: PhantomData<&'a mut T>,
+}
+
+unsafe impl<T: Send> Send for IterMut<'_, T> {}
+unsafe impl<T: Sync> Sync for IterMut<'_, T> {}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        self.iter.next().map(|index| unsafe { &mut *self.data.add(index) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// Moves the elements out of a borrowed table.
+/// Elements not yielded are dropped with it, the table keeps its memory.
+pub(crate) struct RawDrain<'a, T, A: Allocator> {
+    iter: RawIter,
+    table: &'a mut RawTable<T, A>,
+}
+
+impl<T, A: Allocator> Iterator for RawDrain<'_, T, A> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.iter.next().map(|index| self.table.take(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T, A: Allocator> Drop for RawDrain<'_, T, A> {
+    fn drop(&mut self) {
+        self.for_each(drop);
+        // Tombstones left by `take` are cleared too
+        self.table.clear_no_drop();
+    }
+}
+
+/// Moves the elements out of an owned table
+pub(crate) struct RawIntoIter<T, A: Allocator> {
+    iter: RawIter,
+    table: RawTable<T, A>,
+}
+
+impl<T, A: Allocator> RawIntoIter<T, A> {
+    pub(crate) fn new(table: RawTable<T, A>) -> Self {
+        RawIntoIter { iter: table.iter(), table }
+    }
+}
+
+impl<T, A: Allocator> Iterator for RawIntoIter<T, A> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.iter.next().map(|index| self.table.take(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
@@ -0,0 +1,450 @@
+use std::fmt;
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
+use std::mem::MaybeUninit;
+use std::ptr;
+use std::slice;
+
+use crate::allocator::{Allocator, Global};
+use crate::chunks::Chunks;
+use crate::vector::Vector;
+
+/// History of the last `N` values, stored inline.
+///
+/// Pushing into a full buffer overwrites the oldest value. Elements start at slot
+/// `head` and wrap around the end of the array.
+pub struct RingBuffer<T, const N: usize> {
+    slots: [MaybeUninit<T>; N],
+    head: usize,
+    len: usize,
+}
+
+/// `RingBuffer` with the capacity chosen at runtime, stored in `Chunks` on the heap
+pub struct HeapRingBuffer<T, A: Allocator = Global> {
+    slots: Chunks<T, true, A>,
+    head: usize,
+    len: usize,
+}
+
+impl<T, const N: usize> RingBuffer<T, N> {
+    // Constructor
+    pub const fn new() -> Self {
+        Self {
+            slots: [const { MaybeUninit::uninit() }; N],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    fn base(&self) -> *const T {
+        self.slots.as_ptr() as *const T
+    }
+
+    fn base_mut(&mut self) -> *mut T {
+        self.slots.as_mut_ptr() as *mut T
+    }
+}
+
+impl<T> HeapRingBuffer<T> {
+    // Constructor
+    pub fn new(capacity: usize) -> Self {
+        Self::new_in(capacity, Global)
+    }
+}
+
+impl<T, A: Allocator> HeapRingBuffer<T, A> {
+    // Constructor
+    pub fn new_in(capacity: usize, alloc: A) -> Self {
+        Self {
+            slots: Chunks::alloc_in(capacity, alloc),
+            head: 0,
+            len: 0,
+        }
+    }
+
+    pub fn allocator(&self) -> &A {
+        self.slots.allocator()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.slots.count
+    }
+
+    fn base(&self) -> *const T {
+        self.slots.as_ptr()
+    }
+
+    fn base_mut(&mut self) -> *mut T {
+        self.slots.as_mut_ptr()
+    }
+}
+
+// Both buffers only differ in where the slots live: `base()`, `base_mut()` and `capacity()`
+macro_rules! ring_buffer_impl {
+    () => {
+        pub fn len(&self) -> usize {
+            self.len
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.len == 0
+        }
+
+        pub fn is_full(&self) -> bool {
+            self.len == self.capacity()
+        }
+
+        // Slot of the `index`-th oldest element
+        fn to_physical(&self, index: usize) -> usize {
+            let i = self.head + index;
+            if i >= self.capacity() {
+                i - self.capacity()
+            } else {
+                i
+            }
+        }
+
+        /// Appends `value` as the latest element. When full, the oldest one
+        /// is overwritten and returned. A zero-capacity buffer returns `value` itself.
+        pub fn push(&mut self, value: T) -> Option<T> {
+            if self.capacity() == 0 {
+                return Some(value);
+            }
+
+            if self.is_full() {
+                let slot = self.head;
+                self.head = self.to_physical(1);
+                unsafe {
+                    Some(ptr::replace(self.base_mut().add(slot), value))
+                }
+            } else {
+                let slot = self.to_physical(self.len);
+                unsafe {
+                    ptr::write(self.base_mut().add(slot), value);
+                }
+                self.len += 1;
+                None
+            }
+        }
+
+        pub fn pop_oldest(&mut self) -> Option<T> {
+            if self.len == 0 {
+                return None;
+            }
+            let slot = self.head;
+            self.head = self.to_physical(1);
+            self.len -= 1;
+            unsafe {
+                Some(ptr::read(self.base().add(slot)))
+            }
+        }
+
+        pub fn pop_latest(&mut self) -> Option<T> {
+            if self.len == 0 {
+                return None;
+            }
+            self.len -= 1;
+            unsafe {
+                Some(ptr::read(self.base().add(self.to_physical(self.len))))
+            }
+        }
+
+        /// The `index`-th oldest element: 0 is the oldest, `len - 1` the latest
+        pub fn get(&self, index: usize) -> Option<&T> {
+            if index < self.len {
+                unsafe {
+                    Some(&*self.base().add(self.to_physical(index)))
+                }
+            } else {
+                None
+            }
+        }
+
+        pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+            if index < self.len {
+                let slot = self.to_physical(index);
+                unsafe {
+                    Some(&mut *self.base_mut().add(slot))
+                }
+            } else {
+                None
+            }
+        }
+
+        pub fn oldest(&self) -> Option<&T> {
+            self.get(0)
+        }
+
+        /// The most recently pushed element
+        pub fn latest(&self) -> Option<&T> {
+            self.get(self.len.wrapping_sub(1))
+        }
+
+        pub fn latest_mut(&mut self) -> Option<&mut T> {
+            self.get_mut(self.len.wrapping_sub(1))
+        }
+
+        /// Oldest to newest: the part from `head` to the end of the slots, then the wrapped part
+        pub fn as_slices(&self) -> (&[T], &[T]) {
+            let head_len = self.capacity() - self.head;
+            unsafe {
+                if self.len <= head_len {
+                    (slice::from_raw_parts(self.base().add(self.head), self.len), &[])
+                } else {
+                    (
+                        slice::from_raw_parts(self.base().add(self.head), head_len),
+                        slice::from_raw_parts(self.base(), self.len - head_len),
+                    )
+                }
+            }
+        }
+
+        /// Oldest to newest
+        pub fn iter(&self) -> Iter<'_, T> {
+            let (a, b) = self.as_slices();
+            Iter { a: a.iter(), b: b.iter() }
+        }
+
+        /// Moves all elements out, oldest first. The buffer is empty afterwards,
+        /// also if the `Drain` is dropped early.
+        pub fn drain(&mut self) -> Drain<'_, T> {
+            let drain = Drain {
+                base: self.base_mut(),
+                capacity: self.capacity(),
+                head: self.head,
+                remaining: self.len,
+                marker: PhantomData,
+            };
+            // The Drain owns the elements now
+            self.head = 0;
+            self.len = 0;
+            drain
+        }
+
+        pub fn clear(&mut self) {
+            self.drain();
+        }
+
+        /// Clones the elements into a contiguous `Vector`, oldest first
+        pub fn to_vector(&self) -> Vector<T>
+        where
+            T: Clone,
+        {
+            let mut v = Vector::with_capacity(self.len);
+            for elem in self.iter() {
+                v.push(elem.clone());
+            }
+            v
+        }
+    };
+}
+
+impl<T, const N: usize> RingBuffer<T, N> {
+    ring_buffer_impl!();
+}
+
+impl<T, A: Allocator> HeapRingBuffer<T, A> {
+    ring_buffer_impl!();
+}
+
+// ======== ITERATORS ========
+
+/// Borrows elements oldest to newest, created by `iter()`
+#[derive(Clone, Debug)]
+pub struct Iter<'a, T> {
+    a: slice::Iter<'a, T>,
+    b: slice::Iter<'a, T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.a.next().or_else(|| self.b.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.a.len() + self.b.len();
+        (len, Some(len))
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.b.next_back().or_else(|| self.a.next_back())
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+/// Moves elements out oldest to newest, created by `drain()`.
+/// Elements not yielded are dropped with it.
+pub struct Drain<'a, T> {
+    base: *mut T,
+    capacity: usize,
+    head: usize,
+    remaining: usize,
+    marker: PhantomData<&'a mut T>,
+}
+
+impl<T> Iterator for Drain<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let slot = self.head;
+        self.head = if self.head + 1 == self.capacity { 0 } else { self.head + 1 };
+        self.remaining -= 1;
+        unsafe {
+            Some(ptr::read(self.base.add(slot)))
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> DoubleEndedIterator for Drain<'_, T> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let mut slot = self.head + self.remaining;
+        if slot >= self.capacity {
+            slot -= self.capacity;
+        }
+        unsafe {
+            Some(ptr::read(self.base.add(slot)))
+        }
+    }
+}
+
+impl<T> ExactSizeIterator for Drain<'_, T> {}
+
+impl<T> FusedIterator for Drain<'_, T> {}
+
+impl<T> Drop for Drain<'_, T> {
+    fn drop(&mut self) {
+        // Keeps dropping if one of the drops panics
+        struct Guard<'r, 'a, T>(&'r mut Drain<'a, T>);
+
+        impl<T> Drop for Guard<'_, '_, T> {
+            fn drop(&mut self) {
+                self.0.for_each(drop);
+            }
+        }
+
+        let guard = Guard(self);
+        guard.0.for_each(drop);
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Drain<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Drain").field("remaining", &self.remaining).finish()
+    }
+}
+
+// ======== STD TRAITS ========
+
+impl<T, const N: usize> Default for RingBuffer<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for RingBuffer<T, N> {
+    fn clone(&self) -> Self {
+        let mut c = Self::new();
+        c.extend(self.iter().cloned());
+        c
+    }
+}
+
+impl<T: Clone, A: Allocator + Clone> Clone for HeapRingBuffer<T, A> {
+    fn clone(&self) -> Self {
+        let mut c = Self::new_in(self.capacity(), self.allocator().clone());
+        c.extend(self.iter().cloned());
+        c
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for RingBuffer<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for HeapRingBuffer<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+// Pushing keeps only the last `capacity` values
+impl<T, const N: usize> Extend<T> for RingBuffer<T, N> {
+    fn extend<I: IntoIterator<Item=T>>(&mut self, iter: I) {
+        for elem in iter {
+            self.push(elem);
+        }
+    }
+}
+
+impl<T, A: Allocator> Extend<T> for HeapRingBuffer<T, A> {
+    fn extend<I: IntoIterator<Item=T>>(&mut self, iter: I) {
+        for elem in iter {
+            self.push(elem);
+        }
+    }
+}
+
+impl<T, const N: usize> FromIterator<T> for RingBuffer<T, N> {
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> Self {
+        let mut r = Self::new();
+        r.extend(iter);
+        r
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a RingBuffer<T, N> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T, A: Allocator> IntoIterator for &'a HeapRingBuffer<T, A> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+// ======== DROP ========
+
+impl<T, const N: usize> Drop for RingBuffer<T, N> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T, A: Allocator> Drop for HeapRingBuffer<T, A> {
+    fn drop(&mut self) {
+        // `slots` frees the memory afterwards
+        self.clear();
+    }
+}
@@ -0,0 +1,470 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::iter::FusedIterator;
+use std::ops::{Bound, Deref, RangeBounds};
+use std::slice;
+
+use crate::allocator::{Allocator, Global};
+use crate::vector::{self, Vector};
+
+/// Set of unique elements kept in ascending order in a `Vector`.
+///
+/// Lookups are binary searches, inserts and removals shift the tail: meant for
+/// small, read-mostly sets where a tree would be overkill.
+pub struct SortedVector<T, A: Allocator = Global> {
+    vec: Vector<T, A>,
+}
+
+impl<T> SortedVector<T> {
+    // Constructor
+    pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+
+    // Constructor
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_in(capacity, Global)
+    }
+}
+
+impl<T, A: Allocator> SortedVector<T, A> {
+    // Constructor
+    pub fn new_in(alloc: A) -> Self {
+        Self { vec: Vector::new_in(alloc) }
+    }
+
+    // Constructor
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        Self { vec: Vector::with_capacity_in(capacity, alloc) }
+    }
+
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vec.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        self.vec.as_slice()
+    }
+
+    pub fn clear(&mut self) {
+        self.vec.clear();
+    }
+
+    pub fn pop_first(&mut self) -> Option<T> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.vec.remove(0))
+        }
+    }
+
+    pub fn pop_last(&mut self) -> Option<T> {
+        self.vec.pop()
+    }
+
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, f: F) {
+        self.vec.retain(f);
+    }
+
+    pub fn into_vector(self) -> Vector<T, A> {
+        self.vec
+    }
+}
+
+impl<T: Ord, A: Allocator> SortedVector<T, A> {
+    /// Sorts and deduplicates: of equal elements the first one is kept
+    pub fn from_vector(mut vec: Vector<T, A>) -> Self {
+        vec.sort_stable();
+        vec.dedup();
+        Self { vec }
+    }
+
+    // Position of `value`, or where it would be inserted
+    fn search<Q: Ord + ?Sized>(&self, value: &Q) -> Result<usize, usize>
+    where
+        T: Borrow<Q>,
+    {
+        let index = self.rank(value);
+        match self.vec.as_slice().get(index) {
+            Some(x) if x.borrow() == value => Ok(index),
+            _ => Err(index),
+        }
+    }
+
+    /// Adds `value` if absent: binary search, then the tail is shifted by one.
+    /// Returns false, keeping the old element, if an equal one is already present.
+    pub fn insert(&mut self, value: T) -> bool {
+        match self.search(&value) {
+            Ok(_) => false,
+            Err(index) => self.vec.insert(index, value),
+        }
+    }
+
+    /// Adds `value`, returning the equal element it replaced
+    pub fn replace(&mut self, value: T) -> Option<T> {
+        match self.search(&value) {
+            Ok(index) => Some(std::mem::replace(&mut self.vec[index], value)),
+            Err(index) => {
+                self.vec.insert(index, value);
+                None
+            }
+        }
+    }
+
+    pub fn contains<Q: Ord + ?Sized>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+    {
+        self.search(value).is_ok()
+    }
+
+    pub fn get<Q: Ord + ?Sized>(&self, value: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+    {
+        self.search(value).ok().map(|index| &self.vec[index])
+    }
+
+    pub fn remove<Q: Ord + ?Sized>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+    {
+        self.take(value).is_some()
+    }
+
+    pub fn take<Q: Ord + ?Sized>(&mut self, value: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+    {
+        self.search(value).ok().map(|index| self.vec.remove(index))
+    }
+
+    /// Number of elements less than `value`, i.e. its index if it's present
+    pub fn rank<Q: Ord + ?Sized>(&self, value: &Q) -> usize
+    where
+        T: Borrow<Q>,
+    {
+        self.vec.partition_point(|x| x.borrow() < value)
+    }
+
+    /// Elements within `range` as a slice. A reversed range gives an empty slice.
+    pub fn range<Q: Ord + ?Sized, R: RangeBounds<Q>>(&self, range: R) -> &[T]
+    where
+        T: Borrow<Q>,
+    {
+        let start = match range.start_bound() {
+            Bound::Included(s) => self.vec.partition_point(|x| x.borrow() < s),
+            Bound::Excluded(s) => self.vec.partition_point(|x| x.borrow() <= s),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(e) => self.vec.partition_point(|x| x.borrow() <= e),
+            Bound::Excluded(e) => self.vec.partition_point(|x| x.borrow() < e),
+            Bound::Unbounded => self.len(),
+        };
+        &self.as_slice()[start..end.max(start)]
+    }
+
+    // Set algebra: merges of the two sorted slices, O(n + m), yielding in ascending order
+
+    pub fn union<'a, A2: Allocator>(&'a self, other: &'a SortedVector<T, A2>) -> Union<'a, T> {
+        Union { a: self.as_slice(), b: other.as_slice() }
+    }
+
+    pub fn intersection<'a, A2: Allocator>(&'a self, other: &'a SortedVector<T, A2>) -> Intersection<'a, T> {
+        Intersection { a: self.as_slice(), b: other.as_slice() }
+    }
+
+    pub fn difference<'a, A2: Allocator>(&'a self, other: &'a SortedVector<T, A2>) -> Difference<'a, T> {
+        Difference { a: self.as_slice(), b: other.as_slice() }
+    }
+
+    pub fn symmetric_difference<'a, A2: Allocator>(
+        &'a self,
+        other: &'a SortedVector<T, A2>,
+    ) -> SymmetricDifference<'a, T> {
+        SymmetricDifference { a: self.as_slice(), b: other.as_slice() }
+    }
+
+    pub fn is_subset<A2: Allocator>(&self, other: &SortedVector<T, A2>) -> bool {
+        self.len() <= other.len() && self.difference(other).next().is_none()
+    }
+
+    pub fn is_disjoint<A2: Allocator>(&self, other: &SortedVector<T, A2>) -> bool {
+        self.intersection(other).next().is_none()
+    }
+}
+
+// ======== SET ITERATORS ========
+
+// Pops the first element of a slice
+fn advance<'a, T>(s: &mut &'a [T]) -> Option<&'a T> {
+    let (first, rest) = s.split_first()?;
+    *s = rest;
+    Some(first)
+}
+
+/// Elements of either set, created by `SortedVector::union()`
+#[derive(Clone, Debug)]
+pub struct Union<'a, T> {
+    a: &'a [T],
+    b: &'a [T],
+}
+
+impl<'a, T: Ord> Iterator for Union<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let ordering = match (self.a.first(), self.b.first()) {
+            (Some(x), Some(y)) => x.cmp(y),
+            (Some(_), None) => Ordering::Less,
+            (None, _) => Ordering::Greater,
+        };
+        match ordering {
+            Ordering::Less => advance(&mut self.a),
+            Ordering::Greater => advance(&mut self.b),
+            Ordering::Equal => {
+                advance(&mut self.b);
+                advance(&mut self.a)
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (a, b) = (self.a.len(), self.b.len());
+        (a.max(b), Some(a + b))
+    }
+}
+
+impl<T: Ord> FusedIterator for Union<'_, T> {}
+
+/// Elements of both sets, created by `SortedVector::intersection()`
+#[derive(Clone, Debug)]
+pub struct Intersection<'a, T> {
+    a: &'a [T],
+    b: &'a [T],
+}
+
+impl<'a, T: Ord> Iterator for Intersection<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        loop {
+            match self.a.first()?.cmp(self.b.first()?) {
+                Ordering::Less => self.a = &self.a[1..],
+                Ordering::Greater => self.b = &self.b[1..],
+                Ordering::Equal => {
+                    advance(&mut self.b);
+                    return advance(&mut self.a);
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.a.len().min(self.b.len())))
+    }
+}
+
+impl<T: Ord> FusedIterator for Intersection<'_, T> {}
+
+/// Elements of the first set missing from the second, created by `SortedVector::difference()`
+#[derive(Clone, Debug)]
+pub struct Difference<'a, T> {
+    a: &'a [T],
+    b: &'a [T],
+}
+
+impl<'a, T: Ord> Iterator for Difference<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        loop {
+            let x = self.a.first()?;
+            let Some(y) = self.b.first() else {
+                return advance(&mut self.a);
+            };
+            match x.cmp(y) {
+                Ordering::Less => return advance(&mut self.a),
+                Ordering::Greater => self.b = &self.b[1..],
+                Ordering::Equal => {
+                    self.a = &self.a[1..];
+                    self.b = &self.b[1..];
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.a.len().saturating_sub(self.b.len()), Some(self.a.len()))
+    }
+}
+
+impl<T: Ord> FusedIterator for Difference<'_, T> {}
+
+/// Elements in exactly one of the sets, created by `SortedVector::symmetric_difference()`
+#[derive(Clone, Debug)]
+pub struct SymmetricDifference<'a, T> {
+    a: &'a [T],
+    b: &'a [T],
+}
+
+impl<'a, T: Ord> Iterator for SymmetricDifference<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        loop {
+            let ordering = match (self.a.first(), self.b.first()) {
+                (Some(x), Some(y)) => x.cmp(y),
+                (Some(_), None) => Ordering::Less,
+                (None, _) => Ordering::Greater,
+            };
+            match ordering {
+                Ordering::Less => return advance(&mut self.a),
+                Ordering::Greater => return advance(&mut self.b),
+                Ordering::Equal => {
+                    self.a = &self.a[1..];
+                    self.b = &self.b[1..];
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.a.len() + self.b.len()))
+    }
+}
+
+impl<T: Ord> FusedIterator for SymmetricDifference<'_, T> {}
+
+// ======== DEREF ========
+// Read-only: mutable access could break the order
+
+impl<T, A: Allocator> Deref for SortedVector<T, A> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, A: Allocator> AsRef<[T]> for SortedVector<T, A> {
+    fn as_ref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+// ======== ITER ========
+
+impl<T: Ord> FromIterator<T> for SortedVector<T> {
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> Self {
+        Self::from_vector(iter.into_iter().collect())
+    }
+}
+
+impl<T, A: Allocator> IntoIterator for SortedVector<T, A> {
+    type Item = T;
+    type IntoIter = vector::IntoIter<T, A>;
+
+    fn into_iter(self) -> vector::IntoIter<T, A> {
+        self.vec.into_iter()
+    }
+}
+
+impl<'a, T, A: Allocator> IntoIterator for &'a SortedVector<T, A> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> slice::Iter<'a, T> {
+        self.as_slice().iter()
+    }
+}
+
+// Appends everything, then restores the order once: O((n + m) log(n + m))
+// instead of an insert with a shift per element
+impl<T: Ord, A: Allocator> Extend<T> for SortedVector<T, A> {
+    fn extend<I: IntoIterator<Item=T>>(&mut self, iter: I) {
+        let old_len = self.len();
+        self.vec.extend(iter);
+        if self.len() != old_len {
+            // Stable: present elements stay ahead of new equal ones and win the dedup
+            self.vec.sort_stable();
+            self.vec.dedup();
+        }
+    }
+}
+
+// ======== STD TRAITS ========
+
+impl<T> Default for SortedVector<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone, A: Allocator + Clone> Clone for SortedVector<T, A> {
+    fn clone(&self) -> Self {
+        Self { vec: self.vec.clone() }
+    }
+}
+
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for SortedVector<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.as_slice()).finish()
+    }
+}
+
+impl<T: PartialEq, A1: Allocator, A2: Allocator> PartialEq<SortedVector<T, A2>> for SortedVector<T, A1> {
+    fn eq(&self, other: &SortedVector<T, A2>) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T: Eq, A: Allocator> Eq for SortedVector<T, A> {}
+
+impl<T: Ord, A: Allocator> PartialOrd for SortedVector<T, A> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord, A: Allocator> Ord for SortedVector<T, A> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_slice().cmp(other.as_slice())
+    }
+}
+
+impl<T: std::hash::Hash, A: Allocator> std::hash::Hash for SortedVector<T, A> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state);
+    }
+}
+
+// ======== FROM & INTO ========
+
+impl<T: Ord, A: Allocator> From<Vector<T, A>> for SortedVector<T, A> {
+    fn from(value: Vector<T, A>) -> Self {
+        Self::from_vector(value)
+    }
+}
+
+impl<T: Ord, const N: usize> From<[T; N]> for SortedVector<T> {
+    fn from(value: [T; N]) -> Self {
+        Self::from_vector(Vector::from(value))
+    }
+}
+
+impl<T, A: Allocator> From<SortedVector<T, A>> for Vector<T, A> {
+    fn from(value: SortedVector<T, A>) -> Self {
+        value.vec
+    }
+}
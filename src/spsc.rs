@@ -0,0 +1,298 @@
+//! Bounded single-producer single-consumer queue.
+//!
+//! `channel(capacity)` gives a `Producer` and a `Consumer` sharing one `Chunks`
+//! buffer. Every operation is wait-free: it never blocks, and returns right
+//! away when the queue is full or empty.
+
+use std::fmt;
+use std::ops::Deref;
+use std::ptr;
+use std::sync::Arc;
+use std::sync::atomic::{self, AtomicUsize, Ordering};
+
+use crate::chunks::Chunks;
+
+/// Keeps a value on its own cache line, so that the producer and the consumer
+/// don't invalidate each other's line on every update
+#[repr(align(64))]
+pub(crate) struct CachePadded<T>(pub(crate) T);
+
+impl<T> Deref for CachePadded<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+// Indices run over `0..2 * capacity`: equal ones mean empty, `capacity` apart mean full
+struct Shared<T> {
+    // Next index to read, written by the Consumer only
+    head: CachePadded<AtomicUsize>,
+    // Next index to write, written by the Producer only
+    tail: CachePadded<AtomicUsize>,
+    buf: Chunks<T>,
+}
+
+unsafe impl<T: Send> Send for Shared<T> {}
+unsafe impl<T: Send> Sync for Shared<T> {}
+
+impl<T> Shared<T> {
+    fn capacity(&self) -> usize {
+        self.buf.count
+    }
+
+    // Position in `buf` of an index
+    fn physical(&self, index: usize) -> usize {
+        if index >= self.capacity() { index - self.capacity() } else { index }
+    }
+
+    fn slot(&self, index: usize) -> *mut T {
+        unsafe {
+            self.buf.as_mut_ptr().add(self.physical(index))
+        }
+    }
+
+    fn advance(&self, index: usize, n: usize) -> usize {
+        let i = index + n;
+        if i >= 2 * self.capacity() { i - 2 * self.capacity() } else { i }
+    }
+
+    // Number of elements between `head` and `tail`
+    fn distance(&self, head: usize, tail: usize) -> usize {
+        if tail >= head { tail - head } else { tail + 2 * self.capacity() - head }
+    }
+}
+
+impl<T> Drop for Shared<T> {
+    fn drop(&mut self) {
+        // Both ends are gone: whatever is between them was never popped
+        let mut head = *self.head.0.get_mut();
+        let tail = *self.tail.0.get_mut();
+        while head != tail {
+            unsafe {
+                ptr::drop_in_place(self.slot(head));
+            }
+            head = self.advance(head, 1);
+        }
+    }
+}
+
+/// Creates a queue holding up to `capacity` elements. Panics if `capacity` is 0.
+pub fn channel<T>(capacity: usize) -> (Producer<T>, Consumer<T>) {
+    assert!(capacity > 0, "spsc capacity must be non-zero");
+    let shared = Arc::new(Shared {
+        head: CachePadded(AtomicUsize::new(0)),
+        tail: CachePadded(AtomicUsize::new(0)),
+        buf: Chunks::alloc(capacity),
+    });
+    let producer = Producer {
+        shared: shared.clone(),
+        tail: 0,
+        cached_head: 0,
+    };
+    let consumer = Consumer {
+        shared,
+        head: 0,
+        cached_tail: 0,
+    };
+    (producer, consumer)
+}
+
+// ======== PRODUCER ========
+
+/// Writing end of the queue
+pub struct Producer<T> {
+    shared: Arc<Shared<T>>,
+    // Own copy of `shared.tail`
+    tail: usize,
+    // Last seen `shared.head`, only reloaded when the queue looks full
+    cached_head: usize,
+}
+
+impl<T> Producer<T> {
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity()
+    }
+
+    /// Elements in the queue. The Consumer may pop some right after.
+    pub fn len(&self) -> usize {
+        self.shared.distance(self.shared.head.load(Ordering::Acquire), self.tail)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len() == self.capacity()
+    }
+
+    /// The Consumer has been dropped: nothing pushed will be read anymore
+    pub fn is_abandoned(&self) -> bool {
+        // The count is read relaxed: synchronize with the other end's drop,
+        // so that everything it did before is visible afterwards
+        let abandoned = Arc::strong_count(&self.shared) == 1;
+        if abandoned {
+            atomic::fence(Ordering::Acquire);
+        }
+        abandoned
+    }
+
+    // Free slots, reloading the Consumer's position only when needed
+    fn free(&mut self, wanted: usize) -> usize {
+        let mut free = self.capacity() - self.shared.distance(self.cached_head, self.tail);
+        if free < wanted {
+            self.cached_head = self.shared.head.load(Ordering::Acquire);
+            free = self.capacity() - self.shared.distance(self.cached_head, self.tail);
+        }
+        free
+    }
+
+    /// Appends `value`, or gives it back if the queue is full
+    pub fn push(&mut self, value: T) -> Result<(), T> {
+        if self.free(1) == 0 {
+            return Err(value);
+        }
+        unsafe {
+            ptr::write(self.shared.slot(self.tail), value);
+        }
+        self.tail = self.shared.advance(self.tail, 1);
+        self.shared.tail.store(self.tail, Ordering::Release);
+        Ok(())
+    }
+
+    /// Copies as many elements from the front of `src` as fit, returns how many
+    pub fn push_slice(&mut self, src: &[T]) -> usize
+    where
+        T: Copy,
+    {
+        let n = self.free(src.len()).min(src.len());
+        if n == 0 {
+            return 0;
+        }
+
+        // At most two copies: up to the end of the buffer, then from its start
+        let start = self.shared.slot(self.tail);
+        let until_end = self.capacity() - self.shared.physical(self.tail);
+        let first = n.min(until_end);
+        unsafe {
+            ptr::copy_nonoverlapping(src.as_ptr(), start, first);
+            ptr::copy_nonoverlapping(src.as_ptr().add(first), self.shared.slot(0), n - first);
+        }
+        self.tail = self.shared.advance(self.tail, n);
+        self.shared.tail.store(self.tail, Ordering::Release);
+        n
+    }
+}
+
+impl<T> fmt::Debug for Producer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Producer")
+            .field("len", &self.len())
+            .field("capacity", &self.capacity())
+            .finish()
+    }
+}
+
+// ======== CONSUMER ========
+
+/// Reading end of the queue
+pub struct Consumer<T> {
+    shared: Arc<Shared<T>>,
+    // Own copy of `shared.head`
+    head: usize,
+    // Last seen `shared.tail`, only reloaded when the queue looks empty
+    cached_tail: usize,
+}
+
+impl<T> Consumer<T> {
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity()
+    }
+
+    /// Elements in the queue. The Producer may push more right after.
+    pub fn len(&self) -> usize {
+        self.shared.distance(self.head, self.shared.tail.load(Ordering::Acquire))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The Producer has been dropped: once empty, the queue stays empty
+    pub fn is_abandoned(&self) -> bool {
+        // The count is read relaxed: synchronize with the other end's drop,
+        // so that everything it did before is visible afterwards
+        let abandoned = Arc::strong_count(&self.shared) == 1;
+        if abandoned {
+            atomic::fence(Ordering::Acquire);
+        }
+        abandoned
+    }
+
+    // Ready elements, reloading the Producer's position only when needed
+    fn available(&mut self, wanted: usize) -> usize {
+        let mut available = self.shared.distance(self.head, self.cached_tail);
+        if available < wanted {
+            self.cached_tail = self.shared.tail.load(Ordering::Acquire);
+            available = self.shared.distance(self.head, self.cached_tail);
+        }
+        available
+    }
+
+    /// Removes the oldest element, `None` if the queue is empty
+    pub fn pop(&mut self) -> Option<T> {
+        if self.available(1) == 0 {
+            return None;
+        }
+        let value = unsafe {
+            ptr::read(self.shared.slot(self.head))
+        };
+        self.head = self.shared.advance(self.head, 1);
+        self.shared.head.store(self.head, Ordering::Release);
+        Some(value)
+    }
+
+    /// The oldest element, left in the queue.
+    /// Takes `&mut self` so that only the consuming thread can borrow elements.
+    pub fn peek(&mut self) -> Option<&T> {
+        if self.available(1) == 0 {
+            return None;
+        }
+        unsafe {
+            Some(&*self.shared.slot(self.head))
+        }
+    }
+
+    /// Moves as many elements as are ready into the front of `dst`, returns how many
+    pub fn pop_slice(&mut self, dst: &mut [T]) -> usize
+    where
+        T: Copy,
+    {
+        let n = self.available(dst.len()).min(dst.len());
+        if n == 0 {
+            return 0;
+        }
+
+        let start = self.shared.slot(self.head);
+        let until_end = self.capacity() - self.shared.physical(self.head);
+        let first = n.min(until_end);
+        unsafe {
+            ptr::copy_nonoverlapping(start, dst.as_mut_ptr(), first);
+            ptr::copy_nonoverlapping(self.shared.slot(0), dst.as_mut_ptr().add(first), n - first);
+        }
+        self.head = self.shared.advance(self.head, n);
+        self.shared.head.store(self.head, Ordering::Release);
+        n
+    }
+}
+
+impl<T> fmt::Debug for Consumer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Consumer")
+            .field("len", &self.len())
+            .field("capacity", &self.capacity())
+            .finish()
+    }
+}
@@ -1,27 +1,50 @@
-use std::fmt::Display;
-use std::mem::ManuallyDrop;
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem;
+use std::mem::{ManuallyDrop, MaybeUninit};
 use std::ptr;
 use std::ops::{Index, IndexMut};
 use std::ops::{Deref, DerefMut};
+use std::ops::{Bound, Range, RangeBounds};
+use std::borrow::{Borrow, BorrowMut};
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+use std::slice;
 use crate::chunks as my;
-
-#[derive(Debug)]
-pub struct Vector<T: Display + Clone> {
-    data: my::Chunks<T>,
-    pub len: usize,
+use crate::allocator::{Allocator, AllocError, Global, handle_alloc_error};
+use crate::growth::{Doubling, GrowthPolicy};
+
+mod into_iter;
+mod drain;
+mod splice;
+mod extract_if;
+mod sort;
+mod select;
+
+pub use into_iter::IntoIter;
+pub use drain::Drain;
+pub use splice::Splice;
+pub use extract_if::ExtractIf;
+pub use sort::RadixKey;
+
+/// Growable array owning the initialized prefix `0..len` of its `Chunks`.
+/// Slots `len..capacity` are spare memory and never read or dropped.
+pub struct Vector<T, A: Allocator = Global, G: GrowthPolicy = Doubling> {
+    data: my::Chunks<T, true, A>,
+    // Slots `0..len` are initialized. Private: outside code goes through unsafe `set_len`
+    len: usize,
+    growth: PhantomData<G>,
 }
 
 
-impl<T: Display + Clone + Copy> Vector<T> {
+impl<T: Copy> Vector<T> {
     // Constructor
     pub fn new_copy(value: T, len: usize) -> Self {
-        // Allocate at least something
-        let capacity = if len > 0 { len } else { 1 };
-
-        let chunks = my::Chunks::filled_copy(value, capacity);
+        let chunks = my::Chunks::filled_copy(value, len);
         Vector {
             data: chunks,
-            len: len
+            len,
+            growth: PhantomData,
         }
     }
 
@@ -29,29 +52,42 @@ impl<T: Display + Clone + Copy> Vector<T> {
     pub fn from_slice_copy(from: &[T]) -> Self {
         Self {
             data: my::Chunks::from_slice_copy(from),
-            len: from.len()
+            len: from.len(),
+            growth: PhantomData,
         }
     }
 
 }
 
-impl<T: Display + Clone> Vector<T> {
+impl<T> Vector<T> {
+    /// Doesn't allocate until the first element is added
     pub fn new() -> Self {
-        Self {
-            data: my::Chunks::alloc(1),
-            len: 0
+        Self::new_in(Global)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_in(capacity, Global)
+    }
+
+    // Constructor
+    /// # Safety
+    /// `ptr` must be allocated by the global allocator for `capacity` elements,
+    /// and the first `len` of them must be initialized (e.g. parts of a `Vec`).
+    pub unsafe fn from_raw_parts(ptr: *mut T, len: usize, capacity: usize) -> Self {
+        unsafe {
+            Self::from_raw_parts_in(ptr, len, capacity, Global)
         }
     }
+}
 
+impl<T: Clone> Vector<T> {
     // Constructor
     pub fn new_clone(value: T, len: usize) -> Self {
-        // Allocate at least something
-        let capacity = if len > 0 { len } else { 1 };
-
-        let chunks: my::Chunks<T> = my::Chunks::filled_clone(value, capacity);
+        let chunks: my::Chunks<T> = my::Chunks::filled_clone(value, len);
         Self {
             data: chunks,
-            len: len
+            len,
+            growth: PhantomData,
         }
     }
 
@@ -59,21 +95,72 @@ impl<T: Display + Clone> Vector<T> {
     pub fn from_slice_clone(from: &[T]) -> Self {
         Self {
             data: my::Chunks::from_slice_clone(from),
-            len: from.len()
+            len: from.len(),
+            growth: PhantomData,
+        }
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> Vector<T, A, G> {
+    pub fn new_in(alloc: A) -> Self {
+        Self::with_capacity_in(0, alloc)
+    }
+
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        Self {
+            data: my::Chunks::alloc_in(capacity, alloc),
+            len: 0,
+            growth: PhantomData,
         }
     }
 
     // Constructor
-    pub unsafe fn from_raw_parts(ptr: *mut T, len: usize, capacity: usize) -> Self {
+    /// # Safety
+    /// Same as for `from_raw_parts`, with `alloc` instead of the global allocator.
+    pub unsafe fn from_raw_parts_in(ptr: *mut T, len: usize, capacity: usize, alloc: A) -> Self {
         Self {
-            data: my::Chunks {
-                ptr: ptr,
-                count: capacity,
+            data: unsafe {
+                my::Chunks::from_raw_parts_in(ptr, capacity, alloc)
             },
-            len: len
+            len,
+            growth: PhantomData,
+        }
+    }
+
+    /// Takes over a buffer together with its elements
+    ///
+    /// # Safety
+    /// Slots `0..len` of `data` must be initialized.
+    pub(crate) unsafe fn from_chunks(data: my::Chunks<T, true, A>, len: usize) -> Self {
+        Self {
+            data,
+            len,
+            growth: PhantomData,
         }
     }
 
+    /// Gives the buffer away together with the number of initialized slots
+    pub(crate) fn into_chunks(self) -> (my::Chunks<T, true, A>, usize) {
+        let me = ManuallyDrop::new(self);
+        // Safety: `me` is never dropped, so the buffer gets exactly one owner
+        let data = unsafe {
+            ptr::read(&me.data)
+        };
+        (data, me.len)
+    }
+
+    pub fn allocator(&self) -> &A {
+        self.data.allocator()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
     pub fn len_bytes(&self) -> usize {
         self.len * size_of::<T>()
     }
@@ -112,31 +199,105 @@ impl<T: Display + Clone> Vector<T> {
         }
     }
 
+    #[inline]
     pub fn push(&mut self, elem: T) -> bool {
+        if let Err(err) = self.try_push(elem) {
+            handle_alloc_error(err);
+        }
+        true
+    }
+
+    /// Like `push`, but reports allocation failure instead of aborting.
+    /// On error the vector is left unchanged and `elem` is dropped.
+    pub fn try_push(&mut self, elem: T) -> Result<(), AllocError> {
         if self.len == self.data.count {
-            self.data.grow(1);
+            self.grow_amortized(1)?;
         }
 
-        self.data.write_index(self.len, elem);
+        // Safety: slot `len` is within capacity and holds no live value.
+        // Skips write_index() bounds check: this is the hot path of push()
+        unsafe {
+            self.as_mut_ptr().add(self.len).write(elem);
+        }
         self.len += 1;
-        true
+        Ok(())
+    }
+
+    /// Makes room for at least `additional` more elements without reallocation
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), AllocError> {
+        if additional <= self.capacity() - self.len {
+            return Ok(());
+        }
+        self.grow_amortized(additional)
+    }
+
+    /// Like `try_reserve`, but never allocates more than `len + additional`
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), AllocError> {
+        let required = self.len.checked_add(additional)
+            .ok_or(AllocError::CapacityOverflow)?;
+        if required <= self.capacity() {
+            return Ok(());
+        }
+
+        self.data.try_realloc(required)
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        if let Err(err) = self.try_reserve(additional) {
+            handle_alloc_error(err);
+        }
+    }
+
+    pub fn reserve_exact(&mut self, additional: usize) {
+        if let Err(err) = self.try_reserve_exact(additional) {
+            handle_alloc_error(err);
+        }
+    }
+
+    /// Lowers the capacity to `max(len, min_capacity)`; never grows
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        let new_capacity = self.len.max(min_capacity);
+        if new_capacity < self.capacity() {
+            self.data.realloc(new_capacity);
+        }
     }
 
+    pub fn shrink_to_fit(&mut self) {
+        self.shrink_to(0);
+    }
+
+    /// Slots `len..capacity`, to be written before extending `len` via `set_len`
+    pub fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<T>] {
+        let len = self.len;
+        &mut self.data.as_uninit_mut_slice()[len..]
+    }
+
+    /// # Safety
+    /// `new_len` must not exceed `capacity()`, and slots `len..new_len` must be initialized
+    pub unsafe fn set_len(&mut self, new_len: usize) {
+        self.len = new_len;
+    }
+
+    /// Shifts `index..len` right by one; `index == len` appends.
+    /// Returns false if `index > len`
     pub fn insert(&mut self, index: usize, elem: T) -> bool {
-        if self.len <= index {
+        if index > self.len {
             return false;
         }
 
-        if self.len == self.data.count {
-            self.data.grow(self.grow_strategy());
+        if self.len == self.capacity()
+            && let Err(err) = self.grow_amortized(1)
+        {
+            handle_alloc_error(err);
         }
 
-        // TODO Safety: ?
-        // TODO Why it introduces overhead if used in append()?
+        // Safety: capacity > len after growing, so both ranges are in the allocation.
+        // Raw pointers: slot `len` isn't initialized and `index + 1` may equal capacity
         unsafe {
+            let p = self.as_mut_ptr().add(index);
             ptr::copy(
-                &mut self.data[index],
-                &mut self.data[index + 1],
+                p,
+                p.add(1),
                 self.len - index,
             );
         }
@@ -150,14 +311,182 @@ impl<T: Display + Clone> Vector<T> {
     pub fn pop(&mut self) -> Option<T> {
         if self.len > 0 {
             self.len -= 1;
-            // Interesting: we can copy by reference, this is done automatically by compiler
-            // (bad design!)
-            Some(self.data[self.len].clone())
+            // Safety: slot was initialized and is no longer owned by the Vector,
+            // so the value is moved out rather than cloned
+            unsafe {
+                Some(ptr::read(self.as_ptr().add(self.len)))
+            }
         } else {
             None
         }
     }
 
+    /// Removes the element at `index`, shifting the tail left.
+    /// Panics if `index >= len`
+    pub fn remove(&mut self, index: usize) -> T {
+        if index >= self.len {
+            panic!("removal index (is {}) should be < len (is {})", index, self.len);
+        }
+
+        unsafe {
+            let p = self.as_mut_ptr().add(index);
+            let elem = ptr::read(p);
+            ptr::copy(p.add(1), p, self.len - index - 1);
+            self.len -= 1;
+            elem
+        }
+    }
+
+    /// Removes the element at `index`, moving the last element into its place. O(1)
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        if index >= self.len {
+            panic!("swap_remove index (is {}) should be < len (is {})", index, self.len);
+        }
+
+        unsafe {
+            let base = self.as_mut_ptr();
+            let elem = ptr::read(base.add(index));
+            // Overlapping when index is the last element: copy() handles it
+            ptr::copy(base.add(self.len - 1), base.add(index), 1);
+            self.len -= 1;
+            elem
+        }
+    }
+
+    /// Drops elements `len..self.len`, keeping the capacity
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+
+        let tail: *mut [T] = unsafe {
+            ptr::slice_from_raw_parts_mut(self.as_mut_ptr().add(len), self.len - len)
+        };
+        // Shrink first: if a destructor panics, the rest is leaked, not double-dropped
+        self.len = len;
+        unsafe {
+            ptr::drop_in_place(tail);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    pub fn resize_with<F: FnMut() -> T>(&mut self, new_len: usize, mut f: F) {
+        if new_len <= self.len {
+            self.truncate(new_len);
+            return;
+        }
+
+        self.reserve(new_len - self.len);
+        while self.len < new_len {
+            self.push(f());
+        }
+    }
+
+    /// Keeps only the elements for which `f` returns true, preserving their order
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut f: F) {
+        self.retain_mut(|elem| f(elem));
+    }
+
+    pub fn retain_mut<F: FnMut(&mut T) -> bool>(&mut self, mut f: F) {
+        let mut gap = self.gap(0);
+        let (base, original_len) = (gap.base, gap.original_len);
+
+        while gap.read < original_len {
+            unsafe {
+                let cur = base.add(gap.read);
+                if !f(&mut *cur) {
+                    // Advance first: a panicking destructor must not see it dropped twice
+                    gap.read += 1;
+                    ptr::drop_in_place(cur);
+                } else {
+                    if gap.read != gap.write {
+                        ptr::copy_nonoverlapping(cur, base.add(gap.write), 1);
+                    }
+                    gap.read += 1;
+                    gap.write += 1;
+                }
+            }
+        }
+    }
+
+    /// Removes consecutive elements for which `same_bucket(current, previous_kept)` is true
+    pub fn dedup_by<F: FnMut(&mut T, &mut T) -> bool>(&mut self, mut same_bucket: F) {
+        let original_len = self.len;
+        if original_len <= 1 {
+            return;
+        }
+
+        let mut gap = self.gap(1);
+        let base = gap.base;
+
+        while gap.read < original_len {
+            unsafe {
+                let cur = base.add(gap.read);
+                let prev = base.add(gap.write - 1);
+                if same_bucket(&mut *cur, &mut *prev) {
+                    gap.read += 1;
+                    ptr::drop_in_place(cur);
+                } else {
+                    if gap.read != gap.write {
+                        ptr::copy_nonoverlapping(cur, base.add(gap.write), 1);
+                    }
+                    gap.read += 1;
+                    gap.write += 1;
+                }
+            }
+        }
+    }
+
+    pub fn dedup_by_key<K: PartialEq, F: FnMut(&mut T) -> K>(&mut self, mut key: F) {
+        self.dedup_by(|a, b| key(a) == key(b));
+    }
+
+    /// Removes `range` from the Vector, yielding the removed elements.
+    /// Elements not consumed are dropped with the iterator. Panics if out of bounds
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> Drain<'_, T, A, G> {
+        let range = slice_range(range, self.len);
+        Drain::new(self, range.start, range.end)
+    }
+
+    /// Replaces `range` with the items of `replace_with`, yielding the removed elements.
+    /// The replacement is done when the returned iterator is dropped
+    pub fn splice<R, I>(&mut self, range: R, replace_with: I) -> Splice<'_, I::IntoIter, A, G>
+    where
+        R: RangeBounds<usize>,
+        I: IntoIterator<Item = T>,
+    {
+        Splice {
+            drain: self.drain(range),
+            replace_with: replace_with.into_iter(),
+        }
+    }
+
+    /// Lazily removes elements of `range` for which `filter` returns true, yielding them
+    pub fn extract_if<F, R>(&mut self, range: R, filter: F) -> ExtractIf<'_, T, F, A, G>
+    where
+        F: FnMut(&mut T) -> bool,
+        R: RangeBounds<usize>,
+    {
+        let range = slice_range(range, self.len);
+        ExtractIf::new(self, range.start, range.end, filter)
+    }
+
+    /// Moves all elements of `other` to the end of `self`, leaving `other` empty
+    pub fn append(&mut self, other: &mut Self) {
+        let count = other.len;
+        self.reserve(count);
+
+        unsafe {
+            ptr::copy_nonoverlapping(other.as_ptr(), self.as_mut_ptr().add(self.len), count);
+        }
+        // Elements are owned by `self` now
+        other.len = 0;
+        self.len += count;
+    }
+
     pub fn capacity(&self) -> usize {
         self.data.count
     }
@@ -166,37 +495,153 @@ impl<T: Display + Clone> Vector<T> {
 
     fn bounds(&self, index: usize) -> bool {
         // TODO Turn BOUNDS_CHECK off for self.data
-        0 <= index && index < self.len
+        index < self.len
+    }
+
+    /// Reallocates for `additional` more elements, growing as the policy `G` says.
+    /// Shared by every path that adds elements, so N pushes cost O(N) in total.
+    /// Callers check that `additional` doesn't fit first, keeping this off the hot path.
+    #[cold]
+    fn grow_amortized(&mut self, additional: usize) -> Result<(), AllocError> {
+        let required = self.len.checked_add(additional)
+            .ok_or(AllocError::CapacityOverflow)?;
+
+        let new_capacity = G::grow(self.capacity(), required, mem::size_of::<T>());
+        self.data.try_realloc(new_capacity)
+    }
+
+    /// Starts compacting in place with `0..start` kept, see `Gap`
+    pub(crate) fn gap(&mut self, start: usize) -> Gap<'_, T> {
+        Gap {
+            original_len: self.len,
+            base: self.as_mut_ptr(),
+            read: start,
+            write: start,
+            len: &mut self.len,
+        }
+    }
+}
+
+impl<T: Clone, A: Allocator, G: GrowthPolicy> Vector<T, A, G> {
+    pub fn resize(&mut self, new_len: usize, value: T) {
+        self.resize_with(new_len, || value.clone());
     }
 
-    /// Given the current capacity & size of an elem, returns new capacity to grow by
-    fn grow_strategy(&self) -> usize {
-        match self.capacity() {
-            0..3 => 4,
-            _ => self.capacity() * 2
+    pub fn extend_from_slice(&mut self, other: &[T]) {
+        self.reserve(other.len());
+        for elem in other {
+            self.push(elem.clone());
+        }
+    }
+
+    /// Appends clones of `self[src]`. Panics if the range is out of bounds
+    pub fn extend_from_within<R: RangeBounds<usize>>(&mut self, src: R) {
+        let range = slice_range(src, self.len);
+        self.reserve(range.len());
+        for i in range {
+            let elem = self[i].clone();
+            self.push(elem);
         }
     }
 }
 
+impl<T: PartialEq, A: Allocator, G: GrowthPolicy> Vector<T, A, G> {
+    /// Removes consecutive repeated elements
+    pub fn dedup(&mut self) {
+        self.dedup_by(|a, b| a == b);
+    }
+}
+
+impl<T, A: Allocator + Clone, G: GrowthPolicy> Vector<T, A, G> {
+    /// Splits into `[0, at)` kept in `self` and `[at, len)` returned in a new Vector.
+    /// Panics if `at > len`
+    pub fn split_off(&mut self, at: usize) -> Self {
+        if at > self.len {
+            panic!("`at` split index (is {}) should be <= len (is {})", at, self.len);
+        }
+
+        let count = self.len - at;
+        let mut other = Self::with_capacity_in(count, self.allocator().clone());
+        unsafe {
+            ptr::copy_nonoverlapping(self.as_ptr().add(at), other.as_mut_ptr(), count);
+        }
+        self.len = at;
+        other.len = count;
+        other
+    }
+}
+
+/// Compacts a Vector while elements are removed in place (retain, dedup).
+///
+/// Elements `write..read` are holes. On drop, including unwinding out of a user
+/// closure, the unprocessed tail `read..original_len` is moved over the holes
+/// and `len` is fixed up, so nothing is dropped twice or exposed uninitialized.
+pub(crate) struct Gap<'a, T> {
+    pub(crate) len: &'a mut usize,
+    pub(crate) base: *mut T,
+    pub(crate) read: usize,
+    pub(crate) write: usize,
+    pub(crate) original_len: usize,
+}
+
+impl<T> Drop for Gap<'_, T> {
+    fn drop(&mut self) {
+        let tail = self.original_len - self.read;
+        if self.read != self.write {
+            unsafe {
+                ptr::copy(self.base.add(self.read), self.base.add(self.write), tail);
+            }
+        }
+        *self.len = self.write + tail;
+    }
+}
+
+/// Resolves `range` against a container of `len` elements. Panics like slice indexing
+pub(crate) fn slice_range<R: RangeBounds<usize>>(range: R, len: usize) -> Range<usize> {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start.checked_add(1)
+            .unwrap_or_else(|| panic!("attempted to index slice from after maximum usize")),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end.checked_add(1)
+            .unwrap_or_else(|| panic!("attempted to index slice up to maximum usize")),
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => len,
+    };
+
+    if start > end {
+        panic!("slice index starts at {} but ends at {}", start, end);
+    }
+    if end > len {
+        panic!("range end index {} out of range for slice of length {}", end, len);
+    }
+    start..end
+}
+
 // ======== CONSEC ========
 
 pub trait ConsecConstrucor {
     fn consec(len: usize) -> Self;
 }
 
-impl<T: Display + From<usize> + Clone> ConsecConstrucor for Vector<T> {
+impl<T: From<usize>> ConsecConstrucor for Vector<T> {
     // Constructor
     fn consec(len: usize) -> Self {
         let mut obj = Self {
             data: my::Chunks::alloc(len),
-            len: len
+            len,
+            growth: PhantomData,
         };
 
         for i in 0..len {
             // non-primitive cast: `usize` as `T`
             // Why does it occur?
             // obj[i] = (i + 1) as T;
-            obj[i] = T::from(i + 1);
+
+            // Slots are uninitialized: assigning via index would drop garbage
+            obj.data.write_index(i, T::from(i + 1));
         }
         obj
     }
@@ -205,8 +650,10 @@ impl<T: Display + From<usize> + Clone> ConsecConstrucor for Vector<T> {
 // ======== INDEX ========
 
 impl<
-    T: Display + Clone,
-> Index<usize> for Vector<T> {
+    T,
+    A: Allocator,
+    G: GrowthPolicy,
+> Index<usize> for Vector<T, A, G> {
     type Output = T;
 
     fn index(&self, index: usize) -> &Self::Output {
@@ -219,8 +666,10 @@ impl<
 }
 
 impl<
-    T: Display + Clone,
-> IndexMut<usize> for Vector<T> {
+    T,
+    A: Allocator,
+    G: GrowthPolicy,
+> IndexMut<usize> for Vector<T, A, G> {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
         if !self.bounds(index) {
             panic!("Index out of bounds");
@@ -233,7 +682,7 @@ impl<
 // ======== DEREF ========
 // Automatically implements iter(). How it works?
 
-impl<T: Display + Clone> Deref for Vector<T> {
+impl<T, A: Allocator, G: GrowthPolicy> Deref for Vector<T, A, G> {
     type Target = [T];
 
     fn deref(&self) -> &[T] {
@@ -241,38 +690,203 @@ impl<T: Display + Clone> Deref for Vector<T> {
     }
 }
 
-impl<T: Display + Clone> DerefMut for Vector<T> {
+impl<T, A: Allocator, G: GrowthPolicy> DerefMut for Vector<T, A, G> {
     fn deref_mut(&mut self) -> &mut [T] {
         self.as_mut_slice()
     }
 }
 
+// ======== DEFAULT ========
+
+impl<T, A: Allocator + Default, G: GrowthPolicy> Default for Vector<T, A, G> {
+    fn default() -> Self {
+        Self::new_in(A::default())
+    }
+}
+
 // ======== ITERATOR ========
 
-impl<T: Display + Clone> FromIterator<T> for Vector<T> {
+impl<T, G: GrowthPolicy> FromIterator<T> for Vector<T, Global, G> {
     fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> Self {
-        let mut c = Self::new();
+        let mut c = Self::new_in(Global);
+        c.extend(iter);
+        c
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> IntoIterator for Vector<T, A, G> {
+    type Item = T;
+    type IntoIter = IntoIter<T, A>;
+
+    fn into_iter(self) -> IntoIter<T, A> {
+        let (buf, len) = self.into_chunks();
+        IntoIter::new(buf, len)
+    }
+}
+
+impl<'a, T, A: Allocator, G: GrowthPolicy> IntoIterator for &'a Vector<T, A, G> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> slice::Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T, A: Allocator, G: GrowthPolicy> IntoIterator for &'a mut Vector<T, A, G> {
+    type Item = &'a mut T;
+    type IntoIter = slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> slice::IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> Extend<T> for Vector<T, A, G> {
+    fn extend<I: IntoIterator<Item=T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        // Lower bound of size_hint is a safe guess: never over-allocates
+        let additional = iter.size_hint().0;
+        if additional > self.capacity() - self.len
+            && let Err(err) = self.grow_amortized(additional)
+        {
+            handle_alloc_error(err);
+        }
 
         for i in iter {
-            c.push(i);
+            self.push(i);
         }
+    }
+}
 
-        c
+impl<'a, T: Copy + 'a, A: Allocator, G: GrowthPolicy> Extend<&'a T> for Vector<T, A, G> {
+    fn extend<I: IntoIterator<Item=&'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
     }
 }
 
 // ================== DROP ==================
 
-//impl<T: Display + Clone> Drop for Vector<T> {
-//    fn drop(&mut self) {
-//        drop(self.data);
-//    }
-//}
+impl<T, A: Allocator, G: GrowthPolicy> Drop for Vector<T, A, G> {
+    fn drop(&mut self) {
+        // Only elements are dropped here; memory is freed by Chunks afterwards
+        unsafe {
+            ptr::drop_in_place(self.as_mut_slice());
+        }
+    }
+}
+
+// ======== FMT ========
+
+impl<T: fmt::Debug, A: Allocator, G: GrowthPolicy> fmt::Debug for Vector<T, A, G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+// ======== CMP & HASH ========
+// Compared and hashed as slices: allocator and growth policy don't matter
+
+impl<T, U, A1, A2, G1, G2> PartialEq<Vector<U, A2, G2>> for Vector<T, A1, G1>
+where
+    T: PartialEq<U>,
+    A1: Allocator,
+    A2: Allocator,
+    G1: GrowthPolicy,
+    G2: GrowthPolicy,
+{
+    fn eq(&self, other: &Vector<U, A2, G2>) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T: PartialEq<U>, U, A: Allocator, G: GrowthPolicy> PartialEq<[U]> for Vector<T, A, G> {
+    fn eq(&self, other: &[U]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+impl<T: PartialEq<U>, U, A: Allocator, G: GrowthPolicy> PartialEq<&[U]> for Vector<T, A, G> {
+    fn eq(&self, other: &&[U]) -> bool {
+        self.as_slice() == *other
+    }
+}
+
+impl<T: PartialEq<U>, U, A: Allocator, G: GrowthPolicy, const N: usize> PartialEq<[U; N]> for Vector<T, A, G> {
+    fn eq(&self, other: &[U; N]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+impl<T: PartialEq<U>, U, A: Allocator, G: GrowthPolicy> PartialEq<Vec<U>> for Vector<T, A, G> {
+    fn eq(&self, other: &Vec<U>) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T: Eq, A: Allocator, G: GrowthPolicy> Eq for Vector<T, A, G> {}
+
+impl<T: PartialOrd, A: Allocator, G: GrowthPolicy> PartialOrd for Vector<T, A, G> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.as_slice().partial_cmp(other.as_slice())
+    }
+}
+
+impl<T: Ord, A: Allocator, G: GrowthPolicy> Ord for Vector<T, A, G> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_slice().cmp(other.as_slice())
+    }
+}
+
+impl<T: Hash, A: Allocator, G: GrowthPolicy> Hash for Vector<T, A, G> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state);
+    }
+}
+
+// ======== AS_REF & BORROW ========
+
+impl<T, A: Allocator, G: GrowthPolicy> AsRef<[T]> for Vector<T, A, G> {
+    fn as_ref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> AsMut<[T]> for Vector<T, A, G> {
+    fn as_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> Borrow<[T]> for Vector<T, A, G> {
+    fn borrow(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> BorrowMut<[T]> for Vector<T, A, G> {
+    fn borrow_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+// ======== CLONE ========
+
+impl<T: Clone, A: Allocator + Clone, G: GrowthPolicy> Clone for Vector<T, A, G> {
+    fn clone(&self) -> Self {
+        let mut c = Self::with_capacity_in(self.len, self.allocator().clone());
+
+        for elem in self.iter() {
+            c.push(elem.clone());
+        }
+        c
+    }
+}
 
 // ======== FROM & INTO ========
 
-impl<T: Display + Clone> From<Vec<T>> for Vector<T> {
-    fn from(mut value: Vec<T>) -> Self {
+impl<T> From<Vec<T>> for Vector<T> {
+    fn from(value: Vec<T>) -> Self {
         // Disable drop of Vec
         let mut value = ManuallyDrop::new(value);
 
@@ -286,12 +900,12 @@ impl<T: Display + Clone> From<Vec<T>> for Vector<T> {
     }
 }
 
-impl<T: Display + Clone> Into<Vec<T>> for Vector<T> {
-    fn into(self) -> Vec<T> {
+impl<T> From<Vector<T>> for Vec<T> {
+    fn from(value: Vector<T>) -> Self {
         // --> Will lead to dobule-free
-        //let mut _self = self;
+        //let mut _self = value;
 
-        let mut _self = ManuallyDrop::new(self);
+        let mut _self = ManuallyDrop::new(value);
         unsafe {
             Vec::from_raw_parts(
                 _self.as_mut_ptr(),
@@ -302,3 +916,61 @@ impl<T: Display + Clone> Into<Vec<T>> for Vector<T> {
     }
 }
 
+impl<T: Clone> From<&[T]> for Vector<T> {
+    fn from(value: &[T]) -> Self {
+        Self::from_slice_clone(value)
+    }
+}
+
+impl<T: Clone> From<&mut [T]> for Vector<T> {
+    fn from(value: &mut [T]) -> Self {
+        Self::from_slice_clone(value)
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for Vector<T> {
+    fn from(value: [T; N]) -> Self {
+        let mut v = Self::with_capacity(N);
+        // Elements are moved into the Vector, the array must not drop them
+        let value = ManuallyDrop::new(value);
+        unsafe {
+            ptr::copy_nonoverlapping(value.as_ptr(), v.as_mut_ptr(), N);
+        }
+        v.len = N;
+        v
+    }
+}
+
+/// Fails with the original Vector if its length isn't exactly `N`
+impl<T, A: Allocator, G: GrowthPolicy, const N: usize> TryFrom<Vector<T, A, G>> for [T; N] {
+    type Error = Vector<T, A, G>;
+
+    fn try_from(mut value: Vector<T, A, G>) -> Result<Self, Self::Error> {
+        if value.len != N {
+            return Err(value);
+        }
+
+        // Elements are moved out: the Vector only frees memory on drop
+        value.len = 0;
+        unsafe {
+            Ok(ptr::read(value.as_ptr() as *const [T; N]))
+        }
+    }
+}
+
+impl<T> From<Box<[T]>> for Vector<T> {
+    fn from(value: Box<[T]>) -> Self {
+        let len = value.len();
+        // Box<[T]> is allocated by the global allocator with the layout of `len` elements
+        let ptr = Box::into_raw(value) as *mut T;
+        unsafe {
+            Vector::from_raw_parts(ptr, len, len)
+        }
+    }
+}
+
+impl<T> From<Vector<T>> for Box<[T]> {
+    fn from(value: Vector<T>) -> Self {
+        Vec::from(value).into_boxed_slice()
+    }
+}
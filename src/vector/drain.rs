@@ -0,0 +1,158 @@
+use std::fmt;
+use std::iter::FusedIterator;
+use std::ptr;
+use std::slice;
+
+use crate::allocator::{Allocator, Global, handle_alloc_error};
+use crate::growth::{Doubling, GrowthPolicy};
+use super::Vector;
+
+/// Removes a range from a `Vector`, created by `Vector::drain()`.
+///
+/// While alive, the Vector is shortened to the part before the range: if the
+/// Drain is leaked (`mem::forget`), the drained and tail elements leak too,
+/// but nothing is ever dropped twice. On drop, elements not yielded are
+/// dropped and the tail is moved back to close the gap.
+pub struct Drain<'a, T, A: Allocator = Global, G: GrowthPolicy = Doubling> {
+    pub(super) vec: &'a mut Vector<T, A, G>,
+    // Elements not yielded yet
+    pub(super) start: usize,
+    pub(super) end: usize,
+    // Elements after the drained range, moved back on drop
+    pub(super) tail_start: usize,
+    pub(super) tail_len: usize,
+}
+
+impl<'a, T, A: Allocator, G: GrowthPolicy> Drain<'a, T, A, G> {
+    pub(super) fn new(vec: &'a mut Vector<T, A, G>, start: usize, end: usize) -> Self {
+        let len = vec.len;
+        vec.len = start;
+        Self {
+            vec,
+            start,
+            end,
+            tail_start: end,
+            tail_len: len - end,
+        }
+    }
+
+    /// Elements not yielded yet
+    pub fn as_slice(&self) -> &[T] {
+        unsafe {
+            slice::from_raw_parts(self.vec.as_ptr().add(self.start), self.end - self.start)
+        }
+    }
+
+    pub fn allocator(&self) -> &A {
+        self.vec.allocator()
+    }
+
+    /// Writes items from `iter` into the gap `vec.len..tail_start`.
+    /// Returns true if the whole gap was filled
+    pub(super) fn fill<I: Iterator<Item = T>>(&mut self, iter: &mut I) -> bool {
+        while self.vec.len < self.tail_start {
+            match iter.next() {
+                Some(elem) => unsafe {
+                    self.vec.as_mut_ptr().add(self.vec.len).write(elem);
+                    self.vec.len += 1;
+                },
+                None => return false,
+            }
+        }
+        true
+    }
+
+    /// Widens the gap by `additional` slots, moving the tail to the right
+    pub(super) fn move_tail(&mut self, additional: usize) {
+        // Reserve relative to the end of the tail, not to the shortened len
+        let len = self.vec.len;
+        self.vec.len = self.tail_start + self.tail_len;
+        let res = self.vec.try_reserve(additional);
+        self.vec.len = len;
+        if let Err(err) = res {
+            handle_alloc_error(err);
+        }
+
+        let new_tail_start = self.tail_start + additional;
+        unsafe {
+            let base = self.vec.as_mut_ptr();
+            ptr::copy(base.add(self.tail_start), base.add(new_tail_start), self.tail_len);
+        }
+        self.tail_start = new_tail_start;
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> Iterator for Drain<'_, T, A, G> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.start == self.end {
+            return None;
+        }
+
+        let elem = unsafe {
+            ptr::read(self.vec.as_ptr().add(self.start))
+        };
+        self.start += 1;
+        Some(elem)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.start;
+        (len, Some(len))
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> DoubleEndedIterator for Drain<'_, T, A, G> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.start == self.end {
+            return None;
+        }
+
+        self.end -= 1;
+        unsafe {
+            Some(ptr::read(self.vec.as_ptr().add(self.end)))
+        }
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> ExactSizeIterator for Drain<'_, T, A, G> {}
+
+impl<T, A: Allocator, G: GrowthPolicy> FusedIterator for Drain<'_, T, A, G> {}
+
+impl<T: fmt::Debug, A: Allocator, G: GrowthPolicy> fmt::Debug for Drain<'_, T, A, G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Drain").field(&self.as_slice()).finish()
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> Drop for Drain<'_, T, A, G> {
+    fn drop(&mut self) {
+        // Moves the tail back even if dropping the remaining elements panics
+        struct TailGuard<'r, 'a, T, A: Allocator, G: GrowthPolicy>(&'r mut Drain<'a, T, A, G>);
+
+        impl<T, A: Allocator, G: GrowthPolicy> Drop for TailGuard<'_, '_, T, A, G> {
+            fn drop(&mut self) {
+                let drain = &mut *self.0;
+                let start = drain.vec.len;
+                if drain.tail_start != start {
+                    unsafe {
+                        let base = drain.vec.as_mut_ptr();
+                        ptr::copy(base.add(drain.tail_start), base.add(start), drain.tail_len);
+                    }
+                }
+                drain.vec.len = start + drain.tail_len;
+            }
+        }
+
+        let remaining: *mut [T] = unsafe {
+            ptr::slice_from_raw_parts_mut(self.vec.as_mut_ptr().add(self.start), self.end - self.start)
+        };
+        self.start = self.end;
+
+        let _guard = TailGuard(self);
+        unsafe {
+            ptr::drop_in_place(remaining);
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use std::fmt;
+use std::ptr;
+
+use crate::allocator::{Allocator, Global};
+use crate::growth::{Doubling, GrowthPolicy};
+use super::Vector;
+
+/// Removes and yields elements matching a predicate, created by `Vector::extract_if()`.
+///
+/// Elements that don't match are kept in order. Dropping the iterator early
+/// keeps all elements not visited yet. If the predicate panics, the element
+/// being tested and the rest stay in the Vector.
+pub struct ExtractIf<'a, T, F, A: Allocator = Global, G: GrowthPolicy = Doubling>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    vec: &'a mut Vector<T, A, G>,
+    // Next element to test
+    idx: usize,
+    // Exclusive end of the range to test
+    end: usize,
+    // Number of elements extracted so far
+    del: usize,
+    old_len: usize,
+    pred: F,
+}
+
+impl<'a, T, F, A: Allocator, G: GrowthPolicy> ExtractIf<'a, T, F, A, G>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    pub(super) fn new(vec: &'a mut Vector<T, A, G>, start: usize, end: usize, pred: F) -> Self {
+        let old_len = vec.len;
+        // Elements are shuffled in place: hide them all while the iterator lives
+        vec.len = 0;
+        Self {
+            vec,
+            idx: start,
+            end,
+            del: 0,
+            old_len,
+            pred,
+        }
+    }
+}
+
+impl<T, F, A: Allocator, G: GrowthPolicy> Iterator for ExtractIf<'_, T, F, A, G>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let base = self.vec.as_mut_ptr();
+        while self.idx < self.end {
+            unsafe {
+                let cur = base.add(self.idx);
+                let extracted = (self.pred)(&mut *cur);
+                // Advance only after the predicate: on panic `cur` stays in the Vector
+                self.idx += 1;
+                if extracted {
+                    self.del += 1;
+                    return Some(ptr::read(cur));
+                } else if self.del > 0 {
+                    ptr::copy_nonoverlapping(cur, base.add(self.idx - 1 - self.del), 1);
+                }
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.end - self.idx))
+    }
+}
+
+impl<T, F, A: Allocator, G: GrowthPolicy> Drop for ExtractIf<'_, T, F, A, G>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    fn drop(&mut self) {
+        if self.del > 0 && self.idx < self.old_len {
+            unsafe {
+                let base = self.vec.as_mut_ptr();
+                ptr::copy(base.add(self.idx), base.add(self.idx - self.del), self.old_len - self.idx);
+            }
+        }
+        self.vec.len = self.old_len - self.del;
+    }
+}
+
+impl<T: fmt::Debug, F, A: Allocator, G: GrowthPolicy> fmt::Debug for ExtractIf<'_, T, F, A, G>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let peek = if self.idx < self.end {
+            unsafe { Some(&*self.vec.as_ptr().add(self.idx)) }
+        } else {
+            None
+        };
+        f.debug_struct("ExtractIf").field("peek", &peek).finish_non_exhaustive()
+    }
+}
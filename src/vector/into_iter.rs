@@ -0,0 +1,101 @@
+use std::fmt;
+use std::iter::FusedIterator;
+use std::ptr;
+use std::slice;
+
+use crate::allocator::{Allocator, Global};
+use crate::chunks::Chunks;
+
+/// Moves elements out of a `Vector`, created by `Vector::into_iter()`.
+///
+/// Owns the buffer: elements `start..end` are still live, everything
+/// outside was either yielded or never initialized.
+pub struct IntoIter<T, A: Allocator = Global> {
+    buf: Chunks<T, true, A>,
+    start: usize,
+    end: usize,
+}
+
+impl<T, A: Allocator> IntoIter<T, A> {
+    pub(super) fn new(buf: Chunks<T, true, A>, len: usize) -> Self {
+        Self {
+            buf,
+            start: 0,
+            end: len,
+        }
+    }
+
+    /// Elements not yielded yet
+    pub fn as_slice(&self) -> &[T] {
+        unsafe {
+            slice::from_raw_parts(self.buf.as_ptr().add(self.start), self.end - self.start)
+        }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe {
+            slice::from_raw_parts_mut(self.buf.as_mut_ptr().add(self.start), self.end - self.start)
+        }
+    }
+
+    pub fn allocator(&self) -> &A {
+        self.buf.allocator()
+    }
+}
+
+impl<T, A: Allocator> Iterator for IntoIter<T, A> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.start == self.end {
+            return None;
+        }
+
+        // Safety: slot is live; advancing `start` hands its ownership to the caller
+        let elem = unsafe {
+            ptr::read(self.buf.as_ptr().add(self.start))
+        };
+        self.start += 1;
+        Some(elem)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.start;
+        (len, Some(len))
+    }
+}
+
+impl<T, A: Allocator> DoubleEndedIterator for IntoIter<T, A> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.start == self.end {
+            return None;
+        }
+
+        self.end -= 1;
+        unsafe {
+            Some(ptr::read(self.buf.as_ptr().add(self.end)))
+        }
+    }
+}
+
+impl<T, A: Allocator> ExactSizeIterator for IntoIter<T, A> {}
+
+impl<T, A: Allocator> FusedIterator for IntoIter<T, A> {}
+
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for IntoIter<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IntoIter").field(&self.as_slice()).finish()
+    }
+}
+
+impl<T, A: Allocator> Drop for IntoIter<T, A> {
+    fn drop(&mut self) {
+        let remaining: *mut [T] = self.as_mut_slice();
+        // Mark as yielded first: a panicking destructor leaks the rest instead of re-dropping
+        self.start = self.end;
+        unsafe {
+            ptr::drop_in_place(remaining);
+        }
+        // `buf` frees the memory afterwards
+    }
+}
@@ -0,0 +1,226 @@
+use std::cmp::Ordering;
+
+use crate::allocator::Allocator;
+use crate::growth::GrowthPolicy;
+use super::Vector;
+use super::sort::{self, INSERTION_THRESHOLD};
+
+// Unbalanced partitions tolerated before selection falls back to median of medians
+const SELECT_LIMIT: u32 = 16;
+
+// ======== VECTOR API ========
+
+impl<T, A: Allocator, G: GrowthPolicy> Vector<T, A, G> {
+    /// Reorders so that the element at `index` is the one a full sort would put there:
+    /// smaller ones before it, greater ones after, both sides in no particular order.
+    /// Quickselect with a median-of-medians fallback: O(n) worst case. Panics if `index >= len`.
+    pub fn select_nth_unstable(&mut self, index: usize) -> (&mut [T], &mut T, &mut [T])
+    where
+        T: Ord,
+    {
+        self.select_nth_unstable_by(index, T::cmp)
+    }
+
+    pub fn select_nth_unstable_by<F: FnMut(&T, &T) -> Ordering>(
+        &mut self,
+        index: usize,
+        mut compare: F,
+    ) -> (&mut [T], &mut T, &mut [T]) {
+        assert!(index < self.len, "select index {} out of range for length {}", index, self.len);
+
+        select(self.as_mut_slice(), index, &mut |a, b| compare(a, b) == Ordering::Less);
+        let (left, rest) = self.as_mut_slice().split_at_mut(index);
+        let (nth, right) = rest.split_first_mut().unwrap();
+        (left, nth, right)
+    }
+
+    pub fn select_nth_unstable_by_key<K: Ord, F: FnMut(&T) -> K>(
+        &mut self,
+        index: usize,
+        mut f: F,
+    ) -> (&mut [T], &mut T, &mut [T]) {
+        self.select_nth_unstable_by(index, |a, b| f(a).cmp(&f(b)))
+    }
+
+    /// Moves the `k` greatest elements to the front, greatest first, and returns them.
+    /// O(n + k log k) worst case; the rest of the Vector is left in no particular order.
+    pub fn top_k(&mut self, k: usize) -> &mut [T]
+    where
+        T: Ord,
+    {
+        self.top_k_by(k, T::cmp)
+    }
+
+    pub fn top_k_by<F: FnMut(&T, &T) -> Ordering>(&mut self, k: usize, mut compare: F) -> &mut [T] {
+        let k = k.min(self.len);
+        let v = self.as_mut_slice();
+        if k == 0 {
+            return &mut v[..0];
+        }
+
+        let mut is_greater = |a: &T, b: &T| compare(a, b) == Ordering::Greater;
+        if k < v.len() {
+            select(v, k - 1, &mut is_greater);
+        }
+        sort::quicksort(&mut v[..k], &mut is_greater);
+        &mut v[..k]
+    }
+
+    pub fn top_k_by_key<K: Ord, F: FnMut(&T) -> K>(&mut self, k: usize, mut f: F) -> &mut [T] {
+        self.top_k_by(k, |a, b| f(a).cmp(&f(b)))
+    }
+
+    /// Moves elements matching `pred` before the others, returns how many matched.
+    /// Unstable, in place, `pred` is called once per element.
+    pub fn partition_in_place<P: FnMut(&T) -> bool>(&mut self, mut pred: P) -> usize {
+        let v = self.as_mut_slice();
+        let mut l = 0;
+        let mut r = v.len();
+        loop {
+            while l < r && pred(&v[l]) {
+                l += 1;
+            }
+            // `v[l]` is known to fail, don't test it again
+            while r > l + 1 && !pred(&v[r - 1]) {
+                r -= 1;
+            }
+            if r <= l + 1 {
+                return l;
+            }
+            r -= 1;
+            v.swap(l, r);
+            l += 1;
+        }
+    }
+
+    /// Index of the first element for which `pred` is false.
+    /// The Vector must be partitioned by `pred`: all `true` elements first. O(log n).
+    pub fn partition_point<P: FnMut(&T) -> bool>(&self, mut pred: P) -> usize {
+        let v = self.as_slice();
+        let mut lo = 0;
+        let mut hi = v.len();
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if pred(&v[mid]) {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+        lo
+    }
+
+    pub fn is_sorted(&self) -> bool
+    where
+        T: PartialOrd,
+    {
+        self.is_sorted_by(|a, b| a <= b)
+    }
+
+    /// `compare(a, b)` must hold for every adjacent pair `a`, `b`
+    pub fn is_sorted_by<F: FnMut(&T, &T) -> bool>(&self, mut compare: F) -> bool {
+        self.as_slice().windows(2).all(|w| compare(&w[0], &w[1]))
+    }
+
+    pub fn is_sorted_by_key<K: PartialOrd, F: FnMut(&T) -> K>(&self, mut f: F) -> bool {
+        self.is_sorted_by(|a, b| f(a) <= f(b))
+    }
+
+    /// Unstable heap sort: O(n log n) worst case, no extra memory
+    pub fn sort_heap(&mut self)
+    where
+        T: Ord,
+    {
+        self.sort_heap_by(T::cmp);
+    }
+
+    pub fn sort_heap_by<F: FnMut(&T, &T) -> Ordering>(&mut self, mut compare: F) {
+        sort::heapsort(self.as_mut_slice(), &mut |a, b| compare(a, b) == Ordering::Less);
+    }
+}
+
+// ======== QUICKSELECT ========
+
+// Narrows down to the side holding `index`, partitioning like `sort::quicksort`
+fn select<T, F: FnMut(&T, &T) -> bool>(mut v: &mut [T], mut index: usize, is_less: &mut F) {
+    let mut limit = SELECT_LIMIT;
+    // Pivot of the enclosing partition: not greater than anything in `v`
+    let mut pred: Option<&T> = None;
+
+    loop {
+        let len = v.len();
+        if len <= INSERTION_THRESHOLD {
+            sort::insertion_sort(v, 1, is_less);
+            return;
+        }
+
+        if limit == 0 {
+            median_of_medians(v, index, is_less);
+            return;
+        }
+
+        let (pivot, _) = sort::choose_pivot(v, is_less);
+
+        // Many copies of the predecessor: skip all of them at once
+        if let Some(p) = pred
+            && !is_less(p, &v[pivot])
+        {
+            let mid = sort::partition_equal(v, pivot, is_less);
+            if index < mid {
+                return;
+            }
+            v = &mut v[mid..];
+            index -= mid;
+            continue;
+        }
+
+        let (mid, _) = sort::partition(v, pivot, is_less);
+        if mid.min(len - mid) < len / 8 {
+            limit -= 1;
+        }
+
+        match index.cmp(&mid) {
+            Ordering::Equal => return,
+            Ordering::Less => v = &mut v[..mid],
+            Ordering::Greater => {
+                let (pivot, right) = v[mid..].split_first_mut().unwrap();
+                pred = Some(pivot);
+                v = right;
+                index -= mid + 1;
+            }
+        }
+    }
+}
+
+// Linear in the worst case: the pivot is the median of the medians of groups of five,
+// so at least 3/10 of the elements are smaller and 3/10 greater, unless equal to it
+fn median_of_medians<T, F: FnMut(&T, &T) -> bool>(mut v: &mut [T], mut index: usize, is_less: &mut F) {
+    loop {
+        let len = v.len();
+        if len <= INSERTION_THRESHOLD {
+            sort::insertion_sort(v, 1, is_less);
+            return;
+        }
+
+        // The medians of the groups gather at the front
+        let groups = len / 5;
+        for g in 0..groups {
+            sort::insertion_sort(&mut v[g * 5..g * 5 + 5], 1, is_less);
+            v.swap(g, g * 5 + 2);
+        }
+        median_of_medians(&mut v[..groups], groups / 2, is_less);
+
+        let (mid, _) = sort::partition(v, groups / 2, is_less);
+        if index < mid {
+            v = &mut v[..mid];
+            continue;
+        }
+        // Copies of the pivot all went right: step over them at once
+        let equal = sort::partition_equal(&mut v[mid..], 0, is_less);
+        if index < mid + equal {
+            return;
+        }
+        v = &mut v[mid + equal..];
+        index -= mid + equal;
+    }
+}
@@ -0,0 +1,658 @@
+use std::cmp::{self, Ordering};
+use std::mem::{self, ManuallyDrop};
+use std::ptr;
+
+use crate::allocator::Allocator;
+use crate::chunks::Chunks;
+use crate::growth::GrowthPolicy;
+use super::Vector;
+
+// Slices up to this length are sorted by insertion
+pub(super) const INSERTION_THRESHOLD: usize = 20;
+// Natural runs shorter than this are extended by insertion before merging
+const MIN_RUN: usize = 32;
+
+/// Integer keys for `Vector::radix_sort`, split into bytes.
+///
+/// Bytes must order like the values themselves: signed types flip the sign bit
+/// so that negative numbers come first.
+pub trait RadixKey: Copy {
+    const BYTES: usize;
+
+    /// Byte `i` of the key, least significant first
+    fn radix_byte(&self, i: usize) -> u8;
+}
+
+macro_rules! radix_key_unsigned {
+    ($($t:ty),*) => {$(
+        impl RadixKey for $t {
+            const BYTES: usize = mem::size_of::<$t>();
+
+            fn radix_byte(&self, i: usize) -> u8 {
+                (*self >> (i * 8)) as u8
+            }
+        }
+    )*}
+}
+
+macro_rules! radix_key_signed {
+    ($($t:ty => $u:ty),*) => {$(
+        impl RadixKey for $t {
+            const BYTES: usize = mem::size_of::<$t>();
+
+            fn radix_byte(&self, i: usize) -> u8 {
+                let flipped = (*self as $u) ^ (1 << (<$u>::BITS - 1));
+                (flipped >> (i * 8)) as u8
+            }
+        }
+    )*}
+}
+
+radix_key_unsigned!(u8, u16, u32, u64, u128, usize);
+radix_key_signed!(i8 => u8, i16 => u16, i32 => u32, i64 => u64, i128 => u128, isize => usize);
+
+// ======== VECTOR API ========
+
+impl<T, A: Allocator, G: GrowthPolicy> Vector<T, A, G> {
+    /// Stable sort: natural runs merged Timsort-style. O(n log n), n / 2 extra slots.
+    pub fn sort_stable(&mut self)
+    where
+        T: Ord,
+    {
+        self.sort_stable_by(T::cmp);
+    }
+
+    pub fn sort_stable_by<F: FnMut(&T, &T) -> Ordering>(&mut self, mut compare: F) {
+        merge_sort(self.as_mut_slice(), &mut |a, b| compare(a, b) == Ordering::Less);
+    }
+
+    pub fn sort_stable_by_key<K: Ord, F: FnMut(&T) -> K>(&mut self, mut f: F) {
+        merge_sort(self.as_mut_slice(), &mut |a, b| f(a) < f(b));
+    }
+
+    /// Unstable in-place sort: pattern-defeating quicksort, heap sort as the worst-case fallback
+    pub fn sort_unstable(&mut self)
+    where
+        T: Ord,
+    {
+        self.sort_unstable_by(T::cmp);
+    }
+
+    pub fn sort_unstable_by<F: FnMut(&T, &T) -> Ordering>(&mut self, mut compare: F) {
+        quicksort(self.as_mut_slice(), &mut |a, b| compare(a, b) == Ordering::Less);
+    }
+
+    pub fn sort_unstable_by_key<K: Ord, F: FnMut(&T) -> K>(&mut self, mut f: F) {
+        quicksort(self.as_mut_slice(), &mut |a, b| f(a) < f(b));
+    }
+
+    /// Stable sort calling `f` once per element: worth it when keys are expensive.
+    pub fn sort_by_cached_key<K: Ord, F: FnMut(&T) -> K>(&mut self, f: F) {
+        if self.len < 2 {
+            return;
+        }
+
+        // Keys paired with their original position are unique, so an unstable sort keeps them stable
+        let mut keys: Vector<(K, usize)> = self.iter()
+            .map(f)
+            .enumerate()
+            .map(|(i, k)| (k, i))
+            .collect();
+        keys.sort_unstable();
+
+        // Apply the permutation in place, following already moved elements
+        for i in 0..self.len {
+            let mut index = keys[i].1;
+            while index < i {
+                index = keys[index].1;
+            }
+            keys[i].1 = index;
+            self.as_mut_slice().swap(i, index);
+        }
+    }
+
+    /// LSD radix sort on bytes of the key: O(n * BYTES), stable, n extra slots.
+    pub fn radix_sort(&mut self)
+    where
+        T: RadixKey,
+    {
+        radix_sort(self.as_mut_slice());
+    }
+}
+
+// ======== INSERTION ========
+
+// Copies `src` into `dest` when dropped: fills the gap left by a value taken out,
+// also when a comparison panics
+struct Hole<T> {
+    src: *const T,
+    dest: *mut T,
+}
+
+impl<T> Drop for Hole<T> {
+    fn drop(&mut self) {
+        unsafe {
+            ptr::copy_nonoverlapping(self.src, self.dest, 1);
+        }
+    }
+}
+
+// Inserts the last element into the sorted `v[..len - 1]`
+fn insert_tail<T, F: FnMut(&T, &T) -> bool>(v: &mut [T], is_less: &mut F) {
+    let len = v.len();
+    let base = v.as_mut_ptr();
+    unsafe {
+        let last = base.add(len - 1);
+        if !is_less(&*last, &*last.sub(1)) {
+            return;
+        }
+
+        let tmp = ManuallyDrop::new(ptr::read(last));
+        let mut hole = Hole { src: &*tmp, dest: last.sub(1) };
+        ptr::copy_nonoverlapping(hole.dest, last, 1);
+
+        for i in (0..len - 2).rev() {
+            let cur = base.add(i);
+            if !is_less(&*tmp, &*cur) {
+                break;
+            }
+            ptr::copy_nonoverlapping(cur, hole.dest, 1);
+            hole.dest = cur;
+        }
+        // `hole` writes `tmp` into its final place
+    }
+}
+
+// Inserts the first element into the sorted `v[1..]`
+fn insert_head<T, F: FnMut(&T, &T) -> bool>(v: &mut [T], is_less: &mut F) {
+    let len = v.len();
+    let base = v.as_mut_ptr();
+    unsafe {
+        if len < 2 || !is_less(&*base.add(1), &*base) {
+            return;
+        }
+
+        let tmp = ManuallyDrop::new(ptr::read(base));
+        let mut hole = Hole { src: &*tmp, dest: base.add(1) };
+        ptr::copy_nonoverlapping(hole.dest, base, 1);
+
+        for i in 2..len {
+            let cur = base.add(i);
+            if !is_less(&*cur, &*tmp) {
+                break;
+            }
+            ptr::copy_nonoverlapping(cur, hole.dest, 1);
+            hole.dest = cur;
+        }
+    }
+}
+
+// Sorts `v` assuming `v[..offset]` is already sorted
+pub(super) fn insertion_sort<T, F: FnMut(&T, &T) -> bool>(v: &mut [T], offset: usize, is_less: &mut F) {
+    for i in offset.max(1)..v.len() {
+        insert_tail(&mut v[..=i], is_less);
+    }
+}
+
+// ======== MERGE SORT ========
+
+#[derive(Clone, Copy)]
+struct Run {
+    start: usize,
+    len: usize,
+}
+
+pub(super) fn merge_sort<T, F: FnMut(&T, &T) -> bool>(v: &mut [T], is_less: &mut F) {
+    // Zero-sized values are indistinguishable, any order is sorted
+    if mem::size_of::<T>() == 0 {
+        return;
+    }
+
+    let len = v.len();
+    if len <= INSERTION_THRESHOLD {
+        insertion_sort(v, 1, is_less);
+        return;
+    }
+
+    // Holds bitwise copies of the shorter run during a merge. Chunks never drops them.
+    let buf: Chunks<T> = Chunks::alloc(len / 2);
+    let mut runs: Vector<Run> = Vector::new();
+
+    let mut start = 0;
+    while start < len {
+        let mut end = start + 1;
+        if end < len {
+            if is_less(&v[end], &v[end - 1]) {
+                // Only strictly descending runs are reversed, to stay stable
+                while end < len && is_less(&v[end], &v[end - 1]) {
+                    end += 1;
+                }
+                v[start..end].reverse();
+            } else {
+                while end < len && !is_less(&v[end], &v[end - 1]) {
+                    end += 1;
+                }
+            }
+        }
+
+        if end - start < MIN_RUN {
+            let new_end = cmp::min(start + MIN_RUN, len);
+            insertion_sort(&mut v[start..new_end], end - start, is_less);
+            end = new_end;
+        }
+
+        runs.push(Run { start, len: end - start });
+        start = end;
+
+        while let Some(r) = collapse(runs.as_slice(), start == len) {
+            let left = runs[r];
+            let right = runs[r + 1];
+            unsafe {
+                merge(&mut v[left.start..right.start + right.len], left.len, buf.as_mut_ptr(), is_less);
+            }
+            runs[r] = Run { start: left.start, len: left.len + right.len };
+            runs.remove(r + 1);
+        }
+    }
+}
+
+// Timsort invariants on the run stack: returns the left run of the pair to merge next
+fn collapse(runs: &[Run], finished: bool) -> Option<usize> {
+    let n = runs.len();
+    if n >= 2
+        && (finished
+            || runs[n - 2].len <= runs[n - 1].len
+            || (n >= 3 && runs[n - 3].len <= runs[n - 2].len + runs[n - 1].len)
+            || (n >= 4 && runs[n - 4].len <= runs[n - 3].len + runs[n - 2].len))
+    {
+        if n >= 3 && runs[n - 3].len < runs[n - 1].len {
+            Some(n - 3)
+        } else {
+            Some(n - 2)
+        }
+    } else {
+        None
+    }
+}
+
+// Copies the not yet merged part of the buffer back on drop, so that a panicking
+// comparison leaves every element in `v` exactly once
+struct MergeHole<T> {
+    start: *mut T,
+    end: *mut T,
+    dest: *mut T,
+}
+
+impl<T> Drop for MergeHole<T> {
+    fn drop(&mut self) {
+        unsafe {
+            let count = self.end.offset_from(self.start) as usize;
+            ptr::copy_nonoverlapping(self.start, self.dest, count);
+        }
+    }
+}
+
+// Merges the sorted `v[..mid]` and `v[mid..]`.
+// `buf` must have room for the shorter of the two runs.
+unsafe fn merge<T, F: FnMut(&T, &T) -> bool>(v: &mut [T], mid: usize, buf: *mut T, is_less: &mut F) {
+    let len = v.len();
+    let v = v.as_mut_ptr();
+    unsafe {
+        let v_mid = v.add(mid);
+        let v_end = v.add(len);
+
+        if mid <= len - mid {
+            // Left run is shorter: move it out and merge front to back
+            ptr::copy_nonoverlapping(v, buf, mid);
+            let mut hole = MergeHole { start: buf, end: buf.add(mid), dest: v };
+            let mut right = v_mid;
+
+            while hole.start < hole.end && right < v_end {
+                if is_less(&*right, &*hole.start) {
+                    ptr::copy_nonoverlapping(right, hole.dest, 1);
+                    right = right.add(1);
+                } else {
+                    ptr::copy_nonoverlapping(hole.start, hole.dest, 1);
+                    hole.start = hole.start.add(1);
+                }
+                hole.dest = hole.dest.add(1);
+            }
+        } else {
+            // Right run is shorter: move it out and merge back to front.
+            // Here `hole.dest` is the end of the unmerged left run.
+            ptr::copy_nonoverlapping(v_mid, buf, len - mid);
+            let mut hole = MergeHole { start: buf, end: buf.add(len - mid), dest: v_mid };
+            let mut out = v_end;
+
+            while v < hole.dest && buf < hole.end {
+                out = out.sub(1);
+                if is_less(&*hole.end.sub(1), &*hole.dest.sub(1)) {
+                    hole.dest = hole.dest.sub(1);
+                    ptr::copy_nonoverlapping(hole.dest, out, 1);
+                } else {
+                    hole.end = hole.end.sub(1);
+                    ptr::copy_nonoverlapping(hole.end, out, 1);
+                }
+            }
+        }
+        // `hole` moves the rest of the buffer into the remaining gap
+    }
+}
+
+// ======== QUICKSORT ========
+
+pub(super) fn quicksort<T, F: FnMut(&T, &T) -> bool>(v: &mut [T], is_less: &mut F) {
+    // Allowed number of unbalanced partitions before switching to heap sort
+    let limit = usize::BITS - v.len().leading_zeros();
+    recurse(v, is_less, None, limit);
+}
+
+fn recurse<'a, T, F: FnMut(&T, &T) -> bool>(
+    mut v: &'a mut [T],
+    is_less: &mut F,
+    mut pred: Option<&'a T>,
+    mut limit: u32,
+) {
+    let mut was_balanced = true;
+    let mut was_partitioned = true;
+
+    loop {
+        let len = v.len();
+        if len <= INSERTION_THRESHOLD {
+            insertion_sort(v, 1, is_less);
+            return;
+        }
+
+        if limit == 0 {
+            heapsort(v, is_less);
+            return;
+        }
+
+        if !was_balanced {
+            break_patterns(v);
+            limit -= 1;
+        }
+
+        let (pivot, likely_sorted) = choose_pivot(v, is_less);
+
+        // Cheap attempt to finish nearly sorted input
+        if was_balanced && was_partitioned && likely_sorted && partial_insertion_sort(v, is_less) {
+            return;
+        }
+
+        // The pivot equals the predecessor (the pivot of an enclosing partition, which is
+        // not greater than anything here): move all its copies aside at once
+        if let Some(p) = pred
+            && !is_less(p, &v[pivot])
+        {
+            let mid = partition_equal(v, pivot, is_less);
+            v = &mut v[mid..];
+            continue;
+        }
+
+        let (mid, partitioned) = partition(v, pivot, is_less);
+        was_balanced = cmp::min(mid, len - mid) >= len / 8;
+        was_partitioned = partitioned;
+
+        let (left, right) = v.split_at_mut(mid);
+        let (pivot, right) = right.split_at_mut(1);
+        let pivot = &pivot[0];
+
+        // Recurse into the shorter side to bound the stack depth
+        if left.len() < right.len() {
+            recurse(left, is_less, pred, limit);
+            v = right;
+            pred = Some(pivot);
+        } else {
+            recurse(right, is_less, Some(pivot), limit);
+            v = left;
+        }
+    }
+}
+
+// Partitions around `v[pivot]`: returns its final position and whether nothing had to move.
+// Only swaps are used, so a panicking comparison leaves a permutation of `v`.
+pub(super) fn partition<T, F: FnMut(&T, &T) -> bool>(v: &mut [T], pivot: usize, is_less: &mut F) -> (usize, bool) {
+    v.swap(0, pivot);
+    let (pivot_slot, rest) = v.split_at_mut(1);
+    let pivot = &pivot_slot[0];
+
+    let mut l = 0;
+    let mut r = rest.len();
+    while l < r && is_less(&rest[l], pivot) {
+        l += 1;
+    }
+    while l < r && !is_less(&rest[r - 1], pivot) {
+        r -= 1;
+    }
+    let was_partitioned = l >= r;
+
+    // Branchless Lomuto on the unsorted middle: every element is swapped to the
+    // boundary, which only advances past smaller ones. No mispredicted branches.
+    let base = rest.as_mut_ptr();
+    let mut boundary = l;
+    for i in l..r {
+        unsafe {
+            let less = is_less(&*base.add(i), pivot);
+            ptr::swap(base.add(i), base.add(boundary));
+            boundary += less as usize;
+        }
+    }
+    l = boundary;
+
+    v.swap(0, l);
+    (l, was_partitioned)
+}
+
+// Moves elements equal to `v[pivot]` (assumed to be the minimum) to the front,
+// returns how many there are
+pub(super) fn partition_equal<T, F: FnMut(&T, &T) -> bool>(v: &mut [T], pivot: usize, is_less: &mut F) -> usize {
+    v.swap(0, pivot);
+    let (pivot_slot, rest) = v.split_at_mut(1);
+    let pivot = &pivot_slot[0];
+
+    let mut l = 0;
+    let mut r = rest.len();
+    loop {
+        while l < r && !is_less(pivot, &rest[l]) {
+            l += 1;
+        }
+        while l < r && is_less(pivot, &rest[r - 1]) {
+            r -= 1;
+        }
+        if l >= r {
+            break;
+        }
+        r -= 1;
+        rest.swap(l, r);
+        l += 1;
+    }
+
+    l + 1
+}
+
+// Fixes a few out-of-order pairs; gives up if there are too many
+fn partial_insertion_sort<T, F: FnMut(&T, &T) -> bool>(v: &mut [T], is_less: &mut F) -> bool {
+    const MAX_STEPS: usize = 5;
+    // Shifting elements of short slices isn't worth it, quicksort will do
+    const SHORTEST_SHIFTING: usize = 50;
+
+    let len = v.len();
+    let mut i = 1;
+    for _ in 0..MAX_STEPS {
+        while i < len && !is_less(&v[i], &v[i - 1]) {
+            i += 1;
+        }
+        if i == len {
+            return true;
+        }
+        if len < SHORTEST_SHIFTING {
+            return false;
+        }
+
+        v.swap(i - 1, i);
+        if i >= 2 {
+            insert_tail(&mut v[..i], is_less);
+        }
+        insert_head(&mut v[i..], is_less);
+    }
+
+    false
+}
+
+fn sort2<T, F: FnMut(&T, &T) -> bool>(v: &[T], a: &mut usize, b: &mut usize, swaps: &mut usize, is_less: &mut F) {
+    if is_less(&v[*b], &v[*a]) {
+        mem::swap(a, b);
+        *swaps += 1;
+    }
+}
+
+fn sort3<T, F: FnMut(&T, &T) -> bool>(
+    v: &[T],
+    a: &mut usize,
+    b: &mut usize,
+    c: &mut usize,
+    swaps: &mut usize,
+    is_less: &mut F,
+) {
+    sort2(v, a, b, swaps, is_less);
+    sort2(v, b, c, swaps, is_less);
+    sort2(v, a, b, swaps, is_less);
+}
+
+// Median of three, or of three medians for long slices.
+// Returns the pivot index and whether the slice looks already sorted.
+pub(super) fn choose_pivot<T, F: FnMut(&T, &T) -> bool>(v: &mut [T], is_less: &mut F) -> (usize, bool) {
+    const SHORTEST_MEDIAN_OF_MEDIANS: usize = 50;
+    const MAX_SWAPS: usize = 4 * 3;
+
+    let len = v.len();
+    let mut a = len / 4;
+    let mut b = len / 4 * 2;
+    let mut c = len / 4 * 3;
+    let mut swaps = 0;
+
+    if len >= 8 {
+        if len >= SHORTEST_MEDIAN_OF_MEDIANS {
+            for m in [&mut a, &mut b, &mut c] {
+                let (mut lo, mut hi) = (*m - 1, *m + 1);
+                sort3(v, &mut lo, m, &mut hi, &mut swaps, is_less);
+            }
+        }
+        sort3(v, &mut a, &mut b, &mut c, &mut swaps, is_less);
+    }
+
+    if swaps < MAX_SWAPS {
+        (b, swaps == 0)
+    } else {
+        // Every comparison swapped: the slice is most likely descending
+        v.reverse();
+        (len - 1 - b, true)
+    }
+}
+
+// Shuffles a few elements to break adversarial patterns after an unbalanced partition
+fn break_patterns<T>(v: &mut [T]) {
+    let len = v.len();
+    if len < 8 {
+        return;
+    }
+
+    // Xorshift, deterministic on purpose
+    let mut seed = len as u32;
+    let mut next = || {
+        seed ^= seed << 13;
+        seed ^= seed >> 17;
+        seed ^= seed << 5;
+        seed as usize
+    };
+
+    let mask = len.next_power_of_two() - 1;
+    let pos = len / 4 * 2;
+    for i in 0..3 {
+        let mut other = next() & mask;
+        if other >= len {
+            other -= len;
+        }
+        v.swap(pos - 1 + i, other);
+    }
+}
+
+// ======== HEAP SORT ========
+
+fn sift_down<T, F: FnMut(&T, &T) -> bool>(v: &mut [T], mut node: usize, is_less: &mut F) {
+    loop {
+        let mut child = 2 * node + 1;
+        if child >= v.len() {
+            break;
+        }
+        if child + 1 < v.len() && is_less(&v[child], &v[child + 1]) {
+            child += 1;
+        }
+        if !is_less(&v[node], &v[child]) {
+            break;
+        }
+        v.swap(node, child);
+        node = child;
+    }
+}
+
+pub(super) fn heapsort<T, F: FnMut(&T, &T) -> bool>(v: &mut [T], is_less: &mut F) {
+    for i in (0..v.len() / 2).rev() {
+        sift_down(v, i, is_less);
+    }
+    for end in (1..v.len()).rev() {
+        v.swap(0, end);
+        sift_down(&mut v[..end], 0, is_less);
+    }
+}
+
+// ======== RADIX SORT ========
+
+pub(super) fn radix_sort<T: RadixKey>(v: &mut [T]) {
+    let len = v.len();
+    if len < 2 {
+        return;
+    }
+
+    // One pass over the input counts every byte position at once
+    let mut counts: Vector<[usize; 256]> = Vector::new_copy([0; 256], T::BYTES);
+    for x in v.iter() {
+        for (byte, count) in counts.iter_mut().enumerate() {
+            count[x.radix_byte(byte) as usize] += 1;
+        }
+    }
+
+    let mut buf: Chunks<T> = Chunks::from_slice_copy(v);
+    let mut src: &mut [T] = v;
+    let mut dst: &mut [T] = buf.as_mut_slice();
+    let mut swapped = false;
+
+    for (byte, count) in counts.iter().enumerate() {
+        // All keys share this byte: the pass wouldn't move anything
+        if count.contains(&len) {
+            continue;
+        }
+
+        let mut offsets = [0usize; 256];
+        let mut sum = 0;
+        for (offset, c) in offsets.iter_mut().zip(count.iter()) {
+            *offset = sum;
+            sum += c;
+        }
+
+        for x in src.iter() {
+            let b = x.radix_byte(byte) as usize;
+            dst[offsets[b]] = *x;
+            offsets[b] += 1;
+        }
+
+        mem::swap(&mut src, &mut dst);
+        swapped = !swapped;
+    }
+
+    // Odd number of passes: the result is in the buffer
+    if swapped {
+        dst.copy_from_slice(src);
+    }
+}
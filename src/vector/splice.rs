@@ -0,0 +1,83 @@
+use std::fmt;
+
+use crate::allocator::{Allocator, Global};
+use crate::growth::{Doubling, GrowthPolicy};
+use super::Drain;
+
+/// Replaces a range of a `Vector` with items of another iterator,
+/// created by `Vector::splice()`.
+///
+/// Yields the removed elements. The replacement happens on drop, even if
+/// nothing was consumed.
+pub struct Splice<'a, I: Iterator, A: Allocator = Global, G: GrowthPolicy = Doubling> {
+    pub(super) drain: Drain<'a, I::Item, A, G>,
+    pub(super) replace_with: I,
+}
+
+impl<I: Iterator, A: Allocator, G: GrowthPolicy> Iterator for Splice<'_, I, A, G> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        self.drain.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.drain.size_hint()
+    }
+}
+
+impl<I: Iterator, A: Allocator, G: GrowthPolicy> DoubleEndedIterator for Splice<'_, I, A, G> {
+    fn next_back(&mut self) -> Option<I::Item> {
+        self.drain.next_back()
+    }
+}
+
+impl<I: Iterator, A: Allocator, G: GrowthPolicy> ExactSizeIterator for Splice<'_, I, A, G> {}
+
+impl<I, A: Allocator, G: GrowthPolicy> fmt::Debug for Splice<'_, I, A, G>
+where
+    I: Iterator + fmt::Debug,
+    I::Item: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Splice")
+            .field("drain", &self.drain)
+            .field("replace_with", &self.replace_with)
+            .finish()
+    }
+}
+
+impl<I: Iterator, A: Allocator, G: GrowthPolicy> Drop for Splice<'_, I, A, G> {
+    fn drop(&mut self) {
+        // Drop removed elements not consumed by the caller
+        self.drain.by_ref().for_each(drop);
+
+        if self.drain.tail_len == 0 {
+            self.drain.vec.extend(self.replace_with.by_ref());
+            return;
+        }
+
+        // First fill the gap left by the removed range
+        if !self.drain.fill(&mut self.replace_with) {
+            return;
+        }
+
+        // More items: make room for the lower bound of what's left
+        let (lower, _) = self.replace_with.size_hint();
+        if lower > 0 {
+            self.drain.move_tail(lower);
+            if !self.drain.fill(&mut self.replace_with) {
+                return;
+            }
+        }
+
+        // The iterator under-reported its size: collect the rest to learn the exact count
+        let mut collected = self.replace_with.by_ref().collect::<Vec<I::Item>>().into_iter();
+        if collected.len() > 0 {
+            self.drain.move_tail(collected.len());
+            let filled = self.drain.fill(&mut collected);
+            debug_assert!(filled);
+        }
+        // Drain's drop moves the tail back next to the inserted items
+    }
+}
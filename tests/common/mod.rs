@@ -0,0 +1,133 @@
+// Helpers shared by the test files, each of which uses only some of them
+#![allow(dead_code)]
+
+use std::alloc::Layout;
+use std::cell::Cell;
+use std::hash::{BuildHasherDefault, Hasher};
+use std::ptr;
+use std::rc::Rc;
+
+use tesap_std::{Allocator, Global};
+
+// Counts drops through a shared counter
+#[derive(Clone)]
+pub struct Tracked {
+    pub value: u64,
+    pub drops: Rc<Cell<usize>>,
+    // Panics on drop, after counting
+    pub bomb: bool,
+}
+
+impl Tracked {
+    // Constructor
+    pub fn new(drops: &Rc<Cell<usize>>) -> Self {
+        Self::with_value(drops, 0)
+    }
+
+    // Constructor
+    pub fn with_value(drops: &Rc<Cell<usize>>, value: u64) -> Self {
+        Self { value, drops: drops.clone(), bomb: false }
+    }
+
+    // Constructor: panics when dropped
+    pub fn bomb(drops: &Rc<Cell<usize>>) -> Self {
+        Self { value: 0, drops: drops.clone(), bomb: true }
+    }
+}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        self.drops.set(self.drops.get() + 1);
+        if self.bomb {
+            panic!("drop bomb");
+        }
+    }
+}
+
+// Deterministic xorshift step, enough to shuffle test inputs. `x` must not be 0.
+pub fn xorshift(x: &mut u64) -> u64 {
+    *x ^= *x << 13;
+    *x ^= *x >> 7;
+    *x ^= *x << 17;
+    *x
+}
+
+// `len` values below `modulo`, the same for the same seed
+pub fn random(len: usize, modulo: u64, seed: u64) -> Vec<u64> {
+    let mut x = seed | 1;
+    (0..len).map(|_| xorshift(&mut x) % modulo).collect()
+}
+
+// Counts calls and live blocks on top of `Global`, and fails any request above
+// `budget` bytes. Equal only to itself, so two instances act as separate arenas.
+pub struct CountingAlloc {
+    pub budget: usize,
+    pub allocs: Cell<usize>,
+    pub reallocs: Cell<usize>,
+    pub deallocs: Cell<usize>,
+    pub live: Cell<isize>,
+}
+
+impl CountingAlloc {
+    // Constructor
+    pub fn with_budget(budget: usize) -> Self {
+        Self {
+            budget,
+            allocs: Cell::new(0),
+            reallocs: Cell::new(0),
+            deallocs: Cell::new(0),
+            live: Cell::new(0),
+        }
+    }
+}
+
+impl Default for CountingAlloc {
+    fn default() -> Self {
+        Self::with_budget(usize::MAX)
+    }
+}
+
+impl PartialEq for CountingAlloc {
+    fn eq(&self, other: &Self) -> bool {
+        ptr::eq(self, other)
+    }
+}
+
+unsafe impl Allocator for CountingAlloc {
+    fn allocate(&self, layout: Layout) -> *mut u8 {
+        if layout.size() > self.budget {
+            return ptr::null_mut();
+        }
+        self.allocs.set(self.allocs.get() + 1);
+        self.live.set(self.live.get() + 1);
+        Global.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: *mut u8, layout: Layout) {
+        self.deallocs.set(self.deallocs.get() + 1);
+        self.live.set(self.live.get() - 1);
+        unsafe { Global.deallocate(ptr, layout) }
+    }
+
+    unsafe fn reallocate(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if new_size > self.budget {
+            return ptr::null_mut();
+        }
+        self.reallocs.set(self.reallocs.get() + 1);
+        unsafe { Global.reallocate(ptr, layout, new_size) }
+    }
+}
+
+// Sends every key to the same bucket and control byte
+#[derive(Default)]
+pub struct Colliding;
+
+impl Hasher for Colliding {
+    fn finish(&self) -> u64 {
+        0x1234
+    }
+
+    fn write(&mut self, _: &[u8]) {}
+}
+
+pub type CollidingState = BuildHasherDefault<Colliding>;
@@ -0,0 +1,206 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::random;
+    use tesap_std::{BinaryHeap, MinHeap, Vector};
+    use tesap_std::binary_heap::PeekMut;
+    use std::cell::Cell;
+    use std::cmp::{Ordering, Reverse};
+    use std::panic::{self, AssertUnwindSafe};
+
+    fn assert_heap<T: Ord>(heap: &BinaryHeap<T>) {
+        let v = heap.as_slice();
+        for i in 1..v.len() {
+            assert!(v[(i - 1) / 2] >= v[i], "heap property broken at {}", i);
+        }
+    }
+
+    #[test]
+    fn test_push_pop_peek() {
+        let mut heap = BinaryHeap::new();
+        assert_eq!(heap.peek(), None);
+        assert_eq!(heap.pop(), None);
+
+        let values = random(500, 1000, 1);
+        let mut std_heap = std::collections::BinaryHeap::new();
+        for &x in &values {
+            heap.push(x);
+            std_heap.push(x);
+            assert_eq!(heap.peek(), std_heap.peek());
+        }
+        assert_heap(&heap);
+        assert_eq!(heap.len(), 500);
+
+        while let Some(x) = std_heap.pop() {
+            assert_eq!(heap.pop(), Some(x));
+        }
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    fn test_peek_mut() {
+        let mut heap = BinaryHeap::from([5, 9, 1, 7]);
+        {
+            let mut top = heap.peek_mut().unwrap();
+            assert_eq!(*top, 9);
+            *top = 0;
+        }
+        assert_heap(&heap);
+        assert_eq!(heap.peek(), Some(&7));
+
+        // Read-only access leaves the heap alone
+        assert_eq!(*heap.peek_mut().unwrap(), 7);
+
+        let top = heap.peek_mut().unwrap();
+        assert_eq!(PeekMut::pop(top), 7);
+        assert_eq!(heap.into_sorted_vec(), Vector::from([0, 1, 5]));
+
+        let mut empty: BinaryHeap<i32> = BinaryHeap::new();
+        assert!(empty.peek_mut().is_none());
+    }
+
+    #[test]
+    fn test_into_sorted_vec_and_heapify() {
+        let values = random(1000, 1000, 2);
+        let heap = BinaryHeap::from_vector(Vector::from(values.as_slice()));
+        assert_heap(&heap);
+
+        let mut expected = values.clone();
+        expected.sort();
+        assert_eq!(heap.into_sorted_vec(), expected);
+
+        // Heapify is linear: fewer than 2n comparisons
+        struct Counted<'a>(u64, &'a Cell<usize>);
+        impl PartialEq for Counted<'_> {
+            fn eq(&self, other: &Self) -> bool {
+                self.0 == other.0
+            }
+        }
+        impl Eq for Counted<'_> {}
+        impl PartialOrd for Counted<'_> {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+        impl Ord for Counted<'_> {
+            fn cmp(&self, other: &Self) -> Ordering {
+                self.1.set(self.1.get() + 1);
+                self.0.cmp(&other.0)
+            }
+        }
+
+        let comparisons = Cell::new(0);
+        let v: Vector<Counted> = values.iter().map(|&x| Counted(x, &comparisons)).collect();
+        let heap = BinaryHeap::from(v);
+        assert!(comparisons.get() < 2 * values.len(), "{} comparisons", comparisons.get());
+        assert_eq!(heap.len(), values.len());
+    }
+
+    #[test]
+    fn test_append_and_extend() {
+        let mut a = BinaryHeap::from([1, 8, 3]);
+        let mut b = BinaryHeap::from([10, 2, 6, 4, 5]);
+        a.append(&mut b);
+        assert!(b.is_empty());
+        assert_heap(&a);
+        assert_eq!(a.len(), 8);
+
+        // Small tail: sifted up one by one
+        let mut big: BinaryHeap<u64> = random(300, 1000, 3).into_iter().collect();
+        let mut small = BinaryHeap::from([2000, 0]);
+        big.append(&mut small);
+        assert_heap(&big);
+        assert_eq!(big.peek(), Some(&2000));
+
+        a.extend([20, 0]);
+        a.extend(&[15]);
+        assert_heap(&a);
+        assert_eq!(a.into_sorted_vec(), Vector::from([0, 1, 2, 3, 4, 5, 6, 8, 10, 15, 20]));
+    }
+
+    #[test]
+    fn test_min_heap() {
+        let mut heap: MinHeap<i32> = MinHeap::new();
+        for x in [5, -3, 8, 0] {
+            heap.push(Reverse(x));
+        }
+        assert_eq!(heap.peek(), Some(&Reverse(-3)));
+        let order: Vec<i32> = std::iter::from_fn(|| heap.pop().map(|Reverse(x)| x)).collect();
+        assert_eq!(order, [-3, 0, 5, 8]);
+    }
+
+    #[test]
+    fn test_retain_drain_iter() {
+        let mut heap: BinaryHeap<i32> = (0..20).collect();
+        heap.retain(|x| x % 3 == 0);
+        assert_heap(&heap);
+        assert_eq!(heap.peek(), Some(&18));
+        assert_eq!(heap.iter().count(), 7);
+
+        let clone = heap.clone();
+        let mut drained: Vec<i32> = heap.drain().collect();
+        drained.sort();
+        assert_eq!(drained, [0, 3, 6, 9, 12, 15, 18]);
+        assert!(heap.is_empty());
+
+        let mut owned: Vec<i32> = clone.into_iter().collect();
+        owned.sort();
+        assert_eq!(owned, drained);
+    }
+
+    #[test]
+    fn test_panicking_compare_keeps_elements() {
+        // Panics on the n-th comparison
+        struct Bomb<'a> {
+            value: u64,
+            fuse: &'a Cell<usize>,
+            drops: &'a Cell<usize>,
+        }
+        impl PartialEq for Bomb<'_> {
+            fn eq(&self, other: &Self) -> bool {
+                self.value == other.value
+            }
+        }
+        impl Eq for Bomb<'_> {}
+        impl PartialOrd for Bomb<'_> {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+        impl Ord for Bomb<'_> {
+            fn cmp(&self, other: &Self) -> Ordering {
+                self.fuse.set(self.fuse.get().wrapping_sub(1));
+                if self.fuse.get() == 0 {
+                    panic!("comparison bomb");
+                }
+                self.value.cmp(&other.value)
+            }
+        }
+        impl Drop for Bomb<'_> {
+            fn drop(&mut self) {
+                self.drops.set(self.drops.get() + 1);
+            }
+        }
+
+        let fuse = Cell::new(usize::MAX);
+        let drops = Cell::new(0);
+        let mut heap = BinaryHeap::new();
+        for value in random(100, 1000, 4) {
+            heap.push(Bomb { value, fuse: &fuse, drops: &drops });
+        }
+
+        fuse.set(20);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            while heap.pop().is_some() {}
+        }));
+        assert!(result.is_err());
+        let popped = drops.get();
+
+        // Every element is still there exactly once
+        fuse.set(usize::MAX);
+        assert_eq!(heap.len() + popped, 100);
+        drop(heap);
+        assert_eq!(drops.get(), 100);
+    }
+}
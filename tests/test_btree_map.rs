@@ -0,0 +1,313 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{CountingAlloc, Tracked, xorshift};
+    use tesap_std::BTreeMap;
+    use tesap_std::btree_map::{IntoIter, Iter, IterMut, Range};
+    use std::cell::Cell;
+    use std::collections::BTreeMap as StdBTreeMap;
+    use std::ops::Bound;
+    use std::panic::{self, AssertUnwindSafe};
+    use std::rc::Rc;
+
+    fn entries<K: Copy, V: Copy>(map: &BTreeMap<K, V>) -> Vec<(K, V)> {
+        map.iter().map(|(&k, &v)| (k, v)).collect()
+    }
+
+    #[test]
+    fn test_insert_get_remove() {
+        let mut map = BTreeMap::new();
+        assert!(map.is_empty());
+        assert_eq!(map.first_key_value(), None);
+        assert_eq!(map.insert("b", 2), None);
+        assert_eq!(map.insert("a", 1), None);
+        assert_eq!(map.insert("c", 3), None);
+        assert_eq!(map.insert("b", 20), Some(2));
+        assert_eq!(map.len(), 3);
+
+        assert_eq!(map.get("b"), Some(&20));
+        assert_eq!(map.get_key_value("c"), Some((&"c", &3)));
+        assert!(!map.contains_key("d"));
+        *map.get_mut("a").unwrap() += 10;
+        assert_eq!(map["a"], 11);
+        assert_eq!(map.first_key_value(), Some((&"a", &11)));
+        assert_eq!(map.last_key_value(), Some((&"c", &3)));
+
+        assert_eq!(map.remove("b"), Some(20));
+        assert_eq!(map.remove("b"), None);
+        assert_eq!(map.remove_entry("a"), Some(("a", 11)));
+        assert_eq!(entries(&map), [("c", 3)]);
+        assert_eq!(format!("{:?}", map), r#"{"c": 3}"#);
+    }
+
+    #[test]
+    fn test_against_model() {
+        let mut map = BTreeMap::new();
+        let mut model = StdBTreeMap::new();
+        let mut seed = 7u64;
+
+        for step in 0..20_000 {
+            let key = xorshift(&mut seed) % 2000;
+            match xorshift(&mut seed) % 6 {
+                0..=2 => assert_eq!(map.insert(key, step), model.insert(key, step)),
+                3 | 4 => assert_eq!(map.remove(&key), model.remove(&key)),
+                _ => assert_eq!(map.pop_first(), model.pop_first()),
+            }
+            assert_eq!(map.len(), model.len());
+        }
+        assert!(map.iter().eq(model.iter()));
+        assert!(map.iter().rev().eq(model.iter().rev()));
+        assert_eq!(map.last_key_value(), model.last_key_value());
+
+        map.retain(|k, v| !(k + *v as u64).is_multiple_of(3));
+        model.retain(|k, v| !(k + *v as u64).is_multiple_of(3));
+        assert!(map.iter().eq(model.iter()));
+
+        while let Some(last) = map.pop_last() {
+            assert_eq!(Some(last), model.pop_last());
+        }
+        assert!(model.is_empty());
+    }
+
+    #[test]
+    fn test_ranges() {
+        let mut map: BTreeMap<u32, u32> = (0..500).map(|i| (i * 2, i)).collect();
+        let model: StdBTreeMap<u32, u32> = (0..500).map(|i| (i * 2, i)).collect();
+
+        let bounds = [Bound::Unbounded, Bound::Included(0), Bound::Excluded(0), Bound::Included(1),
+            Bound::Included(401), Bound::Excluded(400), Bound::Included(998), Bound::Excluded(999),
+            Bound::Included(2000)];
+        for start in bounds {
+            for end in bounds {
+                let ok = match (start, end) {
+                    (Bound::Included(s) | Bound::Excluded(s), Bound::Included(e) | Bound::Excluded(e)) => {
+                        s < e || (s == e && !matches!((start, end), (Bound::Excluded(_), Bound::Excluded(_))))
+                    }
+                    _ => true,
+                };
+                if !ok {
+                    continue;
+                }
+                assert!(map.range((start, end)).eq(model.range((start, end))), "{:?}", (start, end));
+                assert!(map.range((start, end)).rev().eq(model.range((start, end)).rev()));
+            }
+        }
+
+        // Meeting in the middle from both ends
+        let mut range = map.range(10..=20);
+        assert_eq!(range.next(), Some((&10, &5)));
+        assert_eq!(range.next_back(), Some((&20, &10)));
+        assert_eq!(range.clone().count(), 4);
+        assert_eq!(range.next_back(), Some((&18, &9)));
+        assert_eq!(range.by_ref().map(|(k, _)| *k).collect::<Vec<_>>(), [12, 14, 16]);
+        assert_eq!(range.next_back(), None);
+
+        for (_, v) in map.range_mut(100..110) {
+            *v = 0;
+        }
+        assert_eq!(map.values().filter(|&&v| v == 0).count(), 6);
+        assert_eq!(map.range(3..3).count(), 0);
+        assert!(panic::catch_unwind(|| BTreeMap::<u32, u32>::new().range((Bound::Included(5), Bound::Included(3))).count()).is_err());
+    }
+
+    #[test]
+    fn test_split_off_and_append() {
+        for n in [0usize, 1, 10, 11, 12, 100, 1000, 5000] {
+            for at in [0, 1, n / 3, n / 2, n.saturating_sub(1), n, n + 1] {
+                let mut left: BTreeMap<usize, usize> = (0..n).map(|i| (i, i)).collect();
+                let mut right = left.split_off(&at);
+                let cut = at.min(n);
+                assert_eq!(left.len(), cut);
+                assert_eq!(right.len(), n - cut);
+                assert!(left.keys().copied().eq(0..cut));
+                assert!(right.keys().rev().copied().eq((cut..n).rev()));
+
+                // Still valid trees for further edits
+                right.insert(n + 7, 0);
+                left.insert(usize::MAX, 0);
+                assert_eq!(right.remove(&(n + 7)), Some(0));
+                assert_eq!(left.pop_last(), Some((usize::MAX, 0)));
+
+                left.append(&mut right);
+                assert!(right.is_empty());
+                assert!(left.iter().map(|(&k, &v)| (k, v)).eq((0..n).map(|i| (i, i))));
+            }
+        }
+
+        let mut a = BTreeMap::from([(1, "a"), (3, "a"), (5, "a")]);
+        let mut b = BTreeMap::from([(2, "b"), (3, "b"), (6, "b")]);
+        a.append(&mut b);
+        assert_eq!(entries(&a), [(1, "a"), (2, "b"), (3, "b"), (5, "a"), (6, "b")]);
+    }
+
+    #[test]
+    fn test_iterators() {
+        let mut map: BTreeMap<i32, i32> = (0..100).rev().map(|i| (i, i * i)).collect();
+        assert_eq!(map.iter().len(), 100);
+        assert_eq!(map.keys().nth(10), Some(&10));
+        assert_eq!(map.values().next_back(), Some(&9801));
+
+        for v in map.values_mut() {
+            *v = -*v;
+        }
+        for (k, v) in &mut map {
+            *v += k;
+        }
+        assert_eq!(map[&3], -6);
+
+        let mut iter = map.iter();
+        iter.next();
+        iter.next_back();
+        assert_eq!(iter.len(), 98);
+
+        let mut into_iter = map.clone().into_iter();
+        assert_eq!(into_iter.next(), Some((0, 0)));
+        assert_eq!(into_iter.next_back(), Some((99, -9702)));
+        assert_eq!(into_iter.len(), 98);
+        assert!(map.clone().into_keys().eq(0..100));
+        assert_eq!(map.into_values().rev().nth(1), Some(-98 * 98 + 98));
+    }
+
+    #[test]
+    fn test_from_iter_keeps_last_and_traits() {
+        let map: BTreeMap<&str, i32> = [("x", 1), ("y", 2), ("x", 3), ("w", 4), ("x", 5)].into_iter().collect();
+        assert_eq!(entries(&map), [("w", 4), ("x", 5), ("y", 2)]);
+
+        let mut extended = BTreeMap::new();
+        extended.extend([("y", 2), ("x", 5)]);
+        extended.extend([(&"w", &4)]);
+        assert_eq!(extended, map);
+        assert_ne!(extended, BTreeMap::from([("w", 4)]));
+
+        let mut retained: BTreeMap<u32, u32> = (0..1000).map(|i| (i, i)).collect();
+        retained.retain(|k, v| {
+            *v += 1;
+            k % 7 == 0
+        });
+        assert_eq!(retained.len(), 143);
+        assert!(retained.iter().all(|(k, v)| k % 7 == 0 && *v == k + 1));
+        assert_eq!(retained.clone(), retained);
+
+        // A panic in `f` keeps what it hasn't visited, and the tree stays usable
+        let mut map: BTreeMap<u32, u32> = (0..1000).map(|i| (i, i)).collect();
+        let mut calls = 0;
+        let result = panic::catch_unwind(AssertUnwindSafe(|| map.retain(|k, _| {
+            calls += 1;
+            assert!(calls < 500);
+            k % 2 == 0
+        })));
+        assert!(result.is_err());
+        assert_eq!(map.len(), 751);
+        assert!(map.keys().copied().eq((0..499).filter(|k| k % 2 == 0).chain(499..1000)));
+        map.retain(|_, _| false);
+        assert!(map.is_empty());
+        map.insert(1, 1);
+        assert_eq!(entries(&map), [(1, 1)]);
+        assert_eq!(BTreeMap::<u8, u8>::default().iter().next(), None);
+    }
+
+    #[test]
+    fn test_drops() {
+        let drops = Rc::new(Cell::new(0));
+        let mut map = BTreeMap::new();
+        for i in 0..300 {
+            map.insert(i, Tracked::new(&drops));
+        }
+        assert!(map.insert(5, Tracked::new(&drops)).is_some());
+        assert_eq!(drops.get(), 1);
+        map.remove(&6);
+        assert_eq!(drops.get(), 2);
+
+        // Half consumed from both ends
+        let mut into_iter = map.into_iter();
+        into_iter.by_ref().take(50).for_each(drop);
+        into_iter.by_ref().rev().take(50).for_each(drop);
+        assert_eq!(drops.get(), 102);
+        drop(into_iter);
+        assert_eq!(drops.get(), 301);
+
+        // A panicking destructor doesn't leak the others
+        let count = Rc::new(Cell::new(0));
+        let map: BTreeMap<u32, Tracked> =
+            (0..100).map(|i| (i, if i == 40 { Tracked::bomb(&count) } else { Tracked::new(&count) })).collect();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| drop(map)));
+        assert!(result.is_err());
+        assert_eq!(count.get(), 100);
+    }
+
+    #[test]
+    fn test_large_and_zero_sized() {
+        let mut seed = 3u64;
+        let mut map = BTreeMap::new();
+        for _ in 0..100_000 {
+            map.insert(xorshift(&mut seed) % 1_000_000, ());
+        }
+        let keys: Vec<u64> = map.keys().copied().collect();
+        assert!(keys.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(map.len(), keys.len());
+
+        for k in keys.iter().step_by(2) {
+            assert_eq!(map.remove(k), Some(()));
+        }
+        assert!(map.keys().copied().eq(keys.iter().skip(1).step_by(2).copied()));
+    }
+
+    #[test]
+    fn test_allocations() {
+        let a = CountingAlloc::default();
+        {
+            let mut map: BTreeMap<u32, u32, &CountingAlloc> = BTreeMap::new_in(&a);
+            for i in 0..11 {
+                map.insert(i, i);
+            }
+            // One allocation per node
+            assert_eq!(a.allocs.get(), 1);
+            map.insert(11, 11);
+            assert_eq!(a.live.get(), 3);
+            for i in 12..1000 {
+                map.insert(i, i);
+            }
+
+            // Walking the tree allocates nothing
+            let before = a.allocs.get();
+            for _ in 0..100 {
+                assert_eq!(map.iter().count(), 1000);
+                assert_eq!(map.range(100..200).rev().count(), 100);
+            }
+            assert_eq!(map.values_mut().count(), 1000);
+            assert_eq!(a.allocs.get(), before);
+
+            let mut right = map.split_off(&500);
+            right.retain(|k, _| k % 2 == 0);
+            map.append(&mut right);
+            assert_eq!(map.len(), 750);
+            let mut into_iter = map.into_iter();
+            assert_eq!(into_iter.nth(10), Some((10, 10)));
+        }
+        assert_eq!(a.live.get(), 0);
+    }
+
+    #[test]
+    fn test_variance_and_threads() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<BTreeMap<u32, String>>();
+        assert_send_sync::<Iter<'_, u32, String>>();
+        assert_send_sync::<IterMut<'_, u32, String>>();
+        assert_send_sync::<Range<'_, u32, String>>();
+        assert_send_sync::<IntoIter<u32, String>>();
+
+        // Covariant like std: longer-lived references can be used as shorter ones
+        fn shorten<'a>(map: BTreeMap<&'static str, &'static str>) -> BTreeMap<&'a str, &'a str> {
+            map
+        }
+        fn shorten_iter<'a, 'b>(iter: Iter<'a, &'static str, u32>) -> Iter<'a, &'b str, u32> {
+            iter
+        }
+        let map = shorten(BTreeMap::from([("a", "x"), ("b", "y")]));
+        let map = std::thread::spawn(move || map).join().unwrap();
+        assert_eq!(map.get("b"), Some(&"y"));
+        let counts: BTreeMap<&str, u32> = map.keys().map(|k| (*k, 1)).collect();
+        assert_eq!(shorten_iter(counts.iter()).count(), 2);
+    }
+}
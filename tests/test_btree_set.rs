@@ -0,0 +1,134 @@
+#[cfg(test)]
+mod tests {
+    use tesap_std::BTreeSet;
+    use std::cmp::Ordering;
+
+    fn collect<'a, I: IntoIterator<Item=&'a i32>>(iter: I) -> Vec<i32> {
+        iter.into_iter().copied().collect()
+    }
+
+    // Ordered by `key` only, to tell stored elements apart
+    #[derive(Debug)]
+    struct Tagged {
+        key: u32,
+        tag: &'static str,
+    }
+
+    impl PartialEq for Tagged {
+        fn eq(&self, other: &Self) -> bool {
+            self.key == other.key
+        }
+    }
+
+    impl Eq for Tagged {}
+
+    impl PartialOrd for Tagged {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl Ord for Tagged {
+        fn cmp(&self, other: &Self) -> Ordering {
+            self.key.cmp(&other.key)
+        }
+    }
+
+    #[test]
+    fn test_insert_contains_remove() {
+        let mut set = BTreeSet::new();
+        assert!(set.insert("b".to_string()));
+        assert!(set.insert("a".to_string()));
+        assert!(!set.insert("b".to_string()));
+        assert_eq!(set.len(), 2);
+
+        assert!(set.contains("a"));
+        assert!(!set.contains("c"));
+        assert_eq!(set.first().map(String::as_str), Some("a"));
+        assert_eq!(set.last().map(String::as_str), Some("b"));
+
+        assert!(set.remove("a"));
+        assert!(!set.remove("a"));
+        assert_eq!(set.take("b"), Some("b".to_string()));
+        assert!(set.is_empty());
+
+        let mut tagged = BTreeSet::new();
+        tagged.insert(Tagged { key: 1, tag: "first" });
+        assert!(!tagged.insert(Tagged { key: 1, tag: "second" }));
+        assert_eq!(tagged.get(&Tagged { key: 1, tag: "" }).unwrap().tag, "first");
+    }
+
+    #[test]
+    fn test_order_and_ranges() {
+        let mut set: BTreeSet<i32> = [5, -3, 9, 0, 5, 12, 7].into_iter().collect();
+        assert_eq!(collect(&set), [-3, 0, 5, 7, 9, 12]);
+        assert_eq!(collect(set.iter().rev()), [12, 9, 7, 5, 0, -3]);
+        assert_eq!(collect(set.range(0..9)), [0, 5, 7]);
+        assert_eq!(collect(set.range(6..).rev()), [12, 9, 7]);
+        assert_eq!(set.range(20..).count(), 0);
+
+        assert_eq!(set.pop_first(), Some(-3));
+        assert_eq!(set.pop_last(), Some(12));
+        assert_eq!(set.into_iter().collect::<Vec<_>>(), [0, 5, 7, 9]);
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a: BTreeSet<i32> = (0..10).collect();
+        let b: BTreeSet<i32> = (5..20).collect();
+
+        assert_eq!(collect(a.union(&b)), (0..20).collect::<Vec<_>>());
+        assert_eq!(collect(a.intersection(&b)), (5..10).collect::<Vec<_>>());
+        assert_eq!(collect(b.intersection(&a)), (5..10).collect::<Vec<_>>());
+        assert_eq!(collect(a.difference(&b)), (0..5).collect::<Vec<_>>());
+        assert_eq!(collect(b.difference(&a)), (10..20).collect::<Vec<_>>());
+        assert_eq!(collect(a.symmetric_difference(&b)), (0..5).chain(10..20).collect::<Vec<_>>());
+        assert_eq!(a.union(&BTreeSet::new()).count(), 10);
+        assert_eq!(a.union(&b).size_hint(), (15, Some(25)));
+    }
+
+    #[test]
+    fn test_subset_disjoint() {
+        let small = BTreeSet::from([2, 4]);
+        let big: BTreeSet<i32> = (0..10).collect();
+        let other: BTreeSet<i32> = (10..15).collect();
+        let empty: BTreeSet<i32> = BTreeSet::new();
+
+        assert!(small.is_subset(&big));
+        assert!(!big.is_subset(&small));
+        assert!(big.is_superset(&small));
+        assert!(empty.is_subset(&small));
+        assert!(big.is_disjoint(&other));
+        assert!(!small.is_disjoint(&big));
+        assert!(empty.is_disjoint(&empty));
+    }
+
+    #[test]
+    fn test_split_off_append_retain() {
+        let mut set: BTreeSet<u32> = (0..1000).collect();
+        let mut upper = set.split_off(&600);
+        assert_eq!(set.len(), 600);
+        assert_eq!(upper.first(), Some(&600));
+        assert_eq!(set.last(), Some(&599));
+
+        upper.retain(|x| x % 2 == 0);
+        assert_eq!(upper.len(), 200);
+        set.append(&mut upper);
+        assert!(upper.is_empty());
+        assert_eq!(set.len(), 800);
+        assert!(set.contains(&998) && !set.contains(&999));
+    }
+
+    #[test]
+    fn test_traits() {
+        let mut set = BTreeSet::new();
+        set.extend([3, 1]);
+        set.extend(&[2, 3]);
+        assert_eq!(set, BTreeSet::from([1, 2, 3]));
+        assert_ne!(set, BTreeSet::from([1, 2]));
+        assert_eq!(set.clone(), set);
+        assert_eq!(format!("{:?}", set), "{1, 2, 3}");
+        assert_eq!(format!("{:?}", set.iter()), "[1, 2, 3]");
+        assert_eq!(format!("{:?}", BTreeSet::<u8>::default()), "{}");
+    }
+}
@@ -1,3 +1,4 @@
+#![allow(unused_imports, clippy::needless_borrow)]
 
 macro_rules! test_parametrized_sample {
     ($func_name:ident, $test_name:ident, $sample:expr) => {
@@ -68,10 +69,9 @@ mod tests {
     fn compare_bytes(b1: &Bytes, b2: &Bytes) {
         assert_eq!(b1.as_slice(), b2.as_slice());
         assert_eq!(b1.to_int128(), b2.to_int128());
-        // TODO Add PartialEq for Vector
-        assert_eq!(b1.to_bin().0.as_slice(), b2.to_bin().0.as_slice());
-        // TODO Add PartialEq for Hex
-        assert_eq!(b1.to_hex().0, b2.to_hex().0);
+        assert_eq!(b1.to_bin(), b2.to_bin());
+        assert_eq!(b1.to_hex(), b2.to_hex());
+        assert_eq!(b1, b2);
 
     }
 
@@ -147,4 +147,28 @@ mod tests {
 
         assert_eq!(v.as_slice(), &[1, 2, 3, 4, 5]);
     }
+
+    #[test]
+    fn test_to_int128_short() {
+        let bs: Bytes = Bytes::from([1, 2]);
+        assert_eq!(bs.to_int128(), Ok(i128::from_ne_bytes([1, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0])));
+        assert!(Bytes::from([0u8; 17]).to_int128().is_err());
+    }
+
+    #[test]
+    fn test_std_traits() {
+        let mut a: Bytes = Bytes::default();
+        a.extend([1, 2]);
+        a.extend(&[3]);
+        let b = a.clone();
+        assert_eq!(a, b);
+        assert!(a < Bytes::from([1, 2, 4]));
+        assert_eq!(a.as_ref(), &[1, 2, 3]);
+        assert_eq!((&a).into_iter().count(), 3);
+
+        let arr: [u8; 3] = a.try_into().unwrap();
+        assert_eq!(arr, [1, 2, 3]);
+        let b: Result<[u8; 2], _> = b.try_into();
+        assert!(b.is_err());
+    }
 }
@@ -1,3 +1,6 @@
+#![allow(non_snake_case, clippy::unnecessary_operation, clippy::useless_conversion, clippy::vec_init_then_push)]
+
+mod common;
 
 macro_rules! test_parametrized {
     ($func_name:ident, $type_ident:ident, $type:ty) => {
@@ -10,7 +13,8 @@ macro_rules! test_parametrized {
 
 #[cfg(test)]
 mod tests {
-    use tesap_std::Chunks;
+    use crate::common::CountingAlloc;
+    use tesap_std::{AllocError, Chunks};
     use assert_panic::assert_panic;
     use std::mem;
 
@@ -80,9 +84,8 @@ mod tests {
         chunks.memset_copy(VALUE.into());
         assert_eq!(chunks[0], VALUE);
 
-        let chunks_view: Chunks::<T, false> = Chunks {
-            ptr: chunks.ptr,
-            count: chunks.count
+        let chunks_view: Chunks::<T, false> = unsafe {
+            Chunks::from_raw_parts(chunks.ptr, chunks.count)
         };
         /*
          * Check that no further allocation happenned out of bounds
@@ -92,9 +95,8 @@ mod tests {
 
         let ptr = chunks.ptr as *mut u8;
         // BOUNDS_CHECK = false : Turn off as needed to exceed bounds intentionally further
-        let chunks2: Chunks<u8, false> = Chunks {
-            ptr: ptr,
-            count: SIZE * size_factor,
+        let chunks2: Chunks<u8, false> = unsafe {
+            Chunks::from_raw_parts(ptr, SIZE * size_factor)
         };
 
         /*
@@ -134,9 +136,8 @@ mod tests {
         v.shrink_to_fit();
         v.push(21);
 
-        let mut c: Chunks<u8> = Chunks {
-            ptr: v.as_mut_ptr(),
-            count: v.len(),
+        let mut c: Chunks<u8> = unsafe {
+            Chunks::from_raw_parts(v.as_mut_ptr(), v.len())
         };
 
         assert_eq!(v.as_slice(), c.as_slice());
@@ -225,6 +226,21 @@ mod tests {
         assert_eq!(c.as_slice(), &["123", "123", "123", "123", "123"]);
     }
 
+    #[test]
+    fn test_uninit_slice() {
+        let mut c = Chunks::<String>::alloc(3);
+        c.as_uninit_mut_slice()[0].write("a".to_string());
+        c.as_uninit_mut_slice()[1].write("b".to_string());
+        assert_eq!(c.as_uninit_slice().len(), 3);
+
+        unsafe {
+            assert_eq!(c.as_uninit_slice()[1].assume_init_ref(), "b");
+            // Chunks never drops its slots
+            c.as_uninit_mut_slice()[0].assume_init_drop();
+            c.as_uninit_mut_slice()[1].assume_init_drop();
+        }
+    }
+
     // TEST &str
     #[test]
     fn test_str() {
@@ -242,5 +258,145 @@ mod tests {
         assert_eq!(c.as_mut_slice(), &["x", "y", "z"]);
     }
 
-}
+    // ZERO-SIZED
+    #[test]
+    fn test_alloc_zero() {
+        let mut c = Chunks::<u64>::alloc(0);
+        assert!(!c.ptr.is_null());
+        assert_eq!(c.ptr as usize % mem::align_of::<u64>(), 0);
+        assert_eq!(c.as_slice(), &[] as &[u64]);
+
+        c.grow(3);
+        c.memset_copy(5);
+        assert_eq!(c.as_slice(), &[5, 5, 5]);
+
+        c.realloc(0);
+        assert_eq!(c.count, 0);
+        assert!(!c.ptr.is_null());
+    }
+
+    #[test]
+    fn test_alloc_zst() {
+        let a = CountingAlloc::default();
+        {
+            let mut c: Chunks<(), true, &CountingAlloc> = Chunks::alloc_in(10, &a);
+            assert!(!c.ptr.is_null());
+            c.memset_copy(());
+            assert_eq!(c.as_slice().len(), 10);
+            c.grow(usize::MAX - 10);
+            assert_eq!(c.count, usize::MAX);
+        }
+        assert_eq!(a.allocs.get(), 0);
+        assert_eq!(a.reallocs.get(), 0);
+        assert_eq!(a.deallocs.get(), 0);
+    }
+
+    #[test]
+    fn test_try_alloc_overflow() {
+        let res = Chunks::<u64>::try_alloc(usize::MAX / 4);
+        assert_eq!(res.err(), Some(AllocError::CapacityOverflow));
+        assert_panic!({ Chunks::<u64>::alloc(usize::MAX / 4); });
+    }
+
+    #[test]
+    fn test_try_alloc_out_of_memory() {
+        let res = Chunks::<u32, true, CountingAlloc>::try_alloc_in(100, CountingAlloc::with_budget(64));
+        match res {
+            Err(AllocError::OutOfMemory { layout }) => assert_eq!(layout.size(), 400),
+            _ => panic!("Expected OutOfMemory"),
+        }
+    }
+
+    #[test]
+    fn test_try_realloc_keeps_memory() {
+        let mut c = Chunks::<u32, true, CountingAlloc>::alloc_in(4, CountingAlloc::with_budget(32));
+        c.memset_copy(7);
+
+        assert!(c.try_grow(4).is_ok());
+        assert_eq!(c.count, 8);
+        assert!(matches!(c.try_grow(1), Err(AllocError::OutOfMemory { .. })));
+        assert_eq!(c.count, 8);
+        assert_eq!(c.try_grow(usize::MAX), Err(AllocError::CapacityOverflow));
+        assert_eq!(&c.as_slice()[..4], &[7, 7, 7, 7]);
+    }
+
+    #[test]
+    fn test_custom_allocator() {
+        let a = CountingAlloc::default();
+        {
+            let mut c: Chunks<u32, true, &CountingAlloc> = Chunks::alloc_in(4, &a);
+            c.memset_copy(3);
+            c.grow(2);
+            assert_eq!(c.count, 6);
+            assert_eq!(&c.as_slice()[..4], &[3, 3, 3, 3]);
+        }
+        assert_eq!(a.allocs.get(), 1);
+        assert_eq!(a.reallocs.get(), 1);
+        assert_eq!(a.deallocs.get(), 1);
+    }
+
+    #[test]
+    fn test_std_traits() {
+        let a: Chunks<u32> = Chunks::from([1, 2, 3]);
+        let b = a.clone();
+        assert_ne!(a.ptr, b.ptr);
+        assert_eq!(a, b);
+        assert!(a < Chunks::from([1, 2, 4]));
+        assert_eq!(a.as_ref(), &[1, 2, 3]);
+        assert_eq!((&a).into_iter().sum::<u32>(), 6);
+
+        let d: Chunks<u32> = Chunks::default();
+        assert_eq!(d.count, 0);
+    }
+
+    #[test]
+    fn test_extend() {
+        let mut c: Chunks<String> = Chunks::from(["a".to_string()]);
+        c.extend(["b".to_string(), "c".to_string()]);
+        assert_eq!(c.count, 3);
+        assert_eq!(c.as_slice(), &["a", "b", "c"]);
+
+        // Chunks never drops its slots
+        let arr: [String; 3] = c.try_into().unwrap();
+        assert_eq!(arr, ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_extend_in_place() {
+        let a = CountingAlloc::default();
+        let mut c: Chunks<u32, true, &CountingAlloc> = Chunks::alloc_in(1, &a);
+        c.memset_copy(0);
+        // An exact size hint: one reallocation
+        c.extend(1..100);
+        assert_eq!((c.count, a.reallocs.get()), (100, 1));
+        assert!(c.as_slice().iter().copied().eq(0..100));
+
+        // A low hint: a few reallocations, then trimmed to what was written
+        c.extend((100..1000).filter(|x| x % 3 == 0));
+        assert_eq!(c.count, 100 + 300);
+        assert!(a.reallocs.get() < 14, "{} reallocations", a.reallocs.get());
+        assert_eq!(c.as_slice()[399], 999);
+
+        // A panicking iterator leaves the written slots only
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            c.extend((0..10).map(|x| if x == 5 { panic!() } else { x }));
+        }));
+        assert!(result.is_err());
+        assert_eq!(c.count, 405);
+        assert_eq!(&c.as_slice()[400..], &[0, 1, 2, 3, 4]);
+    }
 
+    #[test]
+    fn test_from_box_and_try_into() {
+        let c: Chunks<u8> = Chunks::from(vec![1, 2, 3].into_boxed_slice());
+        assert_eq!(c.as_slice(), &[1, 2, 3]);
+        let c: Result<[u8; 4], _> = c.try_into();
+        let mut c = c.unwrap_err();
+        assert_eq!(c.count, 3);
+
+        // Deallocated: a null pointer with no slots still converts to an empty array
+        c.dealloc();
+        let arr: [u8; 0] = c.try_into().unwrap();
+        assert_eq!(arr, []);
+    }
+}
@@ -0,0 +1,238 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{Tracked, xorshift};
+    use tesap_std::{Deque, Vector};
+    use std::cell::Cell;
+    use std::collections::VecDeque;
+    use std::rc::Rc;
+
+    // Deque of `capacity` with elements 0..len starting at slot `head`
+    fn wrapped(capacity: usize, head: usize, len: usize) -> Deque<usize> {
+        let mut d = Deque::with_capacity(capacity);
+        assert_eq!(d.capacity(), capacity);
+        for _ in 0..head {
+            d.push_back(0);
+            d.pop_front();
+        }
+        for i in 0..len {
+            d.push_back(i);
+        }
+        assert_eq!(d.capacity(), capacity);
+        d
+    }
+
+    #[test]
+    fn test_push_pop_both_ends() {
+        let mut d = Deque::new();
+        d.push_back(2);
+        d.push_back(3);
+        d.push_front(1);
+        d.push_front(0);
+        assert_eq!(d.len(), 4);
+        assert_eq!(d.front(), Some(&0));
+        assert_eq!(d.back(), Some(&3));
+        assert_eq!(d[2], 2);
+        d[2] = 20;
+        assert_eq!(d.pop_front(), Some(0));
+        assert_eq!(d.pop_back(), Some(3));
+        assert_eq!(d.pop_back(), Some(20));
+        assert_eq!(d.pop_back(), Some(1));
+        assert_eq!(d.pop_back(), None);
+        assert_eq!(d.pop_front(), None);
+        assert_eq!(d.back(), None);
+    }
+
+    #[test]
+    fn test_as_slices_wrap() {
+        let d = wrapped(8, 6, 5);
+        assert_eq!(d.as_slices(), (&[0, 1][..], &[2, 3, 4][..]));
+        assert_eq!(d.iter().copied().collect::<Vec<_>>(), [0, 1, 2, 3, 4]);
+        assert_eq!(d.iter().rev().copied().collect::<Vec<_>>(), [4, 3, 2, 1, 0]);
+        assert_eq!(d.get(4), Some(&4));
+        assert_eq!(d.get(5), None);
+    }
+
+    #[test]
+    fn test_growth_unwraps_ring() {
+        for head in 0..8 {
+            for len in 1..=8 {
+                let mut d = wrapped(8, head, len);
+                // Fill up to force reallocation while wrapped
+                for i in len..20 {
+                    d.push_back(i);
+                }
+                d.push_front(100);
+                let mut expected: Vec<usize> = (0..20).collect();
+                expected.insert(0, 100);
+                assert_eq!(d.iter().copied().collect::<Vec<_>>(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_make_contiguous() {
+        // Every head position with a full, nearly full and half full ring
+        for head in 0..10 {
+            for len in [0, 1, 3, 5, 8, 9, 10] {
+                let mut d = wrapped(10, head, len);
+                let expected: Vec<usize> = (0..len).collect();
+                assert_eq!(d.make_contiguous(), expected.as_slice());
+                assert_eq!(d.as_slices().0, expected.as_slice());
+                assert!(d.as_slices().1.is_empty());
+                d.push_back(len);
+                assert_eq!(d.len(), len + 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rotate() {
+        for head in 0..6 {
+            for len in 0..=6 {
+                for n in 0..=len {
+                    let mut d = wrapped(6, head, len);
+                    let mut expected: VecDeque<usize> = (0..len).collect();
+                    d.rotate_left(n);
+                    expected.rotate_left(n);
+                    assert!(d.iter().eq(expected.iter()));
+
+                    d.rotate_right(n);
+                    expected.rotate_right(n);
+                    assert!(d.iter().eq(expected.iter()));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_rotate_out_of_range() {
+        let result = std::panic::catch_unwind(|| {
+            let mut d = Deque::from([1, 2]);
+            d.rotate_left(3);
+        });
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_range() {
+        let mut d = wrapped(8, 5, 7);
+        assert_eq!(d.range(1..5).copied().collect::<Vec<_>>(), [1, 2, 3, 4]);
+        assert_eq!(d.range(..=2).copied().collect::<Vec<_>>(), [0, 1, 2]);
+        assert_eq!(d.range(4..).len(), 3);
+        assert_eq!(d.range(3..3).next(), None);
+        for x in d.range_mut(2..4) {
+            *x *= 10;
+        }
+        assert_eq!(d.iter().copied().collect::<Vec<_>>(), [0, 1, 20, 30, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_vector_conversions() {
+        let v = Vector::from([1, 2, 3]);
+        let ptr = v.as_ptr();
+        let mut d = Deque::from(v);
+        assert_eq!(d.as_slices().0.as_ptr(), ptr);
+        d.push_back(4);
+
+        let v: Vector<i32> = d.into();
+        assert_eq!(v, [1, 2, 3, 4]);
+
+        // Wrapped: the elements move to the front of the same buffer
+        let mut d = wrapped(8, 6, 5);
+        d.push_back(5);
+        let v: Vector<usize> = d.into();
+        assert_eq!(v, [0, 1, 2, 3, 4, 5]);
+        assert_eq!(v.capacity(), 8);
+    }
+
+    #[test]
+    fn test_matches_vec_deque() {
+        let mut d = Deque::new();
+        let mut expected = VecDeque::new();
+        let mut seed = 99;
+        for i in 0..5000 {
+            let x = xorshift(&mut seed);
+            match x % 5 {
+                0 => { d.push_front(i); expected.push_front(i); }
+                1 => { d.push_back(i); expected.push_back(i); }
+                2 => assert_eq!(d.pop_front(), expected.pop_front()),
+                3 => assert_eq!(d.pop_back(), expected.pop_back()),
+                _ => {
+                    let n = x as usize % (expected.len() + 1);
+                    d.rotate_left(n);
+                    expected.rotate_left(n);
+                }
+            }
+            assert_eq!(d.len(), expected.len());
+        }
+        assert!(d.iter().eq(expected.iter()));
+        assert!(d.clone().into_iter().eq(expected.into_iter()));
+    }
+
+    #[test]
+    fn test_drop_wrapped() {
+        let drops = Rc::new(Cell::new(0));
+        {
+            let mut d = Deque::with_capacity(4);
+            for _ in 0..3 {
+                d.push_back(Tracked::new(&drops));
+            }
+            drop(d.pop_front());
+            drop(d.pop_front());
+            for _ in 0..3 {
+                d.push_back(Tracked::new(&drops));
+            }
+            assert_eq!(d.as_slices().1.len(), 2);
+            assert_eq!(drops.get(), 2);
+        }
+        assert_eq!(drops.get(), 6);
+
+        // Partially consumed IntoIter drops the rest
+        let drops = Rc::new(Cell::new(0));
+        let d: Deque<Tracked> = (0..5).map(|_| Tracked::new(&drops)).collect();
+        let mut it = d.into_iter();
+        drop(it.next());
+        drop(it.next_back());
+        drop(it);
+        assert_eq!(drops.get(), 5);
+    }
+
+    #[test]
+    fn test_std_traits() {
+        let a: Deque<i32> = (1..=3).collect();
+        let mut b = Deque::new();
+        b.push_front(2);
+        b.push_front(1);
+        b.push_back(3);
+        assert_eq!(a, b);
+        assert_eq!(format!("{:?}", a), "[1, 2, 3]");
+        assert!(a < Deque::from([1, 2, 4]));
+
+        let mut c = a.clone();
+        c.extend([4, 5]);
+        c.extend(&[6]);
+        assert_eq!(c.len(), 6);
+        c.swap(0, 5);
+        assert_eq!(c.front(), Some(&6));
+        c.truncate(2);
+        assert_eq!(c, Deque::from([6, 2]));
+        c.clear();
+        assert!(c.is_empty());
+    }
+
+    #[test]
+    fn test_zst() {
+        let mut d: Deque<()> = Deque::new();
+        for _ in 0..100 {
+            d.push_front(());
+            d.push_back(());
+        }
+        assert_eq!(d.len(), 200);
+        d.rotate_left(50);
+        d.make_contiguous();
+        assert_eq!(d.iter().count(), 200);
+        assert_eq!(d.pop_back(), Some(()));
+    }
+}
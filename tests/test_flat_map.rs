@@ -0,0 +1,168 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::xorshift;
+    use tesap_std::{FlatMap, Vector};
+    use tesap_std::flat_map::Entry;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_insert_get_remove() {
+        let mut m = FlatMap::new();
+        assert_eq!(m.insert(3, "c"), None);
+        assert_eq!(m.insert(1, "a"), None);
+        assert_eq!(m.insert(2, "b"), None);
+        assert_eq!(m.insert(1, "A"), Some("a"));
+        assert_eq!(m.len(), 3);
+        assert_eq!(m.keys(), &[1, 2, 3]);
+        assert_eq!(m.values(), &["A", "b", "c"]);
+
+        assert_eq!(m.get(&2), Some(&"b"));
+        assert_eq!(m.get(&4), None);
+        assert!(m.contains_key(&3));
+        assert_eq!(m[&3], "c");
+        *m.get_mut(&3).unwrap() = "C";
+        assert_eq!(m.get_key_value(&3), Some((&3, &"C")));
+
+        assert_eq!(m.remove(&2), Some("b"));
+        assert_eq!(m.remove(&2), None);
+        assert_eq!(m.remove_entry(&1), Some((1, "A")));
+        assert_eq!(m.keys(), &[3]);
+    }
+
+    #[test]
+    fn test_borrowed_keys() {
+        let mut m: FlatMap<String, u32> = FlatMap::new();
+        m.insert("one".to_string(), 1);
+        m.insert("two".to_string(), 2);
+        assert_eq!(m.get("two"), Some(&2));
+        assert_eq!(m["one"], 1);
+        assert_eq!(m.remove("one"), Some(1));
+    }
+
+    #[test]
+    fn test_entry() {
+        let mut counts: FlatMap<char, usize> = FlatMap::new();
+        for c in "abracadabra".chars() {
+            *counts.entry(c).or_insert(0) += 1;
+        }
+        assert_eq!(counts.keys(), &['a', 'b', 'c', 'd', 'r']);
+        assert_eq!(counts.values(), &[5, 2, 1, 1, 2]);
+
+        counts.entry('a').and_modify(|n| *n *= 10).or_default();
+        counts.entry('z').and_modify(|n| *n *= 10).or_default();
+        assert_eq!(counts[&'a'], 50);
+        assert_eq!(counts[&'z'], 0);
+
+        assert_eq!(*counts.entry('y').or_insert_with_key(|k| *k as usize), 'y' as usize);
+
+        match counts.entry('b') {
+            Entry::Occupied(mut e) => {
+                assert_eq!(e.insert(7), 2);
+                assert_eq!(e.remove(), 7);
+            }
+            Entry::Vacant(_) => panic!("'b' is present"),
+        }
+        match counts.entry('q') {
+            Entry::Vacant(e) => assert_eq!(e.into_key(), 'q'),
+            Entry::Occupied(_) => panic!("'q' is missing"),
+        }
+        assert!(!counts.contains_key(&'b'));
+        assert!(!counts.contains_key(&'q'));
+    }
+
+    #[test]
+    fn test_range() {
+        let mut m: FlatMap<u32, u32> = (0..10).map(|i| (i * 10, i)).collect();
+        let r: Vec<_> = m.range(20..50).map(|(k, _)| *k).collect();
+        assert_eq!(r, [20, 30, 40]);
+        let r: Vec<_> = m.range(25..=50).map(|(k, _)| *k).collect();
+        assert_eq!(r, [30, 40, 50]);
+        assert_eq!(m.range(..).len(), 10);
+        assert_eq!(m.range(1000..).next(), None);
+        assert_eq!(m.range(..30).next_back(), Some((&20, &2)));
+
+        for (_, v) in m.range_mut(80..) {
+            *v = 0;
+        }
+        assert_eq!(&m.values()[7..], &[7, 0, 0]);
+    }
+
+    #[test]
+    fn test_from_unsorted_last_wins() {
+        let m = FlatMap::from_unsorted(Vector::from([(3, 'a'), (1, 'b'), (3, 'c'), (2, 'd'), (1, 'e')]));
+        assert_eq!(m.keys(), &[1, 2, 3]);
+        assert_eq!(m.values(), &['e', 'd', 'c']);
+
+        let m: FlatMap<i32, char> = [(5, 'x'), (5, 'y')].into_iter().collect();
+        assert_eq!(m.values(), &['y']);
+        assert_eq!(FlatMap::from([(2, 0), (1, 0)]).keys(), &[1, 2]);
+    }
+
+    #[test]
+    fn test_extend_merges() {
+        let mut m = FlatMap::from([(1, "a"), (3, "c"), (5, "e")]);
+        m.extend([(4, "d"), (3, "C"), (0, "z"), (4, "D")]);
+        assert_eq!(m.keys(), &[0, 1, 3, 4, 5]);
+        assert_eq!(m.values(), &["z", "a", "C", "D", "e"]);
+    }
+
+    #[test]
+    fn test_iteration_in_key_order() {
+        let m: FlatMap<i32, i32> = [(2, 20), (-1, -10), (7, 70)].into_iter().collect();
+        let pairs: Vec<(i32, i32)> = m.iter().map(|(k, v)| (*k, *v)).collect();
+        assert_eq!(pairs, [(-1, -10), (2, 20), (7, 70)]);
+        assert_eq!(m.first_key_value(), Some((&-1, &-10)));
+        assert_eq!(m.last_key_value(), Some((&7, &70)));
+        assert_eq!(format!("{:?}", m), "{-1: -10, 2: 20, 7: 70}");
+
+        let mut m = m;
+        for (_, v) in &mut m {
+            *v += 1;
+        }
+        assert_eq!(m.pop_first(), Some((-1, -9)));
+        assert_eq!(m.pop_last(), Some((7, 71)));
+        assert_eq!(m.clone().into_iter().collect::<Vec<_>>(), [(2, 21)]);
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut m: FlatMap<u32, u32> = (0..20).map(|i| (i, i * i)).collect();
+        m.retain(|k, v| {
+            *v += 1;
+            k % 3 == 0
+        });
+        assert_eq!(m.keys(), &[0, 3, 6, 9, 12, 15, 18]);
+        assert_eq!(m[&3], 10);
+
+        // A panic midway keeps the visited survivors and all unvisited entries paired
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            m.retain(|&k, _| {
+                assert!(k < 12, "retain bomb");
+                k != 6
+            })
+        }));
+        assert!(result.is_err());
+        assert_eq!(m.keys(), &[0, 3, 9, 12, 15, 18]);
+        assert_eq!(m.values(), &[1, 10, 82, 145, 226, 325]);
+    }
+
+    #[test]
+    fn test_matches_btree_map() {
+        let mut m = FlatMap::new();
+        let mut expected = BTreeMap::new();
+        let mut seed = 12345;
+        for _ in 0..2000 {
+            let x = xorshift(&mut seed);
+            let key = x % 300;
+            if x.is_multiple_of(4) {
+                assert_eq!(m.remove(&key), expected.remove(&key));
+            } else {
+                assert_eq!(m.insert(key, x), expected.insert(key, x));
+            }
+        }
+        assert!(m.iter().eq(expected.iter()));
+        assert!(m.range(100..200).eq(expected.range(100..200)));
+    }
+}
@@ -0,0 +1,37 @@
+
+#[cfg(test)]
+mod tests {
+    use tesap_std::{GrowthPolicy, Doubling, OneAndHalf, FixedIncrement, PageRounded};
+
+    #[test]
+    fn test_doubling() {
+        assert_eq!(Doubling::grow(0, 1, 8), 4);
+        assert_eq!(Doubling::grow(4, 5, 8), 8);
+        assert_eq!(Doubling::grow(8, 100, 8), 100);
+        assert_eq!(Doubling::grow(usize::MAX / 2 + 1, usize::MAX, 1), usize::MAX);
+    }
+
+    #[test]
+    fn test_one_and_half() {
+        assert_eq!(OneAndHalf::grow(0, 1, 8), 4);
+        assert_eq!(OneAndHalf::grow(4, 5, 8), 6);
+        assert_eq!(OneAndHalf::grow(10, 11, 8), 15);
+    }
+
+    #[test]
+    fn test_fixed_increment() {
+        assert_eq!(FixedIncrement::<1>::grow(0, 1, 8), 1);
+        assert_eq!(FixedIncrement::<16>::grow(16, 17, 8), 32);
+        assert_eq!(FixedIncrement::<16>::grow(16, 40, 8), 40);
+    }
+
+    #[test]
+    fn test_page_rounded() {
+        assert_eq!(PageRounded::grow(0, 1, 1), 4096);
+        assert_eq!(PageRounded::grow(0, 1, 8), 512);
+        assert_eq!(PageRounded::grow(512, 513, 8), 1024);
+        // 4096 / 24 is not whole: as many elements as fit into the page
+        assert_eq!(PageRounded::grow(0, 1, 24), 170);
+        assert_eq!(PageRounded::grow(0, 1, 0), 4);
+    }
+}
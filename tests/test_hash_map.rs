@@ -0,0 +1,304 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{CollidingState, Tracked, xorshift};
+    use tesap_std::{HashMap, HashSet};
+    use tesap_std::hash_map::{Drain, Entry, IntoIter, Iter, IterMut};
+    use std::cell::Cell;
+    use std::hash::{BuildHasher, BuildHasherDefault, Hasher};
+    use std::panic::{self, AssertUnwindSafe};
+    use std::rc::Rc;
+
+    #[test]
+    fn test_insert_get_remove() {
+        let mut map = HashMap::new();
+        assert!(map.is_empty());
+        assert_eq!(map.get("a"), None);
+        assert_eq!(map.remove("a"), None);
+
+        assert_eq!(map.insert("a".to_string(), 1), None);
+        assert_eq!(map.insert("b".to_string(), 2), None);
+        assert_eq!(map.insert("a".to_string(), 10), Some(1));
+        assert_eq!(map.len(), 2);
+
+        assert_eq!(map.get("a"), Some(&10));
+        assert_eq!(map["b"], 2);
+        *map.get_mut("b").unwrap() += 5;
+        assert_eq!(map.get_key_value("b"), Some((&"b".to_string(), &7)));
+        assert!(map.contains_key("a"));
+
+        assert_eq!(map.remove("a"), Some(10));
+        assert_eq!(map.remove_entry("b"), Some(("b".to_string(), 7)));
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn test_against_std() {
+        let mut map = HashMap::new();
+        let mut model = std::collections::HashMap::new();
+        let mut seed = 7u64;
+
+        for _ in 0..20_000 {
+            let key = xorshift(&mut seed) % 2000;
+            match xorshift(&mut seed) % 4 {
+                0 | 1 => assert_eq!(map.insert(key, key * 3), model.insert(key, key * 3)),
+                2 => assert_eq!(map.remove(&key), model.remove(&key)),
+                _ => assert_eq!(map.get(&key), model.get(&key)),
+            }
+            assert_eq!(map.len(), model.len());
+        }
+
+        let mut pairs: Vec<_> = map.iter().map(|(&k, &v)| (k, v)).collect();
+        let mut expected: Vec<_> = model.into_iter().collect();
+        pairs.sort();
+        expected.sort();
+        assert_eq!(pairs, expected);
+    }
+
+    #[test]
+    fn test_growth_and_capacity() {
+        let mut map = HashMap::with_capacity(100);
+        let capacity = map.capacity();
+        assert!(capacity >= 100);
+        for i in 0..100 {
+            map.insert(i, i);
+        }
+        assert_eq!(map.capacity(), capacity);
+
+        for i in 100..10_000 {
+            map.insert(i, i);
+        }
+        assert!(map.capacity() >= 10_000);
+        assert!((0..10_000).all(|i| map[&i] == i));
+
+        map.retain(|k, _| *k < 10);
+        map.shrink_to_fit();
+        assert!(map.capacity() < 100);
+        assert_eq!(map.len(), 10);
+        assert!((0..10).all(|i| map.get(&i) == Some(&i)));
+    }
+
+    #[test]
+    fn test_tombstone_churn() {
+        // Keeps the size small while cycling through many keys:
+        // tombstones must be reclaimed instead of growing forever
+        let mut map = HashMap::new();
+        for i in 0..50_000u32 {
+            map.insert(i, i);
+            if i >= 8 {
+                assert_eq!(map.remove(&(i - 8)), Some(i - 8));
+            }
+        }
+        assert_eq!(map.len(), 8);
+        assert!(map.capacity() < 64);
+        assert!((49_992..50_000).all(|i| map.contains_key(&i)));
+    }
+
+    #[test]
+    fn test_colliding_hashes() {
+        let mut map: HashMap<u32, u32, CollidingState> = HashMap::default();
+        for i in 0..200 {
+            map.insert(i, i + 1);
+        }
+        for i in (0..200).step_by(2) {
+            assert_eq!(map.remove(&i), Some(i + 1));
+        }
+        assert_eq!(map.len(), 100);
+        for i in 0..200 {
+            assert_eq!(map.get(&i).copied(), if i % 2 == 1 { Some(i + 1) } else { None });
+        }
+    }
+
+    #[test]
+    fn test_entry() {
+        let mut counts: HashMap<char, usize> = HashMap::new();
+        for c in "hello world".chars() {
+            *counts.entry(c).or_insert(0) += 1;
+        }
+        assert_eq!(counts[&'l'], 3);
+        assert_eq!(counts[&'o'], 2);
+
+        counts.entry('h').and_modify(|n| *n += 10).or_default();
+        counts.entry('z').and_modify(|n| *n += 10).or_default();
+        assert_eq!(counts[&'h'], 11);
+        assert_eq!(counts[&'z'], 0);
+        assert_eq!(*counts.entry('q').or_insert_with_key(|k| *k as usize), 'q' as usize);
+
+        match counts.entry('l') {
+            Entry::Occupied(mut e) => {
+                assert_eq!(e.key(), &'l');
+                assert_eq!(e.insert(30), 3);
+                assert_eq!(e.remove_entry(), ('l', 30));
+            }
+            Entry::Vacant(_) => panic!("'l' is in the map"),
+        }
+        match counts.entry('l') {
+            Entry::Vacant(e) => {
+                assert_eq!(format!("{:?}", Entry::Vacant::<_, usize, _>(e)), "Vacant('l')");
+            }
+            Entry::Occupied(_) => panic!("'l' was removed"),
+        }
+        assert!(!counts.contains_key(&'l'));
+    }
+
+    #[test]
+    fn test_iterators() {
+        let mut map: HashMap<i32, i32> = (0..50).map(|i| (i, i * i)).collect();
+        assert_eq!(map.iter().len(), 50);
+
+        for v in map.values_mut() {
+            *v += 1;
+        }
+        for (_, v) in &mut map {
+            *v -= 1;
+        }
+        let mut keys: Vec<i32> = map.keys().copied().collect();
+        keys.sort();
+        assert_eq!(keys, (0..50).collect::<Vec<_>>());
+        assert_eq!(map.values().sum::<i32>(), (0..50).map(|i| i * i).sum());
+
+        let clone = map.clone();
+        assert_eq!(clone, map);
+
+        let mut drained: Vec<(i32, i32)> = map.drain().take(10).collect();
+        assert_eq!(drained.len(), 10);
+        assert!(map.is_empty());
+        map.insert(1, 1);
+        assert_eq!(map.len(), 1);
+
+        drained = clone.clone().into_iter().collect();
+        drained.sort();
+        assert_eq!(drained[49], (49, 2401));
+        let mut values: Vec<i32> = clone.into_values().collect();
+        values.sort();
+        assert_eq!(values[..3], [0, 1, 4]);
+    }
+
+    #[test]
+    fn test_drops_every_entry_once() {
+        let drops = Rc::new(Cell::new(0));
+        let mut map = HashMap::new();
+        for i in 0..100 {
+            map.insert(i, Tracked::new(&drops));
+        }
+        // Replaced value is dropped right away
+        map.insert(0, Tracked::new(&drops));
+        assert_eq!(drops.get(), 1);
+
+        map.remove(&1);
+        map.retain(|k, _| *k >= 10);
+        assert_eq!(drops.get(), 11);
+
+        let mut drain = map.drain();
+        drain.next();
+        drop(drain);
+        assert_eq!(drops.get(), 101);
+
+        for i in 0..20 {
+            map.insert(i, Tracked::new(&drops));
+        }
+        let mut into_iter = map.into_iter();
+        into_iter.next();
+        drop(into_iter);
+        assert_eq!(drops.get(), 121);
+    }
+
+    #[test]
+    fn test_panicking_hasher_during_resize() {
+        thread_local! {
+            static FUSE: Cell<usize> = const { Cell::new(usize::MAX) };
+        }
+
+        #[derive(Default)]
+        struct Fused(u64);
+
+        impl Hasher for Fused {
+            fn finish(&self) -> u64 {
+                FUSE.with(|f| {
+                    f.set(f.get() - 1);
+                    if f.get() == 0 {
+                        panic!("hasher bomb");
+                    }
+                });
+                self.0.wrapping_mul(0x9e37_79b9_7f4a_7c15)
+            }
+
+            fn write(&mut self, bytes: &[u8]) {
+                for &b in bytes {
+                    self.0 = self.0.rotate_left(8) ^ b as u64;
+                }
+            }
+        }
+
+        let drops = Rc::new(Cell::new(0));
+        let mut map: HashMap<u32, Tracked, BuildHasherDefault<Fused>> = HashMap::default();
+        for i in 0..28 {
+            map.insert(i, Tracked::new(&drops));
+        }
+        // The next insert grows the table and rehashes everything
+        FUSE.with(|f| f.set(10));
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            map.insert(100, Tracked::new(&drops));
+        }));
+        assert!(result.is_err());
+        FUSE.with(|f| f.set(usize::MAX));
+
+        // Only the new value was lost, the map is as before
+        assert_eq!(drops.get(), 1);
+        assert_eq!(map.len(), 28);
+        assert!((0..28).all(|i| map.contains_key(&i)));
+        map.insert(100, Tracked::new(&drops));
+        drop(map);
+        assert_eq!(drops.get(), 30);
+    }
+
+    #[test]
+    fn test_zero_sized_and_tiny() {
+        let mut set_like: HashMap<u8, ()> = HashMap::new();
+        for i in 0..=255 {
+            set_like.insert(i, ());
+        }
+        assert_eq!(set_like.len(), 256);
+
+        let mut unit: HashMap<(), i32> = HashMap::new();
+        assert_eq!(unit.insert((), 1), None);
+        assert_eq!(unit.insert((), 2), Some(1));
+        assert_eq!(unit.len(), 1);
+
+        // Three entries fit the smallest table
+        let mut tiny = HashMap::with_capacity(3);
+        tiny.extend([(1, 'a'), (2, 'b'), (3, 'c')]);
+        tiny.remove(&2);
+        tiny.insert(4, 'd');
+        assert_eq!(tiny.len(), 3);
+        assert_eq!(tiny.get(&4), Some(&'d'));
+        assert_eq!(format!("{:?}", HashMap::<i32, i32>::new()), "{}");
+    }
+
+    #[test]
+    fn test_hasher_is_used() {
+        let map: HashMap<&str, i32> = HashMap::from([("x", 1), ("y", 2)]);
+        let hash = map.hasher().hash_one("x");
+        assert_eq!(hash, map.hasher().hash_one("x"));
+        let copy: HashMap<&str, i32> = map.iter().map(|(&k, &v)| (k, v)).collect();
+        assert_eq!(copy, map);
+    }
+
+    #[test]
+    fn test_send_sync() {
+        fn assert_send<T: Send>() {}
+        fn assert_sync<T: Sync>() {}
+        assert_send::<HashMap<u32, String>>();
+        assert_sync::<HashMap<u32, String>>();
+        assert_send::<Iter<'_, u32, String>>();
+        assert_send::<IterMut<'_, u32, String>>();
+        assert_send::<IntoIter<u32, String>>();
+        assert_send::<Drain<'_, u32, String>>();
+        assert_send::<HashSet<u32>>();
+
+        let mut map: HashMap<u32, String> = (0..100).map(|i| (i, i.to_string())).collect();
+        map = std::thread::spawn(move || map).join().unwrap();
+        assert_eq!(map[&42], "42");
+    }
+}
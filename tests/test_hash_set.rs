@@ -0,0 +1,156 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::CollidingState;
+    use tesap_std::{HashSet, Vector};
+    use std::hash::{Hash, Hasher};
+
+    fn sorted<'a, I: IntoIterator<Item=&'a i32>>(iter: I) -> Vec<i32> {
+        let mut v: Vec<i32> = iter.into_iter().copied().collect();
+        v.sort();
+        v
+    }
+
+    // Equal by `key` only, to tell stored elements apart
+    #[derive(Debug)]
+    struct Tagged {
+        key: u32,
+        tag: &'static str,
+    }
+
+    impl PartialEq for Tagged {
+        fn eq(&self, other: &Self) -> bool {
+            self.key == other.key
+        }
+    }
+
+    impl Eq for Tagged {}
+
+    impl Hash for Tagged {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            self.key.hash(state);
+        }
+    }
+
+    #[test]
+    fn test_insert_contains_remove() {
+        let mut set = HashSet::new();
+        assert!(set.is_empty());
+        assert!(set.insert("a".to_string()));
+        assert!(set.insert("b".to_string()));
+        assert!(!set.insert("a".to_string()));
+        assert_eq!(set.len(), 2);
+
+        assert!(set.contains("a"));
+        assert!(!set.contains("c"));
+        assert_eq!(set.get("b"), Some(&"b".to_string()));
+
+        assert!(set.remove("a"));
+        assert!(!set.remove("a"));
+        assert_eq!(set.take("b"), Some("b".to_string()));
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn test_insert_keeps_and_replace_swaps() {
+        let mut set = HashSet::new();
+        assert!(set.insert(Tagged { key: 1, tag: "first" }));
+        assert!(!set.insert(Tagged { key: 1, tag: "second" }));
+        assert_eq!(set.get(&Tagged { key: 1, tag: "" }).unwrap().tag, "first");
+
+        let old = set.replace(Tagged { key: 1, tag: "third" }).unwrap();
+        assert_eq!(old.tag, "first");
+        assert_eq!(set.get(&Tagged { key: 1, tag: "" }).unwrap().tag, "third");
+        assert!(set.replace(Tagged { key: 2, tag: "new" }).is_none());
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a: HashSet<i32> = (0..10).collect();
+        let b: HashSet<i32> = (5..20).collect();
+
+        assert_eq!(sorted(a.union(&b)), (0..20).collect::<Vec<_>>());
+        assert_eq!(sorted(a.intersection(&b)), (5..10).collect::<Vec<_>>());
+        assert_eq!(sorted(b.intersection(&a)), (5..10).collect::<Vec<_>>());
+        assert_eq!(sorted(a.difference(&b)), (0..5).collect::<Vec<_>>());
+        assert_eq!(sorted(b.difference(&a)), (10..20).collect::<Vec<_>>());
+        assert_eq!(
+            sorted(a.symmetric_difference(&b)),
+            (0..5).chain(10..20).collect::<Vec<_>>()
+        );
+
+        // Lazy: stops as soon as a common element is found
+        assert_eq!(a.intersection(&b).next().map(|x| (5..10).contains(x)), Some(true));
+        assert_eq!(a.union(&HashSet::new()).count(), 10);
+    }
+
+    #[test]
+    fn test_subset_disjoint() {
+        let small: HashSet<i32> = HashSet::from([2, 4]);
+        let big: HashSet<i32> = (0..10).collect();
+        let other: HashSet<i32> = (10..15).collect();
+        let empty: HashSet<i32> = HashSet::new();
+
+        assert!(small.is_subset(&big));
+        assert!(!big.is_subset(&small));
+        assert!(big.is_superset(&small));
+        assert!(empty.is_subset(&small));
+        assert!(big.is_disjoint(&other));
+        assert!(other.is_disjoint(&big));
+        assert!(!small.is_disjoint(&big));
+        assert!(empty.is_disjoint(&empty));
+    }
+
+    #[test]
+    fn test_dedup_vector() {
+        let mut words = Vector::new();
+        for w in ["b", "a", "b", "c", "a", "b"] {
+            words.push(w);
+        }
+        let unique: HashSet<&str> = words.clone().into_iter().collect();
+        assert_eq!(unique.len(), 3);
+
+        let mut sorted: Vec<&str> = unique.into_iter().collect();
+        sorted.sort();
+        assert_eq!(sorted, ["a", "b", "c"]);
+
+        let mut seen = HashSet::new();
+        words.retain(|w| seen.insert(*w));
+        assert_eq!(words.as_slice(), ["b", "a", "c"]);
+    }
+
+    #[test]
+    fn test_extend_retain_drain() {
+        let mut set: HashSet<i32> = HashSet::with_capacity(4);
+        set.extend([1, 2, 3]);
+        set.extend(&[3, 4, 5]);
+        assert_eq!(set.len(), 5);
+
+        set.retain(|x| x % 2 == 1);
+        assert_eq!(sorted(&set), [1, 3, 5]);
+
+        let clone = set.clone();
+        assert_eq!(clone, set);
+        assert_ne!(clone, HashSet::from([1, 3]));
+
+        let mut drained: Vec<i32> = set.drain().collect();
+        drained.sort();
+        assert_eq!(drained, [1, 3, 5]);
+        assert!(set.is_empty());
+        assert_eq!(format!("{:?}", set), "{}");
+        assert_eq!(format!("{:?}", HashSet::from([7])), "{7}");
+    }
+
+    #[test]
+    fn test_colliding_hasher() {
+        let mut a: HashSet<i32, CollidingState> = HashSet::default();
+        let mut b: HashSet<i32, CollidingState> = HashSet::default();
+        a.extend(0..100);
+        b.extend((0..100).step_by(3));
+        assert!(b.is_subset(&a));
+        assert_eq!(a.difference(&b).count(), 66);
+        assert_eq!(a.intersection(&b).count(), 34);
+    }
+}
@@ -0,0 +1,194 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{CollidingState, xorshift};
+    use tesap_std::IndexMap;
+    use tesap_std::index_map::Entry;
+    use std::cell::Cell;
+    use std::panic::{self, AssertUnwindSafe};
+
+    fn keys<V>(map: &IndexMap<&'static str, V>) -> Vec<&'static str> {
+        map.keys().copied().collect()
+    }
+
+    #[test]
+    fn test_insertion_order() {
+        let mut map = IndexMap::new();
+        assert_eq!(map.insert("zeta", 1), None);
+        assert_eq!(map.insert("alpha", 2), None);
+        assert_eq!(map.insert("mid", 3), None);
+        // Updating keeps the position
+        assert_eq!(map.insert("zeta", 10), Some(1));
+        assert_eq!(keys(&map), ["zeta", "alpha", "mid"]);
+        assert_eq!(map.values().copied().collect::<Vec<_>>(), [10, 2, 3]);
+
+        assert_eq!(map.get_index(1), Some((&"alpha", &2)));
+        assert_eq!(map.get_index(3), None);
+        assert_eq!(map.get_index_of("mid"), Some(2));
+        assert_eq!(map.get_full("mid"), Some((2, &"mid", &3)));
+        assert_eq!(map.first(), Some((&"zeta", &10)));
+        assert_eq!(map.last(), Some((&"mid", &3)));
+        assert_eq!(map["alpha"], 2);
+        map[0] += 1;
+        assert_eq!(map.as_slice(), [("zeta", 11), ("alpha", 2), ("mid", 3)]);
+        assert_eq!(format!("{:?}", map), r#"{"zeta": 11, "alpha": 2, "mid": 3}"#);
+    }
+
+    #[test]
+    fn test_swap_and_shift_remove() {
+        let mut map: IndexMap<&str, i32> = IndexMap::from([("a", 1), ("b", 2), ("c", 3), ("d", 4), ("e", 5)]);
+
+        assert_eq!(map.swap_remove("b"), Some(2));
+        assert_eq!(keys(&map), ["a", "e", "c", "d"]);
+        assert_eq!(map.get_index_of("e"), Some(1));
+
+        assert_eq!(map.shift_remove_full("e"), Some((1, "e", 5)));
+        assert_eq!(keys(&map), ["a", "c", "d"]);
+        assert_eq!(map.get_index_of("d"), Some(2));
+        assert_eq!(map.shift_remove("e"), None);
+
+        assert_eq!(map.swap_remove_index(2), Some(("d", 4)));
+        assert_eq!(map.swap_remove_index(2), None);
+        assert_eq!(map.shift_remove_index(0), Some(("a", 1)));
+        assert_eq!(map.pop(), Some(("c", 3)));
+        assert_eq!(map.pop(), None);
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn test_against_model() {
+        // Model: a Vec in insertion order
+        let mut map = IndexMap::new();
+        let mut model: Vec<(u64, u64)> = Vec::new();
+        let mut seed = 99u64;
+
+        for step in 0..5000 {
+            let key = xorshift(&mut seed) % 300;
+            let pos = model.iter().position(|&(k, _)| k == key);
+            match xorshift(&mut seed) % 5 {
+                0 | 1 => {
+                    let old = map.insert(key, step);
+                    match pos {
+                        Some(i) => assert_eq!(old, Some(std::mem::replace(&mut model[i].1, step))),
+                        None => {
+                            assert_eq!(old, None);
+                            model.push((key, step));
+                        }
+                    }
+                }
+                2 => assert_eq!(map.swap_remove(&key), pos.map(|i| model.swap_remove(i).1)),
+                3 => assert_eq!(map.shift_remove(&key), pos.map(|i| model.remove(i).1)),
+                _ => assert_eq!(map.get_index_of(&key), pos),
+            }
+        }
+        assert_eq!(map.as_slice(), model.as_slice());
+        assert!(model.iter().enumerate().all(|(i, (k, _))| map.get_index_of(k) == Some(i)));
+    }
+
+    #[test]
+    fn test_sort_retain_reverse() {
+        let mut map: IndexMap<i32, char> = [(3, 'c'), (1, 'a'), (4, 'd'), (2, 'b'), (5, 'e')].into_iter().collect();
+        map.sort_keys();
+        assert_eq!(map.keys().copied().collect::<Vec<_>>(), [1, 2, 3, 4, 5]);
+        assert_eq!(map.get_index_of(&4), Some(3));
+
+        map.sort_by(|_, v1, _, v2| v2.cmp(v1));
+        assert_eq!(map.values().collect::<String>(), "edcba");
+        assert_eq!(map.get_index_of(&5), Some(0));
+
+        map.retain(|k, v| {
+            *v = v.to_ascii_uppercase();
+            k % 2 == 1
+        });
+        assert_eq!(map.as_slice(), [(5, 'E'), (3, 'C'), (1, 'A')]);
+        assert_eq!(map.get_index_of(&1), Some(2));
+
+        map.reverse();
+        assert_eq!(map.as_slice(), [(1, 'A'), (3, 'C'), (5, 'E')]);
+        assert_eq!(map.get_index_of(&5), Some(2));
+        assert_eq!(map.get(&3), Some(&'C'));
+    }
+
+    #[test]
+    fn test_panicking_sort_keeps_map_consistent() {
+        let mut map: IndexMap<u32, u32> = (0..100).map(|i| (i, i)).collect();
+        let calls = Cell::new(0);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            map.sort_by(|k1, _, k2, _| {
+                calls.set(calls.get() + 1);
+                if calls.get() == 50 {
+                    panic!("comparator bomb");
+                }
+                k2.cmp(k1)
+            });
+        }));
+        assert!(result.is_err());
+
+        // Whatever order the sort left, every key is found at its position
+        assert_eq!(map.len(), 100);
+        for (i, (k, _)) in map.iter().enumerate() {
+            assert_eq!(map.get_index_of(k), Some(i));
+        }
+    }
+
+    #[test]
+    fn test_entry() {
+        let mut map: IndexMap<char, usize> = IndexMap::new();
+        for c in "mississippi".chars() {
+            *map.entry(c).or_insert(0) += 1;
+        }
+        assert_eq!(map.as_slice(), [('m', 1), ('i', 4), ('s', 4), ('p', 2)]);
+
+        match map.entry('x') {
+            Entry::Vacant(e) => {
+                assert_eq!(e.index(), 4);
+                *e.insert(7) += 1;
+            }
+            Entry::Occupied(_) => panic!("'x' is not in the map"),
+        }
+        assert_eq!(map[4], 8);
+
+        match map.entry('i') {
+            Entry::Occupied(mut e) => {
+                assert_eq!(e.index(), 1);
+                assert_eq!(e.insert(40), 4);
+                assert_eq!(e.shift_remove_entry(), ('i', 40));
+            }
+            Entry::Vacant(_) => panic!("'i' is in the map"),
+        }
+        map.entry('m').and_modify(|n| *n += 1).or_default();
+        assert_eq!(map.as_slice(), [('m', 2), ('s', 4), ('p', 2), ('x', 8)]);
+        if let Entry::Occupied(e) = map.entry('m') {
+            assert_eq!(e.swap_remove(), 2);
+        }
+        assert_eq!(map.as_slice(), [('x', 8), ('s', 4), ('p', 2)]);
+    }
+
+    #[test]
+    fn test_colliding_hashes_and_traits() {
+        let mut map: IndexMap<u32, u32, CollidingState> = IndexMap::default();
+        map.extend((0..64).map(|i| (i, i * 2)));
+        for i in (0..64).step_by(3) {
+            assert_eq!(map.swap_remove(&i), Some(i * 2));
+        }
+        for (i, (k, v)) in map.iter().enumerate() {
+            assert_eq!(map.get_index_of(k), Some(i));
+            assert_eq!(*v, k * 2);
+        }
+
+        let mut shuffled = map.clone();
+        shuffled.reverse();
+        // Equality ignores the order
+        assert_eq!(shuffled, map);
+        assert_ne!(shuffled.as_slice(), map.as_slice());
+
+        for v in &mut shuffled {
+            *v.1 += 1;
+        }
+        assert_ne!(shuffled, map);
+        // Reversed twice: back in the order of `map`
+        let collected: Vec<(u32, u32)> = shuffled.into_iter().rev().map(|(k, v)| (k, v - 1)).collect();
+        assert_eq!(collected, map.as_slice());
+    }
+}
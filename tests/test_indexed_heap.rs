@@ -0,0 +1,161 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::xorshift;
+    use tesap_std::IndexedHeap;
+    use tesap_std::indexed_heap::Handle;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_push_pop_order() {
+        let mut heap = IndexedHeap::new();
+        assert_eq!(heap.pop(), None);
+        for (key, priority) in [("c", 3), ("a", 1), ("d", 4), ("b", 2)] {
+            heap.push(key, priority);
+        }
+        assert_eq!(heap.len(), 4);
+        assert_eq!(heap.peek(), Some((&"a", &1)));
+        assert_eq!(
+            heap.into_sorted_vec().as_slice(),
+            [("a", 1), ("b", 2), ("c", 3), ("d", 4)]
+        );
+    }
+
+    #[test]
+    fn test_decrease_increase_remove() {
+        let mut heap = IndexedHeap::new();
+        let a = heap.push('a', 10);
+        let b = heap.push('b', 20);
+        let c = heap.push('c', 30);
+
+        assert_eq!(heap.decrease_key(c, 5), Some(30));
+        assert_eq!(heap.peek_handle(), Some(c));
+        assert_eq!(heap.increase_key(c, 25), Some(5));
+        assert_eq!(heap.peek(), Some((&'a', &10)));
+        assert_eq!(heap.change_priority(b, 1), Some(20));
+        assert_eq!(heap.peek(), Some((&'b', &1)));
+
+        assert_eq!(heap.remove(a), Some(('a', 10)));
+        assert!(!heap.contains(a));
+        assert_eq!(heap.remove(a), None);
+        assert_eq!(heap.decrease_key(a, 0), None);
+
+        *heap.get_key_mut(c).unwrap() = 'C';
+        assert_eq!(heap.get(c), Some((&'C', &25)));
+        assert_eq!(heap.priority(b), Some(&1));
+        assert_eq!(heap.pop(), Some(('b', 1)));
+        assert_eq!(heap.pop(), Some(('C', 25)));
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "decrease_key to a greater priority")]
+    fn test_decrease_key_wrong_direction() {
+        let mut heap = IndexedHeap::new();
+        let h = heap.push((), 1);
+        heap.decrease_key(h, 2);
+    }
+
+    #[test]
+    fn test_stale_handles() {
+        let mut heap = IndexedHeap::new();
+        let old = heap.push("old", 1);
+        heap.pop();
+        // Reuses the slot of `old`
+        let new = heap.push("new", 2);
+        assert_ne!(old, new);
+        assert!(!heap.contains(old));
+        assert_eq!(heap.get(old), None);
+        assert_eq!(heap.get(new), Some((&"new", &2)));
+
+        heap.clear();
+        assert!(!heap.contains(new));
+        let h = heap.push("again", 3);
+        assert_eq!(heap.iter().map(|(handle, _, _)| handle).collect::<Vec<Handle>>(), [h]);
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        // Random graph, checked against Bellman-Ford
+        const NODES: usize = 60;
+        let mut seed = 0x2545_f491_4f6c_dd1d;
+        let mut edges = Vec::new();
+        for _ in 0..400 {
+            let from = (xorshift(&mut seed) % NODES as u64) as usize;
+            let to = (xorshift(&mut seed) % NODES as u64) as usize;
+            edges.push((from, to, xorshift(&mut seed) % 100));
+        }
+
+        let mut dist = vec![u64::MAX; NODES];
+        let mut handles: Vec<Option<Handle>> = vec![None; NODES];
+        let mut heap = IndexedHeap::new();
+        dist[0] = 0;
+        handles[0] = Some(heap.push(0, 0));
+        while let Some((node, d)) = heap.pop() {
+            for &(from, to, w) in &edges {
+                if from != node || d + w >= dist[to] {
+                    continue;
+                }
+                dist[to] = d + w;
+                match handles[to] {
+                    Some(h) if heap.contains(h) => {
+                        heap.decrease_key(h, d + w);
+                    }
+                    _ => handles[to] = Some(heap.push(to, d + w)),
+                }
+            }
+        }
+
+        let mut expected = vec![u64::MAX; NODES];
+        expected[0] = 0;
+        for _ in 0..NODES {
+            for &(from, to, w) in &edges {
+                if expected[from] != u64::MAX {
+                    expected[to] = expected[to].min(expected[from] + w);
+                }
+            }
+        }
+        assert_eq!(dist, expected);
+    }
+
+    #[test]
+    fn test_random_ops_against_model() {
+        let mut heap = IndexedHeap::new();
+        // (priority, id) -> handle
+        let mut model: BTreeMap<(u64, usize), Handle> = BTreeMap::new();
+        let mut seed = 42u64;
+
+        for id in 0..3000 {
+            match xorshift(&mut seed) % 5 {
+                0 | 1 => {
+                    let p = xorshift(&mut seed) % 500;
+                    model.insert((p, id), heap.push(id, p));
+                }
+                2 => {
+                    let got = heap.pop().map(|(id, p)| (p, id));
+                    let expected = model.keys().next().copied();
+                    // Equal priorities may come out in any order
+                    assert_eq!(got.map(|g| g.0), expected.map(|e| e.0));
+                    if let Some(g) = got {
+                        model.remove(&g).unwrap();
+                    }
+                }
+                _ if !model.is_empty() => {
+                    let nth = (xorshift(&mut seed) as usize) % model.len();
+                    let (&(p, item), &h) = model.iter().nth(nth).unwrap();
+                    model.remove(&(p, item));
+                    if xorshift(&mut seed).is_multiple_of(2) {
+                        assert_eq!(heap.remove(h), Some((item, p)));
+                    } else {
+                        let q = xorshift(&mut seed) % 500;
+                        assert_eq!(heap.change_priority(h, q), Some(p));
+                        model.insert((q, item), h);
+                    }
+                }
+                _ => {}
+            }
+            assert_eq!(heap.len(), model.len());
+        }
+    }
+}
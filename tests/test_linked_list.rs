@@ -0,0 +1,299 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{CountingAlloc, Tracked, xorshift};
+    use tesap_std::LinkedList;
+    use tesap_std::linked_list::{CursorMut, Iter, IterMut};
+    use std::cell::Cell;
+    use std::collections::VecDeque;
+    use std::panic::{self, AssertUnwindSafe};
+    use std::rc::Rc;
+
+    fn items<T: Copy>(list: &LinkedList<T>) -> Vec<T> {
+        list.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_push_pop() {
+        let mut list = LinkedList::new();
+        assert_eq!(list.pop_front(), None);
+        list.push_back(2);
+        list.push_back(3);
+        list.push_front(1);
+        assert_eq!(items(&list), [1, 2, 3]);
+        assert_eq!((list.front(), list.back()), (Some(&1), Some(&3)));
+        *list.back_mut().unwrap() = 30;
+        *list.front_mut().unwrap() = 10;
+
+        assert_eq!(list.pop_back(), Some(30));
+        assert_eq!(list.pop_front(), Some(10));
+        assert_eq!(list.pop_back(), Some(2));
+        assert_eq!(list.pop_back(), None);
+        assert!(list.is_empty());
+        assert_eq!(list.back(), None);
+    }
+
+    #[test]
+    fn test_append_split_off() {
+        let mut a = LinkedList::from([1, 2, 3]);
+        let mut b = LinkedList::from([4, 5]);
+        a.append(&mut b);
+        assert!(b.is_empty());
+        assert_eq!(items(&a), [1, 2, 3, 4, 5]);
+        b.append(&mut a);
+        assert_eq!((a.len(), b.len()), (0, 5));
+
+        for at in 0..=5 {
+            let mut left = b.clone();
+            let right = left.split_off(at);
+            assert_eq!(items(&left), (1..=at as i32).collect::<Vec<_>>());
+            assert_eq!(items(&right), (at as i32 + 1..=5).collect::<Vec<_>>());
+            assert_eq!(right.iter().rev().count(), 5 - at);
+        }
+        assert!(panic::catch_unwind(AssertUnwindSafe(|| b.split_off(6))).is_err());
+    }
+
+    #[test]
+    fn test_append_across_arenas() {
+        let (arena, other_arena) = (CountingAlloc::default(), CountingAlloc::default());
+        {
+            let mut a = LinkedList::new_in(&arena);
+            let mut b = LinkedList::new_in(&arena);
+            a.extend([1, 2]);
+            b.extend([3, 4]);
+            // Same arena: the nodes are relinked
+            a.append(&mut b);
+            assert_eq!(arena.allocs.get(), 4);
+
+            let mut c = LinkedList::new_in(&other_arena);
+            c.extend([5, 6]);
+            // Different arenas: the elements move into nodes of `a`
+            a.append(&mut c);
+            assert_eq!((arena.live.get(), other_arena.live.get()), (6, 0));
+
+            let mut d = LinkedList::new_in(&other_arena);
+            d.extend([0, 9]);
+            let mut cursor = a.cursor_front_mut();
+            cursor.move_next();
+            cursor.splice_before(d);
+            assert_eq!(cursor.index(), Some(3));
+            let mut e = LinkedList::new_in(&other_arena);
+            e.push_back(7);
+            cursor.splice_after(e);
+            assert_eq!(cursor.peek_next(), Some(&mut 7));
+            assert!(a.iter().copied().eq([1, 0, 9, 2, 7, 3, 4, 5, 6]));
+            assert_eq!((arena.live.get(), other_arena.live.get()), (9, 0));
+        }
+        assert_eq!((arena.live.get(), other_arena.live.get()), (0, 0));
+    }
+
+    #[test]
+    fn test_cursor_navigation() {
+        let mut list = LinkedList::from([1, 2, 3]);
+        let mut cursor = list.cursor_front_mut();
+        assert_eq!(cursor.index(), Some(0));
+        assert_eq!(cursor.peek_prev(), None);
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some(&mut 2));
+        cursor.move_next();
+        cursor.move_next();
+        // The ghost sits between the back and the front
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.peek_next(), Some(&mut 1));
+        assert_eq!(cursor.peek_prev(), Some(&mut 3));
+        cursor.move_next();
+        assert_eq!(cursor.index(), Some(0));
+        cursor.move_prev();
+        cursor.move_prev();
+        assert_eq!((cursor.index(), cursor.current()), (Some(2), Some(&mut 3)));
+
+        let mut empty: LinkedList<i32> = LinkedList::new();
+        let mut cursor = empty.cursor_back_mut();
+        assert_eq!(cursor.index(), None);
+        cursor.move_prev();
+        cursor.move_next();
+        assert_eq!(cursor.current(), None);
+    }
+
+    #[test]
+    fn test_cursor_edits() {
+        let mut list = LinkedList::from([1, 2, 3]);
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+        cursor.insert_before(10);
+        cursor.insert_after(20);
+        assert_eq!(cursor.index(), Some(2));
+        assert_eq!(cursor.remove_current(), Some(2));
+        assert_eq!(cursor.current(), Some(&mut 20));
+
+        cursor.splice_after(LinkedList::from([30, 31]));
+        cursor.splice_before(LinkedList::from([40]));
+        assert_eq!(cursor.index(), Some(3));
+        assert_eq!(items(&list), [1, 10, 40, 20, 30, 31, 3]);
+
+        let mut cursor = list.cursor_back_mut();
+        cursor.move_next();
+        // On the ghost: after goes to the front, before to the back
+        cursor.insert_after(0);
+        cursor.insert_before(4);
+        cursor.splice_after(LinkedList::from([-1]));
+        assert_eq!(cursor.remove_current(), None);
+        cursor.move_prev();
+        assert_eq!(cursor.index(), Some(9));
+        assert_eq!(items(&list), [-1, 0, 1, 10, 40, 20, 30, 31, 3, 4]);
+    }
+
+    #[test]
+    fn test_cursor_split() {
+        let mut list: LinkedList<i32> = (0..10).collect();
+        let mut cursor = list.cursor_front_mut();
+        for _ in 0..4 {
+            cursor.move_next();
+        }
+        let front = cursor.split_before();
+        assert_eq!(cursor.index(), Some(0));
+        assert_eq!(cursor.current(), Some(&mut 4));
+        cursor.move_next();
+        let back = cursor.split_after();
+        assert_eq!(cursor.index(), Some(1));
+        assert_eq!(items(&front), [0, 1, 2, 3]);
+        assert_eq!(items(&back), [6, 7, 8, 9]);
+        assert_eq!(items(&list), [4, 5]);
+
+        // On the ghost both take everything
+        let mut cursor = list.cursor_back_mut();
+        cursor.move_next();
+        assert_eq!(items(&cursor.split_before()), [4, 5]);
+        assert_eq!(cursor.split_after().len(), 0);
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn test_cursor_against_model() {
+        let mut list: LinkedList<u64> = LinkedList::new();
+        let mut model: VecDeque<u64> = VecDeque::new();
+        let mut seed = 5u64;
+        // Model position: `model.len()` is the ghost
+        let mut pos = 0;
+        let mut cursor = list.cursor_front_mut();
+
+        for step in 0..5000 {
+            let ghost = pos == model.len();
+            match xorshift(&mut seed) % 6 {
+                0 => {
+                    cursor.move_next();
+                    pos = if ghost { 0 } else { pos + 1 };
+                }
+                1 => {
+                    cursor.move_prev();
+                    pos = if ghost { model.len().saturating_sub(1) } else if pos == 0 { model.len() } else { pos - 1 };
+                }
+                2 => {
+                    cursor.insert_before(step);
+                    model.insert(pos, step);
+                    pos += 1;
+                }
+                3 => {
+                    cursor.insert_after(step);
+                    if ghost {
+                        model.push_front(step);
+                        pos += 1;
+                    } else {
+                        model.insert(pos + 1, step);
+                    }
+                }
+                _ => assert_eq!(cursor.remove_current(), model.remove(pos)),
+            }
+            assert_eq!(cursor.index(), (pos < model.len()).then_some(pos));
+        }
+        assert!(list.iter().eq(model.iter()));
+        assert!(list.iter().rev().eq(model.iter().rev()));
+        assert_eq!(list.len(), model.len());
+    }
+
+    #[test]
+    fn test_drops_and_panics() {
+        let drops = Rc::new(Cell::new(0));
+        let mut list = LinkedList::new();
+        for _ in 0..10 {
+            list.push_back(Tracked::new(&drops));
+        }
+        drop(list.pop_front());
+        let mut cursor = list.cursor_front_mut();
+        drop(cursor.remove_current());
+        assert_eq!(drops.get(), 2);
+        drop(list.split_off(4));
+        assert_eq!(drops.get(), 6);
+        drop(list);
+        assert_eq!(drops.get(), 10);
+
+        // A panicking destructor doesn't leak the rest
+        let count = Rc::new(Cell::new(0));
+        let list: LinkedList<Tracked> =
+            (0..8).map(|i| if i == 2 { Tracked::bomb(&count) } else { Tracked::new(&count) }).collect();
+        assert!(panic::catch_unwind(AssertUnwindSafe(|| drop(list))).is_err());
+        assert_eq!(count.get(), 8);
+
+        // A panicking clone drops the partial copy
+        struct Bomb(Rc<Cell<usize>>);
+        impl Clone for Bomb {
+            fn clone(&self) -> Self {
+                self.0.set(self.0.get() + 1);
+                if self.0.get() == 3 {
+                    panic!("clone bomb");
+                }
+                Bomb(self.0.clone())
+            }
+        }
+        let clones = Rc::new(Cell::new(0));
+        let list: LinkedList<Bomb> = (0..5).map(|_| Bomb(clones.clone())).collect();
+        assert!(panic::catch_unwind(AssertUnwindSafe(|| list.clone())).is_err());
+        assert_eq!(Rc::strong_count(&clones), 6);
+    }
+
+    #[test]
+    fn test_iterators_and_traits() {
+        let mut list: LinkedList<i32> = (1..=4).collect();
+        for x in &mut list {
+            *x *= 10;
+        }
+        list.extend(&[50]);
+        assert_eq!(list.iter().len(), 5);
+        assert_eq!(list.iter_mut().next_back(), Some(&mut 50));
+        assert!(list.contains(&30));
+        assert!(!list.contains(&31));
+
+        assert_eq!(format!("{:?}", list), "[10, 20, 30, 40, 50]");
+        assert_eq!(list.clone(), list);
+        assert_ne!(list, LinkedList::from([10, 20]));
+        assert_eq!(LinkedList::<u8>::default().iter().next(), None);
+
+        let mut into_iter = list.into_iter();
+        assert_eq!(into_iter.next_back(), Some(50));
+        assert_eq!(into_iter.len(), 4);
+        assert_eq!(into_iter.collect::<Vec<_>>(), [10, 20, 30, 40]);
+    }
+
+    #[test]
+    fn test_variance_and_threads() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<LinkedList<u32>>();
+        assert_send_sync::<Iter<'_, u32>>();
+        assert_send_sync::<IterMut<'_, u32>>();
+        assert_send_sync::<CursorMut<'_, u32>>();
+
+        // Covariant like std: a list of longer-lived references can be used as a shorter one
+        fn shorten<'a>(list: LinkedList<&'static str>) -> LinkedList<&'a str> {
+            list
+        }
+        fn shorten_iter<'a, 'b>(iter: Iter<'a, &'static str>) -> Iter<'a, &'b str> {
+            iter
+        }
+        let list = shorten(LinkedList::from(["a", "b"]));
+        assert_eq!(shorten_iter(list.iter()).count(), 2);
+        let list = std::thread::spawn(move || list).join().unwrap();
+        assert_eq!(list.back(), Some(&"b"));
+    }
+}
@@ -0,0 +1,183 @@
+#[cfg(test)]
+mod tests {
+    use tesap_std::mpmc::{self, RecvError, SendError, TryRecvError, TrySendError};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+
+    const PER_THREAD: usize = 20_000;
+
+    #[test]
+    fn test_try_send_recv() {
+        let (tx, rx) = mpmc::channel(3);
+        assert_eq!(tx.capacity(), 3);
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Empty));
+
+        for i in 0..3 {
+            assert!(tx.try_send(i).is_ok());
+        }
+        assert!(tx.is_full());
+        assert_eq!(tx.len(), 3);
+        let err = tx.try_send(3).unwrap_err();
+        assert!(err.is_full());
+        assert_eq!(err.into_inner(), 3);
+
+        assert_eq!(rx.try_recv(), Ok(0));
+        assert!(tx.try_send(3).is_ok());
+        assert_eq!(rx.try_iter().collect::<Vec<_>>(), [1, 2, 3]);
+        assert!(rx.is_empty());
+
+        // Many laps over a capacity that is not a power of two
+        for i in 0..100 {
+            tx.try_send(i).unwrap();
+            tx.try_send(i + 1).unwrap();
+            assert_eq!(rx.len(), 2);
+            assert_eq!(rx.try_recv(), Ok(i));
+            assert_eq!(rx.try_recv(), Ok(i + 1));
+        }
+    }
+
+    #[test]
+    fn test_disconnect() {
+        let (tx, rx) = mpmc::channel(2);
+        let tx2 = tx.clone();
+        tx.send(1).unwrap();
+        drop(tx);
+        tx2.send(2).unwrap();
+        drop(tx2);
+        // Queued values are still delivered
+        assert_eq!(rx.recv(), Ok(1));
+        assert_eq!(rx.try_recv(), Ok(2));
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Disconnected));
+        assert_eq!(rx.recv(), Err(RecvError));
+
+        let (tx, rx) = mpmc::channel(2);
+        drop(rx);
+        assert!(tx.try_send(1).unwrap_err().is_disconnected());
+        assert_eq!(tx.send(2), Err(SendError(2)));
+        assert!(matches!(tx.try_send(3), Err(TrySendError::Disconnected(3))));
+    }
+
+    #[test]
+    fn test_blocking_wakeups() {
+        let (tx, rx) = mpmc::channel(1);
+
+        // Receiver sleeps until a value arrives
+        let consumer = thread::spawn(move || rx.iter().collect::<Vec<i32>>());
+        for i in 0..50 {
+            // Capacity 1: most sends wait for the consumer
+            tx.send(i).unwrap();
+        }
+        drop(tx);
+        assert_eq!(consumer.join().unwrap(), (0..50).collect::<Vec<_>>());
+
+        // Sender sleeps on a full queue until the receiver leaves
+        let (tx, rx) = mpmc::channel(1);
+        tx.send(0).unwrap();
+        let producer = thread::spawn(move || tx.send(1));
+        thread::sleep(std::time::Duration::from_millis(20));
+        drop(rx);
+        assert_eq!(producer.join().unwrap(), Err(SendError(1)));
+    }
+
+    #[test]
+    fn test_drops_unreceived() {
+        struct Tracked(Arc<AtomicUsize>);
+
+        impl Drop for Tracked {
+            fn drop(&mut self) {
+                self.0.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let drops = Arc::new(AtomicUsize::new(0));
+        let (tx, rx) = mpmc::channel(4);
+        for _ in 0..3 {
+            tx.send(Tracked(drops.clone())).unwrap();
+        }
+        drop(rx.recv());
+        assert_eq!(drops.load(Ordering::Relaxed), 1);
+        drop(tx);
+        drop(rx);
+        assert_eq!(drops.load(Ordering::Relaxed), 3);
+    }
+
+    #[test]
+    fn test_stress_many_to_many() {
+        const SENDERS: usize = 4;
+        const RECEIVERS: usize = 3;
+
+        let (tx, rx) = mpmc::channel(16);
+        let sum = Arc::new(AtomicUsize::new(0));
+        let count = Arc::new(AtomicUsize::new(0));
+
+        let mut handles = Vec::new();
+        for t in 0..SENDERS {
+            let tx = tx.clone();
+            handles.push(thread::spawn(move || {
+                for i in 0..PER_THREAD {
+                    tx.send(t * PER_THREAD + i).unwrap();
+                }
+            }));
+        }
+        drop(tx);
+
+        for _ in 0..RECEIVERS {
+            let rx = rx.clone();
+            let sum = sum.clone();
+            let count = count.clone();
+            handles.push(thread::spawn(move || {
+                for value in rx {
+                    sum.fetch_add(value, Ordering::Relaxed);
+                    count.fetch_add(1, Ordering::Relaxed);
+                }
+            }));
+        }
+        drop(rx);
+
+        for h in handles {
+            h.join().unwrap();
+        }
+        let n = SENDERS * PER_THREAD;
+        assert_eq!(count.load(Ordering::Relaxed), n);
+        assert_eq!(sum.load(Ordering::Relaxed), n * (n - 1) / 2);
+    }
+
+    #[test]
+    fn test_stress_try_ops_keep_order_per_sender() {
+        let (tx, rx) = mpmc::channel(5);
+
+        let producers: Vec<_> = (0..2)
+            .map(|t| {
+                let tx = tx.clone();
+                thread::spawn(move || {
+                    for i in 0..PER_THREAD {
+                        let mut value = (t, i);
+                        while let Err(err) = tx.try_send(value) {
+                            value = err.into_inner();
+                            thread::yield_now();
+                        }
+                    }
+                })
+            })
+            .collect();
+        drop(tx);
+
+        // A single receiver sees each sender's values in order
+        let mut next = [0; 2];
+        loop {
+            match rx.try_recv() {
+                Ok((t, i)) => {
+                    assert_eq!(i, next[t]);
+                    next[t] += 1;
+                }
+                Err(TryRecvError::Empty) => thread::yield_now(),
+                Err(TryRecvError::Disconnected) => break,
+            }
+        }
+        for p in producers {
+            p.join().unwrap();
+        }
+        assert_eq!(next, [PER_THREAD; 2]);
+    }
+}
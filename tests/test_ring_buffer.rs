@@ -0,0 +1,145 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::Tracked;
+    use tesap_std::{HeapRingBuffer, RingBuffer, Vector};
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_overwrites_oldest() {
+        let mut r: RingBuffer<i32, 3> = RingBuffer::new();
+        assert!(r.is_empty());
+        assert_eq!(r.latest(), None);
+        assert_eq!(r.push(1), None);
+        assert_eq!(r.push(2), None);
+        assert_eq!(r.push(3), None);
+        assert!(r.is_full());
+        assert_eq!(r.push(4), Some(1));
+        assert_eq!(r.push(5), Some(2));
+
+        assert_eq!(r.len(), 3);
+        assert_eq!(r.oldest(), Some(&3));
+        assert_eq!(r.latest(), Some(&5));
+        assert_eq!(r.get(1), Some(&4));
+        assert_eq!(r.get(3), None);
+        assert_eq!(r.iter().copied().collect::<Vec<_>>(), [3, 4, 5]);
+        assert_eq!(r.iter().rev().copied().collect::<Vec<_>>(), [5, 4, 3]);
+        assert_eq!(r.as_slices(), (&[3][..], &[4, 5][..]));
+
+        *r.latest_mut().unwrap() = 50;
+        assert_eq!(r.pop_latest(), Some(50));
+        assert_eq!(r.pop_oldest(), Some(3));
+        assert_eq!(r.iter().copied().collect::<Vec<_>>(), [4]);
+    }
+
+    #[test]
+    fn test_heap_matches_inline() {
+        let mut inline: RingBuffer<usize, 5> = RingBuffer::new();
+        let mut heap = HeapRingBuffer::new(5);
+        assert_eq!(heap.capacity(), 5);
+        for i in 0..23 {
+            assert_eq!(inline.push(i), heap.push(i));
+            if i % 7 == 6 {
+                assert_eq!(inline.pop_oldest(), heap.pop_oldest());
+            }
+            assert_eq!(inline.iter().collect::<Vec<_>>(), heap.iter().collect::<Vec<_>>());
+            assert_eq!(inline.latest(), Some(&i));
+            assert_eq!(heap.latest(), Some(&i));
+        }
+        assert_eq!(format!("{:?}", heap), "[18, 19, 20, 21, 22]");
+    }
+
+    #[test]
+    fn test_to_vector() {
+        let mut r = HeapRingBuffer::new(4);
+        r.extend(0..10);
+        let v = r.to_vector();
+        assert_eq!(v, Vector::from([6, 7, 8, 9]));
+        assert_eq!(r.len(), 4);
+
+        let r: RingBuffer<String, 2> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(r.to_vector(), ["b".to_string(), "c".to_string()]);
+    }
+
+    #[test]
+    fn test_drain() {
+        let mut r: RingBuffer<i32, 4> = (0..6).collect();
+        assert_eq!(r.drain().collect::<Vec<_>>(), [2, 3, 4, 5]);
+        assert!(r.is_empty());
+
+        r.extend(0..6);
+        let mut d = r.drain();
+        assert_eq!(d.len(), 4);
+        assert_eq!(d.next(), Some(2));
+        assert_eq!(d.next_back(), Some(5));
+        drop(d);
+        assert!(r.is_empty());
+
+        r.push(7);
+        assert_eq!(r.iter().copied().collect::<Vec<_>>(), [7]);
+    }
+
+    #[test]
+    fn test_drops_every_element_once() {
+        let drops = Rc::new(Cell::new(0));
+        let mut r = HeapRingBuffer::new(3);
+        for _ in 0..5 {
+            r.push(Tracked::new(&drops));
+        }
+        // Two were evicted and dropped by the caller
+        assert_eq!(drops.get(), 2);
+
+        let mut d = r.drain();
+        d.next();
+        drop(d);
+        assert_eq!(drops.get(), 5);
+
+        for _ in 0..4 {
+            r.push(Tracked::new(&drops));
+        }
+        drop(r);
+        assert_eq!(drops.get(), 9);
+
+        let mut inline: RingBuffer<Tracked, 2> = RingBuffer::new();
+        inline.push(Tracked::new(&drops));
+        inline.push(Tracked::new(&drops));
+        drop(inline);
+        assert_eq!(drops.get(), 11);
+    }
+
+    #[test]
+    fn test_zero_capacity_and_zst() {
+        let mut r: RingBuffer<i32, 0> = RingBuffer::new();
+        assert_eq!(r.push(1), Some(1));
+        assert!(r.is_empty());
+        assert_eq!(r.latest(), None);
+
+        let mut h = HeapRingBuffer::new(0);
+        assert_eq!(h.push(1), Some(1));
+        assert_eq!(h.to_vector().len(), 0);
+
+        let mut z = HeapRingBuffer::new(2);
+        z.extend([(), (), ()]);
+        assert_eq!(z.len(), 2);
+        assert_eq!(z.drain().count(), 2);
+    }
+
+    #[test]
+    fn test_clone() {
+        let mut r: RingBuffer<String, 3> = RingBuffer::default();
+        r.extend(["x", "y", "z", "w"].iter().map(|s| s.to_string()));
+        let c = r.clone();
+        assert_eq!(c.iter().collect::<Vec<_>>(), r.iter().collect::<Vec<_>>());
+
+        let h: HeapRingBuffer<i32> = {
+            let mut h = HeapRingBuffer::new(2);
+            h.extend([1, 2, 3]);
+            h
+        };
+        let hc = h.clone();
+        assert_eq!(hc.capacity(), 2);
+        assert_eq!((&hc).into_iter().copied().collect::<Vec<_>>(), [2, 3]);
+    }
+}
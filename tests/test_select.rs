@@ -0,0 +1,168 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::random;
+    use tesap_std::Vector;
+    use std::cell::{Cell, RefCell};
+
+    #[test]
+    fn test_select_nth_unstable() {
+        for len in [1, 5, 21, 100, 1000, 5000] {
+            for modulo in [3, 1000, u64::MAX] {
+                let p = random(len, modulo, len as u64);
+                let mut sorted = p.clone();
+                sorted.sort();
+
+                for index in [0, len / 3, len / 2, len - 1] {
+                    let mut v = Vector::from(p.clone());
+                    let (left, nth, right) = v.select_nth_unstable(index);
+                    assert_eq!(*nth, sorted[index]);
+                    assert!(left.iter().all(|x| x <= nth));
+                    assert!(right.iter().all(|x| x >= nth));
+                    assert_eq!(left.len(), index);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_select_nth_all_equal_and_sorted() {
+        let mut v = Vector::new_copy(7u32, 10000);
+        assert_eq!(*v.select_nth_unstable(5000).1, 7);
+
+        let mut v: Vector<u32> = (0..10000).rev().collect();
+        assert_eq!(*v.select_nth_unstable(1234).1, 1234);
+    }
+
+    #[test]
+    fn test_select_nth_adversary() {
+        // McIlroy's adversary: values are fixed only when compared, always so that
+        // the pivot turns out as bad as possible. Selection must stay linear.
+        let len = 5000;
+        let gas = len;
+        let val = RefCell::new(vec![gas; len]);
+        let (solid, candidate, comparisons) = (Cell::new(0), Cell::new(0), Cell::new(0));
+        let freeze = |i: usize| {
+            val.borrow_mut()[i] = solid.get();
+            solid.set(solid.get() + 1);
+        };
+
+        let mut v: Vector<usize> = (0..len).collect();
+        let nth = *v.select_nth_unstable_by(len / 2, |&a, &b| {
+            comparisons.set(comparisons.get() + 1);
+            let (va, vb) = (val.borrow()[a], val.borrow()[b]);
+            if va == gas && vb == gas {
+                freeze(if a == candidate.get() { a } else { b });
+            }
+            let (va, vb) = (val.borrow()[a], val.borrow()[b]);
+            if va == gas {
+                candidate.set(a);
+            } else if vb == gas {
+                candidate.set(b);
+            }
+            va.cmp(&vb)
+        }).1;
+
+        let val = val.into_inner();
+        assert!(v.as_slice()[..len / 2].iter().all(|&i| val[i] <= val[nth]));
+        assert!(v.as_slice()[len / 2..].iter().all(|&i| val[i] >= val[nth]));
+        assert!(comparisons.get() < 30 * len, "{} comparisons", comparisons.get());
+    }
+
+    #[test]
+    fn test_select_nth_by_f64() {
+        // Percentiles over floats go through total_cmp
+        let mut v: Vector<f64> = random(1001, 10000, 3).iter().map(|&x| x as f64 / 10.0).collect();
+        let mut sorted: Vec<f64> = v.as_slice().to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let median = *v.select_nth_unstable_by(500, f64::total_cmp).1;
+        assert_eq!(median, sorted[500]);
+
+        let (_, last, _) = v.select_nth_unstable_by_key(1000, |x| x.to_bits());
+        assert_eq!(*last, sorted[1000]);
+    }
+
+    #[test]
+    fn test_select_nth_out_of_range() {
+        let result = std::panic::catch_unwind(|| {
+            let mut v = Vector::from([1, 2, 3]);
+            v.select_nth_unstable(3);
+        });
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_top_k() {
+        let p = random(2000, 500, 17);
+        let mut sorted = p.clone();
+        sorted.sort_by(|a, b| b.cmp(a));
+
+        let mut v = Vector::from(p.clone());
+        assert_eq!(v.top_k(10), &sorted[..10]);
+        assert_eq!(v.len(), 2000);
+
+        let mut v = Vector::from(p.clone());
+        assert_eq!(v.top_k(0), &[] as &[u64]);
+        assert_eq!(v.top_k(5000), sorted.as_slice());
+
+        // Smallest three by key
+        let mut v = Vector::from([5, -1, 3, -8, 2]);
+        assert_eq!(v.top_k_by_key(3, |x: &i32| -x), &[-8, -1, 2]);
+        assert_eq!(v.top_k_by(1, |a, b| a.cmp(b)), &[5]);
+    }
+
+    #[test]
+    fn test_partition_in_place() {
+        let p = random(1000, 100, 23);
+        let mut v = Vector::from(p.clone());
+        let mut calls = 0;
+        let n = v.partition_in_place(|x| {
+            calls += 1;
+            x % 2 == 0
+        });
+        assert_eq!(calls, 1000);
+        assert_eq!(n, p.iter().filter(|x| *x % 2 == 0).count());
+        assert!(v.as_slice()[..n].iter().all(|x| x % 2 == 0));
+        assert!(v.as_slice()[n..].iter().all(|x| x % 2 == 1));
+
+        let mut e: Vector<u8> = Vector::new();
+        assert_eq!(e.partition_in_place(|_| true), 0);
+    }
+
+    #[test]
+    fn test_partition_point() {
+        let v = Vector::from([1, 2, 2, 3, 5, 8]);
+        assert_eq!(v.partition_point(|x| *x < 2), 1);
+        assert_eq!(v.partition_point(|x| *x <= 2), 3);
+        assert_eq!(v.partition_point(|_| true), 6);
+        assert_eq!(v.partition_point(|_| false), 0);
+        assert_eq!(Vector::<u8>::new().partition_point(|_| true), 0);
+    }
+
+    #[test]
+    fn test_is_sorted() {
+        assert!(Vector::<u8>::new().is_sorted());
+        assert!(Vector::from([1, 2, 2, 9]).is_sorted());
+        assert!(!Vector::from([1, 3, 2]).is_sorted());
+        assert!(Vector::from([3, 2, 1]).is_sorted_by(|a, b| a >= b));
+        assert!(Vector::from(["a", "bb", "ccc"]).is_sorted_by_key(|s| s.len()));
+        assert!(!Vector::from([1.0, f64::NAN]).is_sorted());
+    }
+
+    #[test]
+    fn test_sort_heap() {
+        for len in [0, 1, 2, 50, 1000] {
+            let p = random(len, 64, len as u64 + 5);
+            let mut v = Vector::from(p.clone());
+            v.sort_heap();
+            let mut expected = p;
+            expected.sort();
+            assert_eq!(v, expected);
+        }
+
+        let mut v = Vector::from([1, 4, 2]);
+        v.sort_heap_by(|a, b| b.cmp(a));
+        assert_eq!(v, [4, 2, 1]);
+    }
+}
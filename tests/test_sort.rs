@@ -0,0 +1,183 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{Tracked, random};
+    use tesap_std::Vector;
+    use std::cell::Cell;
+    use std::panic::{self, AssertUnwindSafe};
+    use std::rc::Rc;
+
+    // Random, sorted, reversed, sawtooth and constant inputs of many lengths
+    fn patterns() -> Vec<Vec<u64>> {
+        let mut out = Vec::new();
+        for len in [0, 1, 2, 3, 7, 20, 21, 33, 64, 100, 500, 1000, 4096] {
+            out.push(random(len, u64::MAX, len as u64 + 1));
+            out.push(random(len, 4, len as u64 + 7));
+            out.push((0..len as u64).collect());
+            out.push((0..len as u64).rev().collect());
+            out.push((0..len as u64).map(|i| i % 17).collect());
+            out.push(vec![5; len]);
+        }
+        out
+    }
+
+    #[test]
+    fn test_sort_stable_matches_std() {
+        for p in patterns() {
+            let mut v = Vector::from(p.clone());
+            v.sort_stable();
+            let mut expected = p;
+            expected.sort();
+            assert_eq!(v, expected);
+        }
+    }
+
+    #[test]
+    fn test_sort_unstable_matches_std() {
+        for p in patterns() {
+            let mut v = Vector::from(p.clone());
+            v.sort_unstable();
+            let mut expected = p;
+            expected.sort_unstable();
+            assert_eq!(v, expected);
+        }
+    }
+
+    #[test]
+    fn test_sort_by_reverse() {
+        let p = random(1000, 100, 3);
+        let mut v = Vector::from(p.clone());
+        v.sort_unstable_by(|a, b| b.cmp(a));
+        let mut w = Vector::from(p.clone());
+        w.sort_stable_by(|a, b| b.cmp(a));
+        let mut expected = p;
+        expected.sort_by(|a, b| b.cmp(a));
+        assert_eq!(v, expected);
+        assert_eq!(w, expected);
+    }
+
+    #[test]
+    fn test_sort_stable_keeps_order_of_equal_keys() {
+        for len in [10, 100, 3000] {
+            let keys = random(len, 8, len as u64);
+            let mut v: Vector<(u64, usize)> = keys.iter().copied().zip(0..).collect();
+            v.sort_stable_by_key(|p| p.0);
+            for w in v.windows(2) {
+                assert!(w[0].0 < w[1].0 || (w[0].0 == w[1].0 && w[0].1 < w[1].1));
+            }
+        }
+    }
+
+    #[test]
+    fn test_sort_unstable_by_key() {
+        let mut v = Vector::from(["ccc", "a", "bb"]);
+        v.sort_unstable_by_key(|s| s.len());
+        assert_eq!(v, ["a", "bb", "ccc"]);
+    }
+
+    #[test]
+    fn test_sort_by_cached_key() {
+        let calls = Cell::new(0);
+        let p = random(500, 50, 11);
+        let mut v: Vector<(u64, usize)> = p.iter().copied().zip(0..).collect();
+        v.sort_by_cached_key(|x| {
+            calls.set(calls.get() + 1);
+            x.0.to_string()
+        });
+        assert_eq!(calls.get(), 500);
+
+        let mut expected: Vec<(u64, usize)> = p.iter().copied().zip(0..).collect();
+        expected.sort_by_key(|x| x.0.to_string());
+        assert_eq!(v, expected);
+    }
+
+    #[test]
+    fn test_radix_sort_unsigned() {
+        for p in patterns() {
+            let mut v = Vector::from(p.clone());
+            v.radix_sort();
+            let mut expected = p;
+            expected.sort();
+            assert_eq!(v, expected);
+        }
+
+        let mut v = Vector::from([3u8, 255, 0, 7]);
+        v.radix_sort();
+        assert_eq!(v, [0, 3, 7, 255]);
+    }
+
+    #[test]
+    fn test_radix_sort_signed() {
+        let p: Vec<i32> = random(1000, u64::MAX, 5).iter().map(|&x| x as i32).collect();
+        let mut v = Vector::from(p.clone());
+        v.radix_sort();
+        let mut expected = p;
+        expected.sort();
+        assert_eq!(v, expected);
+
+        let mut v = Vector::from([0i64, -1, i64::MIN, i64::MAX, 1, -100]);
+        v.radix_sort();
+        assert_eq!(v, [i64::MIN, -100, -1, 0, 1, i64::MAX]);
+    }
+
+    #[test]
+    fn test_sort_strings() {
+        let p: Vec<String> = random(300, 1000, 9).iter().map(|x| x.to_string()).collect();
+        let mut v = Vector::from(p.clone());
+        v.sort_stable();
+        let mut w = Vector::from(p.clone());
+        w.sort_unstable();
+        let mut expected = p;
+        expected.sort();
+        assert_eq!(v, expected);
+        assert_eq!(w, expected);
+    }
+
+    type Compare<'a> = &'a mut dyn FnMut(&Tracked, &Tracked) -> std::cmp::Ordering;
+
+    // A comparison panicking midway must leave every element in the Vector exactly once
+    fn check_panic_safety(sort: fn(&mut Vector<Tracked>, Compare)) {
+        for len in [15, 100, 1000] {
+            for panic_after in [1, len / 2, len * 3] {
+                let drops = Rc::new(Cell::new(0));
+                let mut v: Vector<Tracked> = random(len, 50, panic_after as u64)
+                    .into_iter()
+                    .map(|value| Tracked::with_value(&drops, value))
+                    .collect();
+
+                let mut count = 0;
+                let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                    sort(&mut v, &mut |a, b| {
+                        count += 1;
+                        if count == panic_after {
+                            panic!("comparison failed");
+                        }
+                        a.value.cmp(&b.value)
+                    });
+                }));
+                assert!(result.is_err());
+                assert_eq!(drops.get(), 0);
+
+                let mut values: Vec<u64> = v.iter().map(|t| t.value).collect();
+                values.sort();
+                let mut expected = random(len, 50, panic_after as u64);
+                expected.sort();
+                assert_eq!(values, expected);
+
+                drop(v);
+                assert_eq!(drops.get(), len);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sort_stable_panic_safety() {
+        check_panic_safety(|v, f| v.sort_stable_by(f));
+    }
+
+    #[test]
+    fn test_sort_unstable_panic_safety() {
+        check_panic_safety(|v, f| v.sort_unstable_by(f));
+    }
+}
@@ -0,0 +1,147 @@
+#[cfg(test)]
+mod tests {
+    use tesap_std::{SortedVector, Vector};
+    use std::ops::Bound;
+
+    fn set(values: &[i32]) -> SortedVector<i32> {
+        values.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_insert_keeps_order() {
+        let mut s = SortedVector::new();
+        for x in [5, 1, 9, 3, 7, 3, 1] {
+            s.insert(x);
+        }
+        assert_eq!(s.as_slice(), &[1, 3, 5, 7, 9]);
+        assert!(!s.insert(5));
+        assert!(s.insert(0));
+        assert_eq!(s.first(), Some(&0));
+        assert_eq!(s.len(), 6);
+    }
+
+    #[test]
+    fn test_contains_remove_rank() {
+        let mut s = set(&[10, 20, 30, 40]);
+        assert!(s.contains(&20));
+        assert!(!s.contains(&25));
+        assert_eq!(s.rank(&5), 0);
+        assert_eq!(s.rank(&30), 2);
+        assert_eq!(s.rank(&35), 3);
+        assert_eq!(s.rank(&100), 4);
+
+        assert!(s.remove(&20));
+        assert!(!s.remove(&20));
+        assert_eq!(s.take(&40), Some(40));
+        assert_eq!(s.as_slice(), &[10, 30]);
+        assert_eq!(s.pop_first(), Some(10));
+        assert_eq!(s.pop_last(), Some(30));
+        assert_eq!(s.pop_last(), None);
+        assert!(s.is_empty());
+    }
+
+    #[test]
+    fn test_borrowed_lookup() {
+        let s: SortedVector<String> = ["pear", "apple", "fig"].iter().map(|x| x.to_string()).collect();
+        assert!(s.contains("fig"));
+        assert_eq!(s.get("apple").map(String::as_str), Some("apple"));
+        let range: (Bound<&str>, Bound<&str>) = (Bound::Included("b"), Bound::Excluded("g"));
+        assert_eq!(s.range::<str, _>(range), &["fig".to_string()]);
+    }
+
+    #[test]
+    fn test_range() {
+        let s = set(&[1, 3, 5, 7, 9]);
+        assert_eq!(s.range(3..7), &[3, 5]);
+        assert_eq!(s.range(3..=7), &[3, 5, 7]);
+        assert_eq!(s.range(4..), &[5, 7, 9]);
+        assert_eq!(s.range(..=1), &[1]);
+        assert_eq!(s.range(..), &[1, 3, 5, 7, 9]);
+        assert_eq!(s.range(10..20), &[] as &[i32]);
+        #[allow(clippy::reversed_empty_ranges)]
+        let reversed = s.range(7..3);
+        assert_eq!(reversed, &[] as &[i32]);
+    }
+
+    #[test]
+    fn test_set_algebra() {
+        let a = set(&[1, 2, 3, 5, 8]);
+        let b = set(&[2, 3, 4, 8, 9, 10]);
+
+        assert_eq!(a.union(&b).copied().collect::<Vec<_>>(), [1, 2, 3, 4, 5, 8, 9, 10]);
+        assert_eq!(a.intersection(&b).copied().collect::<Vec<_>>(), [2, 3, 8]);
+        assert_eq!(a.difference(&b).copied().collect::<Vec<_>>(), [1, 5]);
+        assert_eq!(b.difference(&a).copied().collect::<Vec<_>>(), [4, 9, 10]);
+        assert_eq!(a.symmetric_difference(&b).copied().collect::<Vec<_>>(), [1, 4, 5, 9, 10]);
+
+        let empty = SortedVector::new();
+        assert_eq!(a.union(&empty).count(), 5);
+        assert_eq!(a.intersection(&empty).count(), 0);
+        assert_eq!(empty.difference(&a).count(), 0);
+
+        assert!(set(&[2, 8]).is_subset(&a));
+        assert!(!set(&[2, 4]).is_subset(&a));
+        assert!(set(&[4, 6]).is_disjoint(&a));
+    }
+
+    #[test]
+    fn test_set_algebra_matches_std() {
+        use std::collections::BTreeSet;
+
+        let xs: Vec<i32> = (0..200).map(|i| (i * 37) % 101).collect();
+        let ys: Vec<i32> = (0..150).map(|i| (i * 53) % 127).collect();
+        let (a, b): (SortedVector<i32>, SortedVector<i32>) = (xs.iter().copied().collect(), ys.iter().copied().collect());
+        let (sa, sb): (BTreeSet<i32>, BTreeSet<i32>) = (xs.into_iter().collect(), ys.into_iter().collect());
+
+        assert!(a.iter().eq(sa.iter()));
+        assert!(a.union(&b).eq(sa.union(&sb)));
+        assert!(a.intersection(&b).eq(sa.intersection(&sb)));
+        assert!(a.difference(&b).eq(sa.difference(&sb)));
+        assert!(a.symmetric_difference(&b).eq(sa.symmetric_difference(&sb)));
+    }
+
+    #[test]
+    fn test_extend_and_conversions() {
+        let mut s = set(&[4, 2]);
+        s.extend([3, 2, 1]);
+        assert_eq!(s.as_slice(), &[1, 2, 3, 4]);
+
+        let s = SortedVector::from(Vector::from([3, 1, 3, 2]));
+        assert_eq!(s, SortedVector::from([1, 2, 3]));
+        let v: Vector<i32> = s.into();
+        assert_eq!(v, [1, 2, 3]);
+
+        let s = SortedVector::from([2, 1]);
+        assert_eq!(format!("{:?}", s), "{1, 2}");
+        assert_eq!(s.into_iter().collect::<Vec<_>>(), [1, 2]);
+    }
+
+    #[test]
+    fn test_replace_keeps_single_copy() {
+        // Compared by key only
+        #[derive(Debug)]
+        struct Entry(u32, &'static str);
+        impl PartialEq for Entry {
+            fn eq(&self, other: &Self) -> bool { self.0 == other.0 }
+        }
+        impl Eq for Entry {}
+        impl PartialOrd for Entry {
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> { Some(self.cmp(other)) }
+        }
+        impl Ord for Entry {
+            fn cmp(&self, other: &Self) -> std::cmp::Ordering { self.0.cmp(&other.0) }
+        }
+
+        let mut s = SortedVector::new();
+        s.insert(Entry(1, "old"));
+        assert!(!s.insert(Entry(1, "ignored")));
+        assert_eq!(s[0].1, "old");
+        assert_eq!(s.replace(Entry(1, "new")).map(|e| e.1), Some("old"));
+        assert_eq!(s[0].1, "new");
+
+        // Extending keeps the elements already present
+        s.extend([Entry(1, "dup"), Entry(0, "zero")]);
+        assert_eq!(s.len(), 2);
+        assert_eq!(s[1].1, "new");
+    }
+}
@@ -0,0 +1,160 @@
+#[cfg(test)]
+mod tests {
+    use tesap_std::spsc;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+
+    const STRESS_COUNT: usize = 200_000;
+
+    #[test]
+    fn test_push_pop() {
+        let (mut tx, mut rx) = spsc::channel(3);
+        assert_eq!(tx.capacity(), 3);
+        assert!(rx.is_empty());
+        assert_eq!(rx.pop(), None);
+
+        assert_eq!(tx.push(1), Ok(()));
+        assert_eq!(tx.push(2), Ok(()));
+        assert_eq!(tx.push(3), Ok(()));
+        assert!(tx.is_full());
+        assert_eq!(tx.push(4), Err(4));
+        assert_eq!(rx.len(), 3);
+
+        assert_eq!(rx.peek(), Some(&1));
+        assert_eq!(rx.pop(), Some(1));
+        assert_eq!(tx.push(4), Ok(()));
+        assert_eq!(rx.pop(), Some(2));
+        assert_eq!(rx.pop(), Some(3));
+        assert_eq!(rx.pop(), Some(4));
+        assert_eq!(rx.pop(), None);
+
+        // Wrap around the buffer many times
+        for i in 0..100 {
+            assert_eq!(tx.push(i), Ok(()));
+            assert_eq!(tx.push(i + 1), Ok(()));
+            assert_eq!(rx.pop(), Some(i));
+            assert_eq!(rx.pop(), Some(i + 1));
+        }
+        assert!(tx.is_empty());
+    }
+
+    #[test]
+    fn test_slices() {
+        let (mut tx, mut rx) = spsc::channel::<u8>(5);
+        assert_eq!(tx.push_slice(&[1, 2, 3]), 3);
+        let mut buf = [0u8; 2];
+        assert_eq!(rx.pop_slice(&mut buf), 2);
+        assert_eq!(buf, [1, 2]);
+
+        // Wraps: slots 3, 4, then 0, 1
+        assert_eq!(tx.push_slice(&[4, 5, 6, 7, 8, 9]), 4);
+        assert!(tx.is_full());
+        assert_eq!(tx.push_slice(&[9]), 0);
+
+        let mut buf = [0u8; 8];
+        assert_eq!(rx.pop_slice(&mut buf), 5);
+        assert_eq!(buf[..5], [3, 4, 5, 6, 7]);
+        assert_eq!(rx.pop_slice(&mut buf), 0);
+        assert_eq!(tx.push_slice(&[]), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_zero_capacity() {
+        spsc::channel::<i32>(0);
+    }
+
+    #[test]
+    fn test_drops_unread() {
+        struct Tracked(Arc<AtomicUsize>);
+
+        impl Drop for Tracked {
+            fn drop(&mut self) {
+                self.0.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let drops = Arc::new(AtomicUsize::new(0));
+        let (mut tx, mut rx) = spsc::channel(4);
+        for _ in 0..6 {
+            let _ = tx.push(Tracked(drops.clone()));
+        }
+        // Two were rejected and dropped right away
+        assert_eq!(drops.load(Ordering::Relaxed), 2);
+        drop(rx.pop());
+        assert_eq!(drops.load(Ordering::Relaxed), 3);
+
+        drop(tx);
+        assert!(rx.is_abandoned());
+        assert_eq!(drops.load(Ordering::Relaxed), 3);
+        drop(rx);
+        assert_eq!(drops.load(Ordering::Relaxed), 6);
+    }
+
+    #[test]
+    fn test_stress_threads() {
+        let (mut tx, mut rx) = spsc::channel(64);
+
+        let producer = thread::spawn(move || {
+            for i in 0..STRESS_COUNT {
+                let mut value = Box::new(i);
+                while let Err(back) = tx.push(value) {
+                    value = back;
+                    thread::yield_now();
+                }
+            }
+        });
+
+        let mut expected = 0;
+        while expected < STRESS_COUNT {
+            match rx.pop() {
+                Some(value) => {
+                    assert_eq!(*value, expected);
+                    expected += 1;
+                }
+                None => thread::yield_now(),
+            }
+        }
+        producer.join().unwrap();
+        assert!(rx.is_abandoned());
+        assert_eq!(rx.pop(), None);
+    }
+
+    #[test]
+    fn test_stress_slices() {
+        let (mut tx, mut rx) = spsc::channel::<u8>(100);
+        let bytes: Vec<u8> = (0..STRESS_COUNT).map(|i| (i * 7 % 251) as u8).collect();
+        let expected = bytes.clone();
+
+        let producer = thread::spawn(move || {
+            let mut sent = 0;
+            // Uneven chunks so that copies straddle the end of the buffer
+            let mut chunk = 1;
+            while sent < bytes.len() {
+                let end = (sent + chunk).min(bytes.len());
+                let n = tx.push_slice(&bytes[sent..end]);
+                if n == 0 {
+                    thread::yield_now();
+                }
+                sent += n;
+                chunk = chunk % 37 + 1;
+            }
+        });
+
+        let mut received = Vec::with_capacity(STRESS_COUNT);
+        let mut buf = [0u8; 29];
+        loop {
+            let n = rx.pop_slice(&mut buf);
+            received.extend_from_slice(&buf[..n]);
+            if n == 0 {
+                if rx.is_abandoned() && rx.is_empty() {
+                    break;
+                }
+                thread::yield_now();
+            }
+        }
+        producer.join().unwrap();
+        assert_eq!(received, expected);
+    }
+}
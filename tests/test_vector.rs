@@ -1,7 +1,15 @@
+#![allow(clippy::bool_assert_comparison)]
+
+mod common;
 
 #[cfg(test)]
 mod tests {
-    use tesap_std::{Vector, ConsecConstrucor};
+    use crate::common::{CountingAlloc, Tracked};
+    use tesap_std::{AllocError, Global, Vector, ConsecConstrucor};
+    use tesap_std::{FixedIncrement, OneAndHalf, PageRounded};
+    use std::cmp::Ordering;
+    use std::cell::Cell;
+    use std::rc::Rc;
     use all_asserts::{assert_false};
     use assert_panic::assert_panic;
 
@@ -9,11 +17,11 @@ mod tests {
     fn test_pop_till_empty() {
         let mut v = Vector::new_copy(1, 3);
         assert_eq!(v.pop(), Some(1));
-        assert_eq!(v.len, 2);
+        assert_eq!(v.len(), 2);
         assert_eq!(v.pop(), Some(1));
-        assert_eq!(v.len, 1);
+        assert_eq!(v.len(), 1);
         assert_eq!(v.pop(), Some(1));
-        assert_eq!(v.len, 0);
+        assert_eq!(v.len(), 0);
         assert_eq!(v.pop(), None);
     }
 
@@ -30,7 +38,10 @@ mod tests {
     #[test]
     fn test_insert_out_of_bounds() {
         let mut v = Vector::new_copy(10, 5);
-        assert_false!(v.insert(v.len, 100));
+        assert_false!(v.insert(v.len() + 1, 100));
+        // Inserting at len appends
+        assert!(v.insert(v.len(), 100));
+        assert_eq!(v.as_slice(), &[10, 10, 10, 10, 10, 100]);
     }
 
     #[test]
@@ -38,14 +49,14 @@ mod tests {
         let mut v = Vector::new_copy(10, 5);
 
         assert_eq!(v.push(100), true);
-        assert_eq!(v.len, 6);
-        assert_eq!(v[v.len - 1], 100);
+        assert_eq!(v.len(), 6);
+        assert_eq!(v[v.len() - 1], 100);
         assert_eq!(v.push(101), true);
-        assert_eq!(v.len, 7);
-        assert_eq!(v[v.len - 1], 101);
+        assert_eq!(v.len(), 7);
+        assert_eq!(v[v.len() - 1], 101);
         assert_eq!(v.push(102), true);
-        assert_eq!(v.len, 8);
-        assert_eq!(v[v.len - 1], 102);
+        assert_eq!(v.len(), 8);
+        assert_eq!(v[v.len() - 1], 102);
     }
 
     #[test]
@@ -143,7 +154,7 @@ mod tests {
         // TODO What is [T] type? How can it be used without reference?
 
         // TODO Can C++ check for bounds in such way
-        assert_panic!({ (&*v)[100]; });
+        assert_panic!({ let _ = (&*v)[100]; });
     }
 
     #[test]
@@ -227,4 +238,709 @@ mod tests {
 
         assert_eq!(v.as_slice(), &[1, 2, 3, 4, 5]);
     }
+
+    // ===== drop
+    fn tracked_vector(drops: &Rc<Cell<usize>>, len: usize) -> Vector<Tracked> {
+        let mut v = Vector::new();
+        for _ in 0..len {
+            v.push(Tracked::new(drops));
+        }
+        v
+    }
+
+    #[test]
+    fn test_drop_elements() {
+        let drops = Rc::new(Cell::new(0));
+        let v = tracked_vector(&drops, 5);
+        assert_eq!(drops.get(), 0);
+        drop(v);
+        assert_eq!(drops.get(), 5);
+    }
+
+    #[test]
+    fn test_pop_moves() {
+        let drops = Rc::new(Cell::new(0));
+        let mut v = tracked_vector(&drops, 3);
+
+        let last = v.pop().unwrap();
+        assert_eq!(drops.get(), 0);
+        drop(last);
+        assert_eq!(drops.get(), 1);
+
+        drop(v);
+        assert_eq!(drops.get(), 3);
+    }
+
+    #[test]
+    fn test_truncate_clear() {
+        let drops = Rc::new(Cell::new(0));
+        let mut v = tracked_vector(&drops, 6);
+        let capacity = v.capacity();
+
+        v.truncate(10);
+        assert_eq!(drops.get(), 0);
+        v.truncate(4);
+        assert_eq!(v.len(), 4);
+        assert_eq!(drops.get(), 2);
+
+        v.clear();
+        assert_eq!(v.len(), 0);
+        assert_eq!(drops.get(), 6);
+        assert_eq!(v.capacity(), capacity);
+
+        drop(v);
+        assert_eq!(drops.get(), 6);
+    }
+
+    #[test]
+    fn test_new_clone_empty() {
+        let drops = Rc::new(Cell::new(0));
+        let v = Vector::new_clone(Tracked::new(&drops), 0);
+        assert_eq!(drops.get(), 1);
+        drop(v);
+        assert_eq!(drops.get(), 1);
+    }
+
+    #[test]
+    fn test_debug() {
+        let v = Vector::from_slice_clone(&["a".to_string(), "b".to_string()]);
+        assert_eq!(format!("{:?}", v), "[\"a\", \"b\"]");
+    }
+
+    // ===== no bounds
+    struct Handle(u32);
+
+    #[test]
+    fn test_non_clone_elements() {
+        let mut v: Vector<Handle> = Vector::new();
+        v.push(Handle(1));
+        v.push(Handle(3));
+        v.insert(1, Handle(2));
+        v[0] = Handle(0);
+        assert_eq!(v.iter().map(|h| h.0).collect::<Vec<u32>>(), vec![0, 2, 3]);
+        assert_eq!(v.pop().map(|h| h.0), Some(3));
+    }
+
+    #[test]
+    fn test_boxed_closures() {
+        let mut v: Vector<Box<dyn Fn(i32) -> i32>> = Vector::new();
+        v.push(Box::new(|x| x + 1));
+        v.push(Box::new(|x| x * 10));
+        assert_eq!(v[0](1), 2);
+        assert_eq!(v[1](2), 20);
+    }
+
+    #[test]
+    fn test_clone() {
+        let v = Vector::from_slice_clone(&["x".to_string(), "y".to_string()]);
+        let mut v2 = v.clone();
+        v2.push("z".to_string());
+        assert_eq!(v.as_slice(), &["x", "y"]);
+        assert_eq!(v2.as_slice(), &["x", "y", "z"]);
+    }
+
+    // ===== capacity
+    #[test]
+    fn test_new_does_not_allocate() {
+        let a = CountingAlloc::default();
+        let v: Vector<u32, &CountingAlloc> = Vector::new_in(&a);
+        assert_eq!(v.capacity(), 0);
+        assert_eq!(a.live.get(), 0);
+
+        let v: Vector<u32> = Vector::new_copy(5, 0);
+        assert_eq!(v.capacity(), 0);
+    }
+
+    #[test]
+    fn test_with_capacity() {
+        let a = CountingAlloc::default();
+        let mut v: Vector<u32, &CountingAlloc> = Vector::with_capacity_in(100, &a);
+        assert_eq!(v.capacity(), 100);
+        assert_eq!(v.len(), 0);
+        for i in 0..100 {
+            v.push(i);
+        }
+        assert_eq!(a.reallocs.get(), 0);
+        v.push(100);
+        assert_eq!(a.reallocs.get(), 1);
+
+        let v: Vector<String> = Vector::with_capacity(3);
+        assert_eq!(v.capacity(), 3);
+        assert!(v.is_empty());
+    }
+
+    #[test]
+    fn test_reserve() {
+        let mut v: Vector<u32> = Vector::new_copy(1, 10);
+        v.reserve(5);
+        assert!(v.capacity() >= 15);
+        // Amortized: jumps further than asked
+        assert_eq!(v.capacity(), 20);
+
+        let capacity = v.capacity();
+        v.reserve(1);
+        assert_eq!(v.capacity(), capacity);
+        assert_panic!({ Vector::<u32>::new_copy(1, 10).reserve(usize::MAX); });
+    }
+
+    #[test]
+    fn test_reserve_exact() {
+        let mut v: Vector<u32> = Vector::new_copy(1, 10);
+        v.reserve_exact(5);
+        assert_eq!(v.capacity(), 15);
+        v.reserve_exact(2);
+        assert_eq!(v.capacity(), 15);
+        assert_eq!(v.try_reserve_exact(usize::MAX), Err(AllocError::CapacityOverflow));
+        assert_eq!(v.as_slice(), &[1; 10]);
+    }
+
+    #[test]
+    fn test_shrink() {
+        let mut v: Vector<String> = Vector::with_capacity(100);
+        v.push("a".to_string());
+        v.push("b".to_string());
+
+        v.shrink_to(50);
+        assert_eq!(v.capacity(), 50);
+        v.shrink_to(60);
+        assert_eq!(v.capacity(), 50);
+        v.shrink_to(0);
+        assert_eq!(v.capacity(), 2);
+
+        v.clear();
+        v.shrink_to_fit();
+        assert_eq!(v.capacity(), 0);
+        v.push("c".to_string());
+        assert_eq!(v.as_slice(), &["c"]);
+    }
+
+    #[test]
+    fn test_spare_capacity_mut() {
+        let mut v: Vector<u32> = Vector::with_capacity(10);
+        v.push(0);
+
+        let spare = v.spare_capacity_mut();
+        assert_eq!(spare.len(), 9);
+        spare[0].write(1);
+        spare[1].write(2);
+        unsafe {
+            v.set_len(3);
+        }
+        assert_eq!(v.as_slice(), &[0, 1, 2]);
+    }
+
+    // ===== mutation
+    #[test]
+    fn test_remove() {
+        let mut v: Vector<usize> = Vector::consec(5);
+        assert_eq!(v.remove(0), 1);
+        assert_eq!(v.remove(3), 5);
+        assert_eq!(v.remove(1), 3);
+        assert_eq!(v.as_slice(), &[2, 4]);
+        assert_panic!({ Vector::<usize>::consec(2).remove(2); });
+    }
+
+    #[test]
+    fn test_swap_remove() {
+        let mut v: Vector<usize> = Vector::consec(5);
+        assert_eq!(v.swap_remove(1), 2);
+        assert_eq!(v.as_slice(), &[1, 5, 3, 4]);
+        assert_eq!(v.swap_remove(3), 4);
+        assert_eq!(v.as_slice(), &[1, 5, 3]);
+        assert_panic!({ Vector::<usize>::new().swap_remove(0); });
+    }
+
+    #[test]
+    fn test_resize() {
+        let mut v: Vector<String> = Vector::new();
+        v.resize(3, "a".to_string());
+        assert_eq!(v.as_slice(), &["a", "a", "a"]);
+        v.resize(1, "b".to_string());
+        assert_eq!(v.as_slice(), &["a"]);
+
+        let mut counter = 0;
+        v.resize_with(4, || {
+            counter += 1;
+            counter.to_string()
+        });
+        assert_eq!(v.as_slice(), &["a", "1", "2", "3"]);
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut v: Vector<usize> = Vector::consec(10);
+        v.retain(|x| x % 3 == 0);
+        assert_eq!(v.as_slice(), &[3, 6, 9]);
+
+        let mut v: Vector<usize> = Vector::consec(5);
+        v.retain_mut(|x| {
+            *x *= 10;
+            *x != 30
+        });
+        assert_eq!(v.as_slice(), &[10, 20, 40, 50]);
+    }
+
+    #[test]
+    fn test_retain_drops() {
+        let drops = Rc::new(Cell::new(0));
+        let mut v = tracked_vector(&drops, 6);
+        let mut i = 0;
+        v.retain(|_| {
+            i += 1;
+            i % 2 == 0
+        });
+        assert_eq!(v.len(), 3);
+        assert_eq!(drops.get(), 3);
+        drop(v);
+        assert_eq!(drops.get(), 6);
+    }
+
+    #[test]
+    fn test_retain_panic_safe() {
+        let drops = Rc::new(Cell::new(0));
+        let mut v = tracked_vector(&drops, 6);
+        let mut i = 0;
+        let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            v.retain(|_| {
+                i += 1;
+                if i == 4 {
+                    panic!("retain");
+                }
+                i % 2 == 0
+            });
+        }));
+        assert!(res.is_err());
+        // 1 and 3 removed; the panicking element and the rest are kept
+        assert_eq!(drops.get(), 2);
+        assert_eq!(v.len(), 4);
+        drop(v);
+        assert_eq!(drops.get(), 6);
+    }
+
+    #[test]
+    fn test_dedup() {
+        let mut v = Vector::from_slice_copy(&[1, 1, 2, 3, 3, 3, 1, 4, 4]);
+        v.dedup();
+        assert_eq!(v.as_slice(), &[1, 2, 3, 1, 4]);
+
+        let mut v = Vector::from_slice_copy(&[10, 11, 20, 21, 22, 30]);
+        v.dedup_by_key(|x| *x / 10);
+        assert_eq!(v.as_slice(), &[10, 20, 30]);
+
+        let mut v: Vector<String> = Vector::from_slice_clone(&["a".to_string(), "A".to_string(), "b".to_string()]);
+        v.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+        assert_eq!(v.as_slice(), &["a", "b"]);
+
+        let mut v: Vector<u8> = Vector::new();
+        v.dedup();
+        assert!(v.is_empty());
+    }
+
+    #[test]
+    fn test_append() {
+        let mut v1: Vector<String> = Vector::from_slice_clone(&["a".to_string()]);
+        let mut v2: Vector<String> = Vector::from_slice_clone(&["b".to_string(), "c".to_string()]);
+        v1.append(&mut v2);
+        assert_eq!(v1.as_slice(), &["a", "b", "c"]);
+        assert_eq!(v2.len(), 0);
+        v2.push("d".to_string());
+        assert_eq!(v2.as_slice(), &["d"]);
+    }
+
+    #[test]
+    fn test_split_off() {
+        let mut v: Vector<usize> = Vector::consec(5);
+        let tail = v.split_off(2);
+        assert_eq!(v.as_slice(), &[1, 2]);
+        assert_eq!(tail.as_slice(), &[3, 4, 5]);
+
+        let empty = v.split_off(2);
+        assert!(empty.is_empty());
+        assert_panic!({ Vector::<usize>::consec(2).split_off(3); });
+    }
+
+    #[test]
+    fn test_extend_from_slice() {
+        let mut v: Vector<String> = Vector::new();
+        v.extend_from_slice(&["x".to_string(), "y".to_string()]);
+        v.extend_from_slice(&[]);
+        assert_eq!(v.as_slice(), &["x", "y"]);
+    }
+
+    #[test]
+    fn test_extend_from_within() {
+        let mut v: Vector<usize> = Vector::consec(4);
+        v.extend_from_within(1..3);
+        assert_eq!(v.as_slice(), &[1, 2, 3, 4, 2, 3]);
+        v.extend_from_within(..=1);
+        assert_eq!(v.as_slice(), &[1, 2, 3, 4, 2, 3, 1, 2]);
+        v.extend_from_within(7..);
+        assert_eq!(v.as_slice(), &[1, 2, 3, 4, 2, 3, 1, 2, 2]);
+        assert_panic!({ Vector::<usize>::consec(4).extend_from_within(2..5); });
+    }
+
+    // ===== iterators
+    #[test]
+    fn test_into_iter() {
+        let v: Vector<String> = Vector::from_slice_clone(&["a".to_string(), "b".to_string(), "c".to_string()]);
+        let mut it = v.into_iter();
+        assert_eq!(it.len(), 3);
+        assert_eq!(it.next(), Some("a".to_string()));
+        assert_eq!(it.as_slice(), &["b", "c"]);
+        assert_eq!(it.next_back(), Some("c".to_string()));
+        assert_eq!(it.next(), Some("b".to_string()));
+        assert_eq!(it.next(), None);
+        assert_eq!(it.next_back(), None);
+
+        let mut joined = String::new();
+        for s in Vector::from_slice_clone(&["x".to_string(), "y".to_string()]) {
+            joined += &s;
+        }
+        assert_eq!(joined, "xy");
+    }
+
+    #[test]
+    fn test_into_iter_drops_rest() {
+        let drops = Rc::new(Cell::new(0));
+        let mut it = tracked_vector(&drops, 5).into_iter();
+        drop(it.next());
+        drop(it.next_back());
+        assert_eq!(drops.get(), 2);
+        drop(it);
+        assert_eq!(drops.get(), 5);
+    }
+
+    #[test]
+    fn test_drain() {
+        let mut v: Vector<usize> = Vector::consec(6);
+        let drained: Vec<usize> = v.drain(1..4).collect();
+        assert_eq!(drained, vec![2, 3, 4]);
+        assert_eq!(v.as_slice(), &[1, 5, 6]);
+
+        let mut d = v.drain(..);
+        assert_eq!(d.as_slice(), &[1, 5, 6]);
+        assert_eq!(d.next_back(), Some(6));
+        drop(d);
+        assert!(v.is_empty());
+
+        assert_panic!({ Vector::<usize>::consec(3).drain(2..4); });
+    }
+
+    #[test]
+    fn test_drain_partial() {
+        let drops = Rc::new(Cell::new(0));
+        let mut v = tracked_vector(&drops, 6);
+        {
+            let mut d = v.drain(2..5);
+            drop(d.next());
+            assert_eq!(drops.get(), 1);
+        }
+        assert_eq!(drops.get(), 3);
+        assert_eq!(v.len(), 3);
+        drop(v);
+        assert_eq!(drops.get(), 6);
+    }
+
+    #[test]
+    fn test_drain_forget() {
+        let mut v: Vector<usize> = Vector::consec(5);
+        std::mem::forget(v.drain(2..3));
+        // Leaks the range and the tail, never exposes moved-out slots
+        assert_eq!(v.as_slice(), &[1, 2]);
+    }
+
+    #[test]
+    fn test_splice() {
+        let mut v: Vector<usize> = Vector::consec(5);
+        let removed: Vec<usize> = v.splice(1..3, [20, 30]).collect();
+        assert_eq!(removed, vec![2, 3]);
+        assert_eq!(v.as_slice(), &[1, 20, 30, 4, 5]);
+
+        // More items than removed
+        v.splice(1..2, [7, 8, 9]);
+        assert_eq!(v.as_slice(), &[1, 7, 8, 9, 30, 4, 5]);
+
+        // Fewer items than removed
+        v.splice(1..5, [0]);
+        assert_eq!(v.as_slice(), &[1, 0, 4, 5]);
+
+        // Size hint lower bound is 0: items are collected before the tail moves
+        v.splice(2..2, (10..14).filter(|x| x % 2 == 0));
+        assert_eq!(v.as_slice(), &[1, 0, 10, 12, 4, 5]);
+
+        // Empty tail: plain extend
+        v.splice(4.., ["a".len(), "bb".len()]);
+        assert_eq!(v.as_slice(), &[1, 0, 10, 12, 1, 2]);
+    }
+
+    #[test]
+    fn test_splice_strings() {
+        let mut v: Vector<String> = Vector::from_slice_clone(&["a".to_string(), "b".to_string(), "c".to_string()]);
+        {
+            let mut s = v.splice(..2, ["x".to_string(), "y".to_string(), "z".to_string()]);
+            assert_eq!(s.next(), Some("a".to_string()));
+        }
+        assert_eq!(v.as_slice(), &["x", "y", "z", "c"]);
+    }
+
+    #[test]
+    fn test_extract_if() {
+        let mut v: Vector<usize> = Vector::consec(10);
+        let evens: Vec<usize> = v.extract_if(.., |x| *x % 2 == 0).collect();
+        assert_eq!(evens, vec![2, 4, 6, 8, 10]);
+        assert_eq!(v.as_slice(), &[1, 3, 5, 7, 9]);
+
+        let big: Vec<usize> = v.extract_if(1..4, |x| *x > 3).collect();
+        assert_eq!(big, vec![5, 7]);
+        assert_eq!(v.as_slice(), &[1, 3, 9]);
+    }
+
+    #[test]
+    fn test_extract_if_early_drop() {
+        let mut v: Vector<usize> = Vector::consec(6);
+        {
+            let mut it = v.extract_if(.., |x| *x % 2 == 1);
+            assert_eq!(it.next(), Some(1));
+            assert_eq!(it.next(), Some(3));
+        }
+        assert_eq!(v.as_slice(), &[2, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_extract_if_panic_safe() {
+        let drops = Rc::new(Cell::new(0));
+        let mut v = tracked_vector(&drops, 6);
+        let mut i = 0;
+        let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            for elem in v.extract_if(.., |_| {
+                i += 1;
+                if i == 4 {
+                    panic!("extract_if");
+                }
+                i % 2 == 1
+            }) {
+                drop(elem);
+            }
+        }));
+        assert!(res.is_err());
+        assert_eq!(drops.get(), 2);
+        assert_eq!(v.len(), 4);
+        drop(v);
+        assert_eq!(drops.get(), 6);
+    }
+
+    #[test]
+    fn test_push_amortized() {
+        let a = CountingAlloc::default();
+        let mut v: Vector<u64, &CountingAlloc> = Vector::new_in(&a);
+        for i in 0..10_000 {
+            v.push(i);
+        }
+        assert_eq!(v.len(), 10_000);
+        assert_eq!(v[9_999], 9_999);
+        // 0 -> 4 -> 8 -> ... -> 16384
+        assert!(a.reallocs.get() <= 13, "reallocs: {}", a.reallocs.get());
+    }
+
+    #[test]
+    fn test_insert_extend_amortized() {
+        let a = CountingAlloc::default();
+        let mut v: Vector<u64, &CountingAlloc> = Vector::new_in(&a);
+        v.push(0);
+        for i in 1..1000 {
+            assert!(v.insert(v.len() / 2, i));
+        }
+        v.extend(0..1000);
+        assert_eq!(v.len(), 2000);
+        assert!(a.reallocs.get() <= 11, "reallocs: {}", a.reallocs.get());
+    }
+
+    #[test]
+    fn test_growth_policies() {
+        let mut v: Vector<u32, Global, OneAndHalf> = Vector::new_in(Global);
+        v.extend(0..100);
+        assert_eq!(v.iter().sum::<u32>(), 4950);
+
+        let mut v: Vector<u32, Global, FixedIncrement<10>> = Vector::new_in(Global);
+        for i in 0..25 {
+            v.push(i);
+        }
+        assert_eq!(v.capacity(), 30);
+
+        let mut v: Vector<u32, Global, PageRounded> = Vector::new_in(Global);
+        v.push(1);
+        v.push(2);
+        assert_eq!(v.capacity(), 1024);
+
+        let v: Vector<u32, Global, OneAndHalf> = (0..10).collect();
+        assert_eq!(v.as_slice(), &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+    }
+
+    // ===== zero-sized
+    #[derive(Debug, PartialEq)]
+    struct Marker;
+
+    #[test]
+    fn test_zst() {
+        let mut v: Vector<()> = Vector::new();
+        for _ in 0..1000 {
+            v.push(());
+        }
+        assert_eq!(v.len(), 1000);
+        assert_eq!(v.pop(), Some(()));
+        assert_eq!(v.iter().count(), 999);
+
+        let mut m: Vector<Marker> = Vector::new();
+        m.push(Marker);
+        assert_eq!(m.pop(), Some(Marker));
+        assert_eq!(m.as_ptr() as usize % std::mem::align_of::<Marker>(), 0);
+    }
+
+    #[test]
+    fn test_from_empty_std_vec() {
+        let mut v: Vector<String> = Vector::from(Vec::new());
+        assert_eq!(v.capacity(), 0);
+        v.push("a".to_string());
+        v.push("b".to_string());
+        assert_eq!(v.as_slice(), &["a", "b"]);
+
+        let v2: Vec<u32> = Vector::from(Vec::<u32>::new()).into();
+        assert!(v2.is_empty());
+    }
+
+    #[test]
+    fn test_custom_allocator() {
+        let a = CountingAlloc::default();
+        {
+            let mut v: Vector<u32, &CountingAlloc> = Vector::new_in(&a);
+            for i in 0..20 {
+                v.push(i);
+            }
+            assert_eq!(v.len(), 20);
+            assert_eq!(v[19], 19);
+            assert_eq!(a.live.get(), 1);
+        }
+        assert_eq!(a.live.get(), 0);
+    }
+
+    #[test]
+    fn test_try_push_out_of_memory() {
+        // Grow one byte at a time to hit the budget exactly
+        let mut v: Vector<u8, CountingAlloc, FixedIncrement<1>> = Vector::new_in(CountingAlloc::with_budget(3));
+        assert_eq!(v.try_push(1), Ok(()));
+        assert_eq!(v.try_push(2), Ok(()));
+        assert_eq!(v.try_push(3), Ok(()));
+        assert!(matches!(v.try_push(4), Err(AllocError::OutOfMemory { .. })));
+        assert_eq!(v.as_slice(), &[1, 2, 3]);
+    }
+
+    #[test]
+    fn test_try_reserve() {
+        let mut v: Vector<u32> = Vector::new();
+        assert_eq!(v.try_reserve(100), Ok(()));
+        assert!(v.capacity() >= 100);
+        assert_eq!(v.try_reserve(usize::MAX), Err(AllocError::CapacityOverflow));
+        assert_eq!(v.try_reserve(usize::MAX / 2), Err(AllocError::CapacityOverflow));
+        v.push(1);
+        assert_eq!(v.as_slice(), &[1]);
+    }
+
+    #[test]
+    fn test_eq_and_ord() {
+        let a = Vector::from([1, 2, 3]);
+        let b: Vector<i32, Global, OneAndHalf> = [1, 2, 3].into_iter().collect();
+        assert_eq!(a, b);
+        assert_eq!(a, [1, 2, 3]);
+        assert_eq!(a, &[1, 2, 3][..]);
+        assert_eq!(a, vec![1, 2, 3]);
+        assert_ne!(a, Vector::from([1, 2]));
+        assert_eq!(a.cmp(&Vector::from([1, 2, 4])), Ordering::Less);
+        assert_eq!(a.cmp(&Vector::from([1, 2])), Ordering::Greater);
+        assert_eq!(a.partial_cmp(&a.clone()), Some(Ordering::Equal));
+    }
+
+    #[test]
+    fn test_hash_matches_slice() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        fn hash_of<H: Hash + ?Sized>(value: &H) -> u64 {
+            let mut h = DefaultHasher::new();
+            value.hash(&mut h);
+            h.finish()
+        }
+
+        let v = Vector::from([4u8, 5, 6]);
+        assert_eq!(hash_of(&v), hash_of(&[4u8, 5, 6][..]));
+
+        // Borrow<[T]> lets slices look up Vector keys
+        let mut set = std::collections::HashSet::new();
+        set.insert(v);
+        assert!(set.contains(&[4u8, 5, 6][..]));
+    }
+
+    #[test]
+    fn test_default_clone_extend() {
+        let mut v: Vector<String> = Vector::default();
+        assert_eq!(v.len(), 0);
+        v.extend(["a".to_string(), "b".to_string()]);
+        let c = v.clone();
+        assert_eq!(c, v);
+
+        let mut n = Vector::from([1, 2]);
+        n.extend(&[3, 4]);
+        assert_eq!(n, [1, 2, 3, 4]);
+
+        // Any default allocator and growth policy
+        let mut d: Vector<u8, CountingAlloc, FixedIncrement<1>> = Vector::default();
+        d.push(1);
+        assert_eq!((d.capacity(), d.allocator().allocs.get()), (1, 1));
+    }
+
+    #[test]
+    fn test_ref_into_iter() {
+        let mut v = Vector::from([1, 2, 3]);
+        for x in &mut v {
+            *x *= 2;
+        }
+        let mut sum = 0;
+        for x in &v {
+            sum += x;
+        }
+        assert_eq!(sum, 12);
+        assert_eq!(v.as_ref(), &[2, 4, 6]);
+    }
+
+    #[test]
+    fn test_from_slice_array_box() {
+        let s: &[String] = &["x".to_string(), "y".to_string()];
+        let v = Vector::from(s);
+        assert_eq!(v.as_slice(), s);
+
+        let drops = Rc::new(Cell::new(0));
+        let v = Vector::from([Tracked::new(&drops), Tracked::new(&drops)]);
+        assert_eq!(drops.get(), 0);
+        drop(v);
+        assert_eq!(drops.get(), 2);
+
+        let b: Box<[i32]> = Box::new([7, 8, 9]);
+        let v = Vector::from(b);
+        assert_eq!(v, [7, 8, 9]);
+        let b: Box<[i32]> = v.into();
+        assert_eq!(&*b, &[7, 8, 9]);
+    }
+
+    #[test]
+    fn test_try_into_array() {
+        let drops = Rc::new(Cell::new(0));
+        let v = tracked_vector(&drops, 3);
+        let arr: [Tracked; 3] = v.try_into().ok().unwrap();
+        assert_eq!(drops.get(), 0);
+        drop(arr);
+        assert_eq!(drops.get(), 3);
+
+        let v = Vector::from([1, 2, 3]);
+        let back: Result<[i32; 2], _> = v.try_into();
+        assert_eq!(back.unwrap_err(), [1, 2, 3]);
+    }
 }